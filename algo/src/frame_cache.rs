@@ -699,7 +699,20 @@ impl<K: Hash + Eq, V> FrameCache<K, V> {
   /// cache.clear();
   /// assert_eq!(cache.len(), 0);
   /// ```
-  pub fn clear(&mut self) { while self.pop_lru().is_some() {} }
+  pub fn clear(&mut self) {
+    // The entries may be placed on both sides of the tail sigil after a frame
+    // end, so release them from the map instead of popping the lru.
+    self.map.drain().for_each(|(_, node)| unsafe {
+      let mut node = *Box::from_raw(node.as_ptr());
+      ptr::drop_in_place(node.key.as_mut_ptr());
+      ptr::drop_in_place(node.val.as_mut_ptr());
+    });
+    unsafe {
+      (*self.head).next = self.tail;
+      (*self.tail).prev = self.head;
+      (*self.tail).next = ptr::null_mut();
+    }
+  }
 
   /// An iterator visiting all entries in most-recently used order. The iterator
   /// element type is `(&K, &V)`.
@@ -1394,6 +1407,23 @@ mod tests {
     assert_eq!(cache.len(), 0);
   }
 
+  #[test]
+  fn clear_after_frame_end() {
+    let mut cache = FrameCache::new();
+    cache.put("apple", "red");
+    cache.end_frame("test");
+    cache.put("banana", "yellow");
+
+    cache.clear();
+    assert_eq!(cache.len(), 0);
+    assert!(cache.get(&"apple").is_none());
+    assert!(cache.get(&"banana").is_none());
+
+    cache.put("apple", "green");
+    cache.end_frame("test");
+    assert_eq!(cache.len(), 1);
+  }
+
   #[test]
   fn test_send() {
     use std::thread;
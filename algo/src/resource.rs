@@ -33,6 +33,10 @@ impl<T: Sized> Resource<T> {
 impl<T: ?Sized> Resource<T> {
   #[inline]
   pub fn as_ptr(this: &Self) -> *const () { triomphe::Arc::as_ptr(&this.0) as *const () }
+
+  /// Return true if this is the only reference to the resource.
+  #[inline]
+  pub fn is_unique(this: &Self) -> bool { this.0.is_unique() }
 }
 
 impl<T> From<T> for Resource<T> {
@@ -0,0 +1,173 @@
+//! The accessibility layer, it exports the widget tree as a semantic tree for
+//! the assistive technologies such as the screen readers.
+//!
+//! A render widget describes itself by [`Render::semantics`], and a composed
+//! widget, such as a button, can describe itself by wrapping its content with
+//! [`Semantics`]. The widgets without semantics are not in the semantic tree,
+//! their semantic descendants are lifted to the nearest semantic ancestor.
+
+use crate::{prelude::*, widget_tree::WidgetTree, wrap_render::*};
+
+/// The role of a semantic node, it tells the assistive technologies what the
+/// widget is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum A11yRole {
+  /// The root node of a window.
+  Window,
+  Button,
+  Text,
+  Image,
+  TextInput,
+  Checkbox,
+  Slider,
+  Link,
+}
+
+/// The semantics a widget contributes to the semantic tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Semantics {
+  pub role: A11yRole,
+  /// The name of the widget, a widget without a label is named by the labels of
+  /// its descendants, such as a button named by its text.
+  pub label: Option<CowArc<str>>,
+  /// The current value of the widget, such as the text of an input.
+  pub value: Option<CowArc<str>>,
+}
+
+/// A node of the semantic tree exported by [`Window::a11y_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct A11yNode {
+  pub id: WidgetId,
+  pub role: A11yRole,
+  pub label: Option<CowArc<str>>,
+  pub value: Option<CowArc<str>>,
+  /// The bounds of the widget in the window coordinate.
+  pub bounds: Rect,
+  pub focusable: bool,
+  pub children: Vec<A11yNode>,
+}
+
+impl Semantics {
+  pub fn new(role: A11yRole) -> Self { Self { role, label: None, value: None } }
+
+  pub fn with_label(mut self, label: impl Into<CowArc<str>>) -> Self {
+    self.label = Some(label.into());
+    self
+  }
+
+  pub fn with_value(mut self, value: impl Into<CowArc<str>>) -> Self {
+    self.value = Some(value.into());
+    self
+  }
+}
+
+impl_compose_child_for_wrap_render!(Semantics, DirtyPhase::Paint);
+
+impl WrapRender for Semantics {
+  fn semantics(&self, host: &dyn Render) -> Option<Semantics> {
+    let mut semantics = self.clone();
+    if semantics.label.is_none() {
+      semantics.label = host.semantics().and_then(|s| s.label);
+    }
+    Some(semantics)
+  }
+}
+
+impl A11yNode {
+  /// Export the semantic tree of the `root` subtree, the `root` is always a
+  /// node of the `Window` role.
+  pub(crate) fn export(root: WidgetId, wnd: &Window) -> Self {
+    let tree = wnd.tree();
+    let mut node = A11yNode {
+      id: root,
+      role: A11yRole::Window,
+      label: None,
+      value: None,
+      bounds: tree.global_rect(root).unwrap_or_default(),
+      focusable: false,
+      children: vec![],
+    };
+    collect_children(root, wnd, tree, &mut node.children);
+    node
+  }
+
+  /// Iterate the node and all its descendants in the depth-first order.
+  pub fn iter(&self) -> Box<dyn Iterator<Item = &A11yNode> + '_> {
+    Box::new(std::iter::once(self).chain(self.children.iter().flat_map(|c| c.iter())))
+  }
+
+  fn content_label(&self) -> Option<CowArc<str>> {
+    let labels: Vec<_> = self
+      .children
+      .iter()
+      .flat_map(|c| c.iter())
+      .filter_map(|c| c.label.as_ref())
+      .map(|l| l.to_string())
+      .collect();
+    (!labels.is_empty()).then(|| labels.join(" ").into())
+  }
+}
+
+fn collect_children(parent: WidgetId, wnd: &Window, tree: &WidgetTree, out: &mut Vec<A11yNode>) {
+  for child in parent.children(tree) {
+    // The widgets not laid out are invisible.
+    let Some(bounds) = tree.global_rect(child) else { continue };
+    if let Some(Semantics { role, label, value }) = child.assert_get(tree).semantics() {
+      let focusable = wnd.focus_mgr.borrow().is_focusable(child);
+      let mut node =
+        A11yNode { id: child, role, label, value, bounds, focusable, children: vec![] };
+      collect_children(child, wnd, tree, &mut node.children);
+      if node.label.is_none() {
+        node.label = node.content_label();
+      }
+      out.push(node);
+    } else {
+      collect_children(child, wnd, tree, out);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn export_button_and_text() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          @ {
+            Semantics::new(A11yRole::Button).with_child(@MockBox {
+              size: Size::new(60., 20.),
+              tab_index: 0i16,
+              @Text { text: "OK" }
+            })
+          }
+          @Text { text: "Hello" }
+        }
+      },
+      Size::new(200., 200.),
+    );
+    wnd.draw_frame();
+
+    let root = wnd.a11y_tree();
+    assert_eq!(root.role, A11yRole::Window);
+    let [button, text] = &root.children[..] else { panic!("expect two nodes") };
+
+    assert_eq!(button.role, A11yRole::Button);
+    // The button is named by its text.
+    assert_eq!(button.label.as_deref(), Some("OK"));
+    assert!(button.focusable);
+    assert_eq!(button.bounds, Rect::new(Point::zero(), Size::new(60., 20.)));
+    assert_eq!(button.children.len(), 1);
+    assert_eq!(button.children[0].role, A11yRole::Text);
+
+    assert_eq!(text.role, A11yRole::Text);
+    assert_eq!(text.label.as_deref(), Some("Hello"));
+    assert!(!text.focusable);
+    assert_eq!(text.bounds.origin, Point::new(60., 0.));
+  }
+}
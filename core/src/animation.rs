@@ -14,6 +14,8 @@ mod stagger;
 pub use stagger::Stagger;
 mod keyframes;
 pub use keyframes::*;
+mod spring;
+pub use spring::*;
 
 ///  Trait to describe how to control the animation.
 pub trait Animation {
@@ -0,0 +1,201 @@
+use crate::{
+  prelude::*,
+  ticker::FrameMsg,
+  window::{WindowFlags, WindowId},
+};
+
+/// The physical parameters of a damped spring, the spring is underdamped and
+/// overshoots its goal if the `damping` is less than `2 * sqrt(stiffness *
+/// mass)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+  pub stiffness: f32,
+  pub damping: f32,
+  pub mass: f32,
+}
+
+impl Default for Spring {
+  fn default() -> Self { Self { stiffness: 170., damping: 26., mass: 1. } }
+}
+
+impl Spring {
+  /// Simulate the spring for `dt` seconds, return the new value and velocity.
+  pub fn step(&self, value: f32, velocity: f32, goal: f32, dt: f32) -> (f32, f32) {
+    // Split a long frame into small steps to keep the simulation stable.
+    const MAX_STEP: f32 = 1. / 240.;
+    let (mut value, mut velocity, mut rest) = (value, velocity, dt);
+    while rest > 0. {
+      let dt = rest.min(MAX_STEP);
+      let force = -self.stiffness * (value - goal) - self.damping * velocity;
+      velocity += force / self.mass * dt;
+      value += velocity * dt;
+      rest -= dt;
+    }
+    (value, velocity)
+  }
+}
+
+/// An animation that drives the `state` toward the `goal` by a [`Spring`] at
+/// every frame of the window, until both the distance to the goal and the
+/// velocity are less than the `rest_threshold`.
+///
+/// Unlike the [`Animate`], it has no duration, and keeps its velocity if the
+/// `goal` is changed while running, so it's suitable for the interruptible
+/// motions such as a scroll fling or a bouncy transition.
+#[simple_declare]
+pub struct SpringAnimate {
+  #[declare(default)]
+  pub spring: Spring,
+  #[declare(strict)]
+  pub state: Stateful<f32>,
+  pub goal: f32,
+  #[declare(default = 0.001)]
+  pub rest_threshold: f32,
+  #[declare(skip)]
+  running_info: Option<SpringInfo>,
+  #[declare(skip, default = BuildCtx::get().window().id())]
+  window_id: WindowId,
+}
+
+struct SpringInfo {
+  velocity: f32,
+  last_at: Option<Instant>,
+  _tick_msg_guard: Box<dyn Any>,
+}
+
+impl Animation for State<SpringAnimate> {
+  fn run(&self) {
+    let this = self.read();
+    let wnd_id = this.window_id;
+    let Some(wnd) = AppCtx::get_window(wnd_id) else { return };
+    if this.is_running() {
+      return;
+    }
+    if !wnd.flags().contains(WindowFlags::ANIMATIONS) {
+      *this.state.write() = this.goal;
+      return;
+    }
+    drop(this);
+
+    let animate = self.clone_writer();
+    let tick_handle = wnd
+      .frame_ticker
+      .clone()
+      .subscribe(move |msg| {
+        if let FrameMsg::BeforeLayout(time) = msg {
+          let at_rest = animate.shallow().advance_to(time);
+          if at_rest {
+            let wnd = AppCtx::get_window(wnd_id).unwrap();
+            let animate = animate.clone_writer();
+            wnd
+              .frame_spawn(async move { animate.stop() })
+              .unwrap();
+          }
+        }
+      })
+      .unsubscribe_when_dropped();
+
+    self.silent().running_info =
+      Some(SpringInfo { velocity: 0., last_at: None, _tick_msg_guard: Box::new(tick_handle) });
+    wnd.inc_running_animate();
+  }
+
+  fn is_running(&self) -> bool { self.read().is_running() }
+
+  fn stop(&self) {
+    let mut this = self.silent();
+    if this.is_running() {
+      if let Some(wnd) = AppCtx::get_window(this.window_id) {
+        wnd.dec_running_animate();
+        this.running_info.take();
+      }
+    }
+  }
+
+  fn box_clone(&self) -> Box<dyn Animation> { Box::new(self.clone_writer()) }
+}
+
+impl SpringAnimate {
+  pub fn is_running(&self) -> bool { self.running_info.is_some() }
+
+  /// Simulate the spring to the given time, return if the spring is at rest.
+  ///
+  /// ## Panics
+  ///
+  /// Panics if the animation is not running.
+  fn advance_to(&mut self, at: Instant) -> bool {
+    let SpringInfo { velocity, last_at, .. } = self
+      .running_info
+      .as_mut()
+      .expect("This animation is not running.");
+
+    let dt = last_at.map_or(0., |last| at.saturating_duration_since(last).as_secs_f32());
+    *last_at = Some(at);
+
+    let value = *self.state.read();
+    let (value, v) = self.spring.step(value, *velocity, self.goal, dt);
+    let at_rest = (value - self.goal).abs() < self.rest_threshold && v.abs() < self.rest_threshold;
+    if at_rest {
+      *velocity = 0.;
+      *self.state.write() = self.goal;
+    } else {
+      *velocity = v;
+      *self.state.write() = value;
+    }
+    at_rest
+  }
+}
+
+impl Drop for SpringAnimate {
+  fn drop(&mut self) {
+    if self.running_info.is_some() {
+      if let Some(wnd) = AppCtx::get_window(self.window_id) {
+        wnd.dec_running_animate();
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn underdamped_spring_settle() {
+    reset_test_env!();
+
+    let value = Stateful::new(0f32);
+    let c_value = value.clone_writer();
+    let w = fn_widget! {
+      let animate = @SpringAnimate {
+        spring: Spring { stiffness: 100., damping: 5., mass: 1. },
+        state: c_value.clone_writer(),
+        goal: 1.,
+      };
+      animate.run();
+      @Void {}
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    // Step a mock clock of 60 fps until the spring stops requesting frames.
+    let mut ticker = wnd.frame_tick_stream();
+    let start = Instant::now();
+    let mut max = 0f32;
+    let mut frames = 0;
+    while wnd.need_draw() && frames < 600 {
+      frames += 1;
+      ticker.next(FrameMsg::BeforeLayout(start + Duration::from_millis(16 * frames)));
+      wnd.run_frame_tasks();
+      max = max.max(*value.read());
+    }
+
+    // Overshoot the goal, then settle to it.
+    assert!(max > 1.2);
+    assert_eq!(*value.read(), 1.);
+    // It takes a while to settle, and no more frames are requested at rest.
+    assert!(frames > 60 && frames < 600);
+    assert!(!wnd.need_draw());
+  }
+}
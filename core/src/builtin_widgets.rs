@@ -12,6 +12,7 @@ use std::ops::DerefMut;
 pub use key::{Key, KeyWidget};
 pub use painting_style::*;
 pub mod image_widget;
+pub use image_widget::*;
 pub mod keep_alive;
 pub use keep_alive::*;
 mod theme;
@@ -34,12 +35,20 @@ mod visibility;
 pub use visibility::*;
 mod ignore_pointer;
 pub use ignore_pointer::*;
+mod hit_test_behavior;
+pub use hit_test_behavior::*;
+mod z_index;
+pub use z_index::*;
 mod void;
 pub use void::Void;
 mod unconstrained_box;
 pub use unconstrained_box::*;
 mod opacity;
 pub use opacity::*;
+mod pixel_snap;
+pub use pixel_snap::*;
+mod custom_paint;
+pub use custom_paint::*;
 mod anchor;
 pub use anchor::*;
 mod layout_box;
@@ -71,6 +80,8 @@ mod constrained_box;
 pub use constrained_box::*;
 mod text_style;
 pub use text_style::*;
+mod directionality;
+pub use directionality::*;
 mod smooth_layout;
 pub use smooth_layout::*;
 
@@ -559,7 +570,8 @@ impl<T> FatObj<T> {
   }
 
   /// Attaches a handler to the widget that is triggered when a pointer event
-  /// cancels.
+  /// cancels, such as the tap of the pressed widget is canceled because the
+  /// pointer sequence is recognized as a drag.
   pub fn on_pointer_cancel(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
     on_mixin!(self, on_pointer_cancel, f)
   }
@@ -644,6 +656,47 @@ impl<T> FatObj<T> {
     self
   }
 
+  /// Attaches a handler to the widget that is triggered when a drag gesture
+  /// begins, that is the pointer pressed on the widget and moved farther than
+  /// the drag threshold.
+  pub fn on_drag_start(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_start, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a drag start event. An ancestor can stop the propagation of the
+  /// event to claim the drag before its descendants, such as a scroll
+  /// container.
+  pub fn on_drag_start_capture(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_start_capture, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the pointer moves
+  /// during a drag gesture began on the widget.
+  pub fn on_drag_update(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_update, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a drag update event. This is similar to `on_drag_update`, but
+  /// it's triggered earlier in the event flow.
+  pub fn on_drag_update_capture(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_update_capture, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the pointer is
+  /// released after a drag gesture began on the widget.
+  pub fn on_drag_end(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_end, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a drag end event. This is similar to `on_drag_end`, but it's
+  /// triggered earlier in the event flow.
+  pub fn on_drag_end_capture(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_end_capture, f)
+  }
+
   /// Attaches a handler to the widget that is triggered when the user rotates a
   /// wheel button on a pointing device (typically a mouse).
   pub fn on_wheel(mut self, f: impl FnMut(&mut WheelEvent) + 'static) -> Self {
@@ -846,7 +899,9 @@ impl<T> FatObj<T> {
 
   /// Initializes the text line height of this widget.
   pub fn text_line_height<const M: usize>(self, v: impl DeclareInto<f32, M>) -> Self {
-    self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| m.text_style.line_height = v)
+    self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| {
+      m.text_style.line_height = v.into()
+    })
   }
 
   /// Initializes the text overflow of this widget.
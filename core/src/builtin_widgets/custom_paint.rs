@@ -0,0 +1,57 @@
+use crate::prelude::*;
+
+/// Widget with a fixed size that is drawn by a custom callback of the painter
+/// backend, such as a 3D scene or a shader effect drawn by the raw GPU
+/// commands. The content is composited in the paint order with the other
+/// widgets and scissored to the visible part of the widget.
+///
+/// The `draw` is a backend specific callback, for example, the wgpu backend
+/// draws a `WgpuCustomDraw`. See [`Painter::draw_custom`].
+#[derive(Declare)]
+pub struct CustomPaint {
+  pub size: Size,
+  pub draw: CustomDraw,
+}
+
+impl Render for CustomPaint {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size { clamp.clamp(self.size) }
+
+  #[inline]
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let rect = Rect::from_size(ctx.box_size().unwrap());
+    ctx
+      .painter()
+      .draw_custom(&rect, self.draw.clone());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn custom_paint_rect() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          @MockBox { size: Size::new(10., 10.) }
+          @CustomPaint { size: Size::new(20., 30.), draw: CustomDraw::new(()) }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let rect = frame.commands.iter().find_map(|cmd| match cmd {
+      PaintCommand::Custom(CustomCommand { rect, .. }) => Some(*rect),
+      _ => None,
+    });
+    assert_eq!(rect, Some(Rect::new(Point::new(10., 0.), Size::new(20., 30.))));
+  }
+}
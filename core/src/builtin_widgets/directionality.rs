@@ -0,0 +1,39 @@
+use crate::prelude::*;
+
+/// The horizontal direction of the UI. The layout widgets mirror their
+/// horizontal placement in the right-to-left direction, for example, a `Row`
+/// places its first child on the right, and the start padding is on the right
+/// side.
+///
+/// It's inherited by the descendants through the [`Provider`], and the
+/// left-to-right direction is used if no one provides it.
+///
+/// ```
+/// use ribir::prelude::*;
+///
+/// let _rtl = providers! {
+///   providers: [Provider::new(Directionality::Rtl)],
+///   @Row {
+///     @Text { text: "I'm on the right" }
+///     @Text { text: "I'm on the left" }
+///   }
+/// };
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Directionality {
+  /// Left to right.
+  #[default]
+  Ltr,
+  /// Right to left.
+  Rtl,
+}
+
+impl Directionality {
+  /// Return the directionality provided in the `ctx`, or the left-to-right
+  /// direction if no one provides it.
+  pub fn of(ctx: &impl AsRef<ProviderCtx>) -> Self {
+    Provider::of::<Self>(ctx).map_or(Self::Ltr, |d| *d)
+  }
+
+  pub fn is_rtl(&self) -> bool { matches!(self, Directionality::Rtl) }
+}
@@ -252,6 +252,22 @@ impl GlobalAnchorY {
     }))
   }
 
+  /// Init the global vertical anchor, which will anchor the widget's
+  /// vertical position by placing its top edge down to the bottom edge of the
+  /// specified widget (`target`) with the given relative pixel value
+  pub fn below(track_id: TrackId, offset: f32) -> Self {
+    Self::Once(Box::new(move |host, wnd: &Sc<Window>| {
+      let host_id = host.get().unwrap();
+      let target = track_id.get().unwrap();
+      if host_id.is_dropped(wnd.tree()) || target.is_dropped(wnd.tree()) {
+        return Err(());
+      }
+      let y = wnd.map_to_global(Point::zero(), target).y;
+      let target_size = wnd.widget_size(target).unwrap_or_default();
+      Ok(y + target_size.height + offset)
+    }))
+  }
+
   /// convert the once anchor to the always follow anchor
   pub fn always_follow(self) -> Self {
     match self {
@@ -0,0 +1,86 @@
+use crate::{prelude::*, wrap_render::*};
+
+/// Widget use to expand the hit region of its host beyond its visual bounds by
+/// the `hit_margin`, so a small control is easier to tap on the touch devices.
+///
+/// The expanded region is tested in the same order as the visual bounds, so if
+/// the expanded regions of two widgets are overlapped, the topmost one is hit.
+///
+/// The start and end insets of the `hit_margin` are taken as the left and
+/// right insets.
+#[derive(Declare, Clone)]
+pub struct HitTestBehavior {
+  #[declare(default)]
+  pub hit_margin: EdgeInsets,
+}
+
+impl_compose_child_for_wrap_render!(HitTestBehavior, DirtyPhase::Paint);
+
+impl WrapRender for HitTestBehavior {
+  fn hit_test(&self, host: &dyn Render, ctx: &mut HitTestCtx, pos: Point) -> HitTest {
+    let mut hit = host.hit_test(ctx, pos);
+    if !hit.hit {
+      let EdgeInsets { left, right, bottom, top, .. } =
+        self.hit_margin.resolve(Directionality::Ltr);
+      hit.hit = ctx.box_rect().is_some_and(|rect| {
+        let origin = rect.origin - Vector::new(left, top);
+        let size = rect.size + Size::new(left + right, top + bottom);
+        Rect::new(origin, size).contains(pos)
+      });
+    }
+    hit
+  }
+
+  fn hit_bounds(&self, host: &dyn Render, size: Size) -> Option<Rect> {
+    let EdgeInsets { left, right, bottom, top, .. } = self.hit_margin.resolve(Directionality::Ltr);
+    let bounds = host.hit_bounds(size)?;
+    let margin = Rect::new(Point::new(-left, -top), size + Size::new(left + right, top + bottom));
+    Some(bounds.union(&margin))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn tap_in_hit_margin() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(50., 50.) }
+        @HitTestBehavior {
+          hit_margin: EdgeInsets::all(8.),
+          @MockBox {
+            size: Size::new(20., 20.),
+            on_tap: move |_| *$w_taps.write() += 1,
+          }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let tap_at = |x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 10.).into() });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    // The button is at `50..70` of the x-axis, a tap 5 pixels outside hits it.
+    tap_at(75.);
+    assert_eq!(*taps.read(), 1);
+
+    // Beyond the margin.
+    tap_at(80.);
+    assert_eq!(*taps.read(), 1);
+  }
+}
@@ -16,3 +16,136 @@ impl Render for Resource<PixelImage> {
     }
   }
 }
+
+/// How an image is scaled to its box when their sizes are different. The image
+/// is always centered in the box.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFit {
+  /// The image is stretched to fill the box, the aspect ratio is not kept.
+  Fill,
+  /// The image is scaled to fit in the box with its aspect ratio kept, the box
+  /// is letterboxed if their aspect ratios are different.
+  Contain,
+  /// The image is scaled to cover the box with its aspect ratio kept, the
+  /// image is cropped if their aspect ratios are different.
+  Cover,
+  /// The image is not scaled, and cropped if it's larger than the box.
+  #[default]
+  None,
+  /// Same as `Contain` if the image is larger than the box, otherwise same as
+  /// `None`.
+  ScaleDown,
+}
+
+/// A widget to display an image, the image is scaled to its box by the `fit`.
+#[derive(Declare)]
+pub struct Image {
+  pub img: Resource<PixelImage>,
+  #[declare(default)]
+  pub fit: ImageFit,
+}
+
+impl ImageFit {
+  /// Return the rect of the image to draw and the rect of the box to draw it
+  /// to. Nothing is returned if no part of the image is visible.
+  pub fn fit_rects(self, img_size: Size, box_size: Size) -> Option<(Rect, Rect)> {
+    if img_size.is_empty() || box_size.is_empty() {
+      return None;
+    }
+    let x = box_size.width / img_size.width;
+    let y = box_size.height / img_size.height;
+    let scale = match self {
+      ImageFit::Fill => Vector::new(x, y),
+      ImageFit::Contain => Vector::splat(x.min(y)),
+      ImageFit::Cover => Vector::splat(x.max(y)),
+      ImageFit::None => Vector::splat(1.),
+      ImageFit::ScaleDown => Vector::splat(x.min(y).min(1.)),
+    };
+
+    let scaled = Size::new(img_size.width * scale.x, img_size.height * scale.y);
+    let origin = ((box_size - scaled) / 2.).to_vector().to_point();
+    let img_rect = Rect::new(origin, scaled);
+    let dst = img_rect.intersection(&Rect::from_size(box_size))?;
+    let src_origin = (dst.origin - img_rect.origin).component_div(scale);
+    let src_size = Size::new(dst.width() / scale.x, dst.height() / scale.y);
+    Some((Rect::new(src_origin.to_point(), src_size), dst))
+  }
+}
+
+impl Render for Image {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+    let size = Size::new(self.img.width() as f32, self.img.height() as f32);
+    clamp.clamp(size)
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let box_size = ctx.box_size().unwrap();
+    let img_size = Size::new(self.img.width() as f32, self.img.height() as f32);
+    if let Some((src, dst)) = self.fit.fit_rects(img_size, box_size) {
+      ctx
+        .painter()
+        .draw_img(self.img.clone(), &dst, &Some(src));
+    }
+  }
+
+  fn semantics(&self) -> Option<Semantics> { Some(Semantics::new(A11yRole::Image)) }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::borrow::Cow;
+
+  use ribir_painter::image::ColorFormat;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn image(width: u32, height: u32) -> Resource<PixelImage> {
+    let data = vec![0; (width * height * 4) as usize];
+    Resource::new(PixelImage::new(Cow::Owned(data), width, height, ColorFormat::Rgba8))
+  }
+
+  #[test]
+  fn contain_letterbox() {
+    reset_test_env!();
+
+    let img = image(600, 300);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! { @Image { img: img.clone(), fit: ImageFit::Contain } },
+      Size::new(300., 300.),
+    );
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(300., 300.));
+
+    let fit = ImageFit::Contain.fit_rects(Size::new(600., 300.), Size::new(300., 300.));
+    assert_eq!(
+      fit,
+      Some((
+        Rect::new(Point::zero(), Size::new(600., 300.)),
+        Rect::new(Point::new(0., 75.), Size::new(300., 150.))
+      ))
+    );
+  }
+
+  #[test]
+  fn cover_crop() {
+    let fit = ImageFit::Cover.fit_rects(Size::new(600., 300.), Size::new(300., 300.));
+    assert_eq!(
+      fit,
+      Some((
+        Rect::new(Point::new(150., 0.), Size::new(300., 300.)),
+        Rect::new(Point::zero(), Size::new(300., 300.))
+      ))
+    );
+
+    // Not scaled, only the center part is visible.
+    let fit = ImageFit::None.fit_rects(Size::new(600., 300.), Size::new(300., 300.));
+    assert_eq!(
+      fit,
+      Some((
+        Rect::new(Point::new(150., 0.), Size::new(300., 300.)),
+        Rect::new(Point::zero(), Size::new(300., 300.))
+      ))
+    );
+  }
+}
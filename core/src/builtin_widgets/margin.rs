@@ -2,10 +2,18 @@ use crate::prelude::*;
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Lerp)]
 pub struct EdgeInsets {
+  /// The inset on the left edge, it's not mirrored by the [`Directionality`].
   pub left: f32,
+  /// The inset on the right edge, it's not mirrored by the [`Directionality`].
   pub right: f32,
   pub bottom: f32,
   pub top: f32,
+  /// The inset on the start edge, that is the left edge in the left-to-right
+  /// direction and the right edge in the right-to-left direction.
+  pub start: f32,
+  /// The inset on the end edge, that is the right edge in the left-to-right
+  /// direction and the left edge in the right-to-left direction.
+  pub end: f32,
 }
 
 /// The widget utilizes empty space to surround the child widget.
@@ -51,7 +59,8 @@ impl Render for Margin {
     let child_clamp = BoxClamp { min, max };
     let size = ctx.perform_child_layout(child, child_clamp);
     let pos = ctx.position(child).unwrap();
-    let pos = pos + Vector::new(self.margin.left, self.margin.top);
+    let margin = self.margin.resolve(Directionality::of(ctx));
+    let pos = pos + Vector::new(margin.left, margin.top);
     ctx.update_position(child, pos);
 
     size + thickness
@@ -64,14 +73,14 @@ impl Margin {
 }
 
 impl EdgeInsets {
-  pub const ZERO: Self = Self { top: 0., right: 0., bottom: 0., left: 0. };
+  pub const ZERO: Self = Self { top: 0., right: 0., bottom: 0., left: 0., start: 0., end: 0. };
 
   #[inline]
   pub const fn all(value: f32) -> Self { Self::new(value, value, value, value) }
 
   #[inline]
   pub const fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
-    Self { top, right, bottom, left }
+    Self { top, right, bottom, left, start: 0., end: 0. }
   }
 
   #[inline]
@@ -85,9 +94,17 @@ impl EdgeInsets {
   #[inline]
   pub const fn only_top(top: f32) -> Self { Self { top, ..Self::ZERO } }
 
+  /// The inset only on the start edge, see [`EdgeInsets::start`].
+  #[inline]
+  pub const fn only_start(start: f32) -> Self { Self { start, ..Self::ZERO } }
+
+  /// The inset only on the end edge, see [`EdgeInsets::end`].
+  #[inline]
+  pub const fn only_end(end: f32) -> Self { Self { end, ..Self::ZERO } }
+
   #[inline]
   pub const fn symmetrical(vertical: f32, horizontal: f32) -> Self {
-    Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal }
+    Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal, ..Self::ZERO }
   }
 
   #[inline]
@@ -101,10 +118,28 @@ impl EdgeInsets {
   }
 
   pub const fn thickness(&self) -> Size {
-    Size::new(self.right + self.left, self.bottom + self.top)
+    Size::new(self.right + self.left + self.start + self.end, self.bottom + self.top)
   }
 
-  /// Convert to an array by the top, right, bottom, left order.
+  /// Resolve the insets to the physical edges in the `dir`, the start and end
+  /// insets are added to the left and right insets by the direction.
+  pub fn resolve(self, dir: Directionality) -> Self {
+    let Self { mut left, mut right, start, end, .. } = self;
+    match dir {
+      Directionality::Ltr => {
+        left += start;
+        right += end;
+      }
+      Directionality::Rtl => {
+        left += end;
+        right += start;
+      }
+    }
+    Self { left, right, start: 0., end: 0., ..self }
+  }
+
+  /// Convert to an array by the top, right, bottom, left order, the start and
+  /// end insets are not included, [`EdgeInsets::resolve`] them first.
   #[inline]
   pub const fn to_array(&self) -> [f32; 4] { [self.top, self.right, self.bottom, self.left] }
 }
@@ -125,6 +160,8 @@ impl std::ops::AddAssign for EdgeInsets {
     self.right += rhs.right;
     self.bottom += rhs.bottom;
     self.top += rhs.top;
+    self.start += rhs.start;
+    self.end += rhs.end;
   }
 }
 
@@ -147,4 +184,36 @@ mod tests {
     LayoutCase::default().with_size(Size::new(102., 102.)),
     LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(1., 1.0, 100., 100.))
   );
+
+  widget_layout_test!(
+    rtl_start_margin,
+    WidgetTester::new(fn_widget! {
+      @MockMulti {
+        providers: [Provider::new(Directionality::Rtl)],
+        @MockBox {
+          margin: EdgeInsets::only_start(10.),
+          size: Size::new(100., 100.)
+        }
+      }
+    })
+    .with_wnd_size(Size::new(200., 200.)),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(110., 100.)),
+    LayoutCase::new(&[0, 0, 0]).with_rect(ribir_geom::rect(0., 0., 100., 100.))
+  );
+
+  widget_layout_test!(
+    rtl_left_margin,
+    WidgetTester::new(fn_widget! {
+      @MockMulti {
+        providers: [Provider::new(Directionality::Rtl)],
+        @MockBox {
+          margin: EdgeInsets::only_left(10.),
+          size: Size::new(100., 100.)
+        }
+      }
+    })
+    .with_wnd_size(Size::new(200., 200.)),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(110., 100.)),
+    LayoutCase::new(&[0, 0, 0]).with_rect(ribir_geom::rect(10., 0., 100., 100.))
+  );
 }
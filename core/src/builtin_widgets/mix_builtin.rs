@@ -5,8 +5,6 @@ use rxrust::prelude::*;
 use self::focus_mgr::FocusType;
 use crate::prelude::*;
 
-const MULTI_TAP_DURATION: Duration = Duration::from_millis(250);
-
 bitflags! {
   #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
   pub struct MixFlags: u64 {
@@ -29,6 +27,8 @@ bitflags! {
     #[doc="Bubble custom event listener flag, hint the widget is listening to \
      custom events"]
     const Customs = 1 << 6;
+    #[doc="Drag listener flag, hint the widget is listening to drag events"]
+    const Drag = 1 << 7;
 
     const AllListeners = Self::Lifecycle.bits()
       | Self::Pointer.bits()
@@ -36,7 +36,8 @@ bitflags! {
       | Self::KeyBoard.bits()
       | Self::Focus.bits()
       | Self::FocusInOut.bits()
-      | Self::Customs.bits();
+      | Self::Customs.bits()
+      | Self::Drag.bits();
     // listener end
 
     #[doc="Indicates whether this widget is tracing its focus status."]
@@ -245,13 +246,37 @@ impl MixBuiltin {
   pub fn on_x_times_tap(
     &self, (times, handler): (usize, impl FnMut(&mut PointerEvent) + 'static),
   ) -> &Self {
-    self.on_x_times_tap_impl(times, MULTI_TAP_DURATION, false, handler)
+    self.on_x_times_tap_impl(times, false, handler)
   }
 
   pub fn on_x_times_tap_capture(
     &self, (times, handler): (usize, impl FnMut(&mut PointerEvent) + 'static),
   ) -> &Self {
-    self.on_x_times_tap_impl(times, MULTI_TAP_DURATION, true, handler)
+    self.on_x_times_tap_impl(times, true, handler)
+  }
+
+  pub fn on_drag_start(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragStart, DragEvent, handler)
+  }
+
+  pub fn on_drag_update(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragUpdate, DragEvent, handler)
+  }
+
+  pub fn on_drag_start_capture(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragStartCapture, DragEvent, handler)
+  }
+
+  pub fn on_drag_update_capture(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragUpdateCapture, DragEvent, handler)
+  }
+
+  pub fn on_drag_end(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragEnd, DragEvent, handler)
+  }
+
+  pub fn on_drag_end_capture(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragEndCapture, DragEvent, handler)
   }
 
   pub fn on_wheel(&self, handler: impl FnMut(&mut WheelEvent) + 'static) -> &Self {
@@ -263,13 +288,12 @@ impl MixBuiltin {
   }
 
   fn on_x_times_tap_impl(
-    &self, times: usize, dur: Duration, capture: bool,
-    handler: impl FnMut(&mut PointerEvent) + 'static,
+    &self, times: usize, capture: bool, handler: impl FnMut(&mut PointerEvent) + 'static,
   ) -> &Self {
     self.silent_mark(MixFlags::Pointer);
     self
       .subject()
-      .filter_map(x_times_tap_map_filter(times, dur, capture))
+      .filter_map(x_times_tap_map_filter(times, capture))
       .subscribe(handler);
     self
   }
@@ -457,45 +481,14 @@ impl<'c> ComposeChild<'c> for MixBuiltin {
 }
 
 fn x_times_tap_map_filter(
-  x: usize, dur: Duration, capture: bool,
+  x: usize, capture: bool,
 ) -> impl FnMut(&mut Event) -> Option<&mut PointerEvent> {
   assert!(x > 0);
-  struct TapInfo {
-    pointer_id: PointerId,
-    stamps: Vec<Instant>,
-  }
-
-  let mut type_info: Option<TapInfo> = None;
-  move |e: &mut Event| {
-    let e = match e {
-      Event::Tap(e) if !capture => e,
-      Event::TapCapture(e) if capture => e,
-      _ => return None,
-    };
-    let now = Instant::now();
-    match &mut type_info {
-      Some(info) if info.pointer_id == e.id => {
-        if info.stamps.len() + 1 == x {
-          if now.duration_since(info.stamps[0]) <= dur {
-            // emit x-tap event and reset the tap info
-            type_info = None;
-            Some(e)
-          } else {
-            // remove the expired tap
-            info.stamps.remove(0);
-            info.stamps.push(now);
-            None
-          }
-        } else {
-          info.stamps.push(now);
-          None
-        }
-      }
-      _ => {
-        type_info = Some(TapInfo { pointer_id: e.id, stamps: vec![now] });
-        None
-      }
-    }
+  // Every x-th tap of a successive tap sequence emit a x-times tap event.
+  move |e: &mut Event| match e {
+    Event::Tap(e) if !capture && e.tap_count() % x == 0 => Some(e),
+    Event::TapCapture(e) if capture && e.tap_count() % x == 0 => Some(e),
+    _ => None,
   }
 }
 
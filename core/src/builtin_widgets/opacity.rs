@@ -1,5 +1,9 @@
 use crate::{prelude::*, wrap_render::*};
 
+/// A wrapper that paints its host and descendants with the `opacity`.
+///
+/// The subtree is painted to a layer and then composited with the `opacity`,
+/// if the `opacity` is `1.`, the subtree is painted directly.
 #[derive(Clone)]
 pub struct Opacity {
   pub opacity: f32,
@@ -23,10 +27,96 @@ impl WrapRender for Opacity {
     host.perform_layout(clamp, ctx)
   }
 
+  fn needs_layer(&self, host: &dyn Render) -> bool { self.opacity < 1. || host.needs_layer() }
+
   fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
-    ctx.painter().apply_alpha(self.opacity);
+    // The widget and its descendants are composited as a group in the layer
+    // began by the framework, so the overlapping children will not show
+    // through each other.
+    ctx.painter().begin_layer(self.opacity);
     if self.opacity > 0. {
       host.paint(ctx)
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn group_opacity() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockStack {
+        opacity: 0.5,
+        @Container { size: Size::new(100., 100.), background: Color::RED }
+        @Container {
+          anchor: Point::new(50., 0.),
+          size: Size::new(100., 100.),
+          background: Color::BLUE,
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let [PaintCommand::Bundle { opacity, bounds, cmds, .. }] = &frame.commands[..] else {
+      panic!("The subtree should be painted as a single layer.");
+    };
+    assert_eq!(*opacity, 0.5);
+    assert_eq!(bounds.size, Size::new(150., 100.));
+    let colors: Vec<_> = cmds
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Paint { brush: CommandBrush::Color(c), .. },
+          ..
+        }) => Some(*c),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(colors, [Color::RED, Color::BLUE]);
+  }
+
+  #[test]
+  fn opaque_without_layer() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @Container { opacity: 1., size: Size::new(100., 100.), background: Color::RED }
+    });
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    assert!(matches!(&frame.commands[..], [PaintCommand::Path(_)]));
+  }
+
+  #[test]
+  fn nested_layer_composited_once() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockStack {
+        opacity: 0.5,
+        @Container { opacity: 0.5, size: Size::new(100., 100.), background: Color::RED }
+        @Container { size: Size::new(100., 100.), background: Color::BLUE }
+      }
+    });
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let [PaintCommand::Bundle { opacity, cmds, .. }] = &frame.commands[..] else {
+      panic!("The subtree should be painted as a single layer.");
+    };
+    assert_eq!(*opacity, 0.5);
+    let [PaintCommand::Bundle { opacity, cmds: inner, .. }, PaintCommand::Path(_)] = &cmds[..]
+    else {
+      panic!("The child with opacity should be painted as a nested layer.");
+    };
+    assert_eq!(*opacity, 0.5);
+    assert!(matches!(&inner[..], [PaintCommand::Path(_)]));
+  }
+}
@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use wrap_render::WrapRender;
 
 use crate::prelude::*;
@@ -23,6 +25,8 @@ use crate::prelude::*;
 #[derive(Default)]
 pub struct Padding {
   pub padding: EdgeInsets,
+  /// The padding resolved by the directionality in the last layout.
+  resolved: Cell<EdgeInsets>,
 }
 
 impl Declare for Padding {
@@ -35,6 +39,9 @@ impl_compose_child_for_wrap_render!(Padding, DirtyPhase::Layout);
 
 impl WrapRender for Padding {
   fn perform_layout(&self, clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
+    self
+      .resolved
+      .set(self.padding.resolve(Directionality::of(ctx)));
     let thickness = self.padding.thickness();
 
     let min = (clamp.min - thickness).max(ZERO_SIZE);
@@ -46,12 +53,14 @@ impl WrapRender for Padding {
   }
 
   fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
-    ctx.content_only_transform_apply(&Transform::translation(self.padding.left, self.padding.top));
+    let padding = self.resolved.get();
+    ctx.content_only_transform_apply(&Transform::translation(padding.left, padding.top));
     host.paint(ctx);
   }
 
   fn get_transform(&self, host: &dyn Render) -> Option<Transform> {
-    let padding_matrix = Transform::translation(self.padding.left, self.padding.top);
+    let padding = self.resolved.get();
+    let padding_matrix = Transform::translation(padding.left, padding.top);
 
     let ts = host
       .get_transform()
@@ -59,15 +68,33 @@ impl WrapRender for Padding {
 
     Some(ts)
   }
+
+  fn intrinsic_width(
+    &self, host: &dyn Render, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    let thickness = self.padding.thickness();
+    let height = height.map(|h| (h - thickness.height).max(0.));
+    host.intrinsic_width(kind, height, ctx) + thickness.width
+  }
+
+  fn intrinsic_height(
+    &self, host: &dyn Render, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    let thickness = self.padding.thickness();
+    let width = width.map(|w| (w - thickness.width).max(0.));
+    host.intrinsic_height(kind, width, ctx) + thickness.height
+  }
 }
 
 impl Padding {
   #[inline]
-  pub fn new(padding: EdgeInsets) -> Self { Self { padding } }
+  pub fn new(padding: EdgeInsets) -> Self { Self { padding, resolved: Cell::default() } }
 }
 
 #[cfg(test)]
 mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
   use ribir_dev_helper::*;
 
   use super::*;
@@ -93,6 +120,29 @@ mod tests {
     LayoutCase::new(&[0, 0]).with_size(Size::new(100., 100.))
   );
 
+  #[test]
+  fn rtl_start_padding() {
+    crate::reset_test_env!();
+
+    let track_id = Rc::new(RefCell::new(None));
+    let c_track_id = track_id.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let mut child = @MockBox { size: Size::new(100., 100.) };
+      *c_track_id.borrow_mut() = Some($child.track_id());
+      @MockMulti {
+        providers: [Provider::new(Directionality::Rtl)],
+        padding: EdgeInsets::only_start(10.),
+        @ { child }
+      }
+    });
+    wnd.draw_frame();
+
+    wnd.assert_root_size(Size::new(110., 100.));
+    // The start padding is on the right side.
+    let child = track_id.borrow().as_ref().unwrap().get().unwrap();
+    assert_eq!(wnd.map_to_global(Point::zero(), child), Point::zero());
+  }
+
   #[test]
   #[cfg(not(target_arch = "wasm32"))]
   fn fix_padding_draw() {
@@ -0,0 +1,68 @@
+use wrap_render::WrapRender;
+
+use crate::prelude::*;
+
+/// This widget snaps the painted origin of its host to the device pixel grid,
+/// so the edges of the host, such as a thin border, are not blurred by a
+/// sub-pixel position.
+///
+/// Only the paint is snapped, the layout is still continuous. The whole subtree
+/// of the host moves with the snapped origin, so the text inside keeps its
+/// layout but is painted from the snapped box.
+#[simple_declare]
+#[derive(Default, Clone, Copy)]
+pub struct PixelSnap;
+
+impl_compose_child_for_wrap_render!(PixelSnap, DirtyPhase::Paint);
+
+impl WrapRender for PixelSnap {
+  fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
+    let ratio = ctx.window().device_pixel_ratio();
+    let painter = ctx.painter();
+    let t = *painter.transform();
+    let snap = |v: f32| (v * ratio).round() / ratio - v;
+    painter.set_transform(t.then_translate(Vector::new(snap(t.m31), snap(t.m32))));
+    host.paint(ctx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn snap_border_origin() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          @MockBox { size: Size::new(10.4, 10.) }
+          @PixelSnap {
+            @Container {
+              size: Size::new(20., 20.),
+              border: Border::all(BorderSide::new(1., Color::BLACK.into())),
+            }
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    // The box is laid out at the fractional offset, but painted at the pixel.
+    assert_eq!(wnd.layout_info_by_path(&[0, 1]).unwrap().pos.x, 10.4);
+    let frame = wnd.take_last_frame().unwrap();
+    let origins: Vec<_> = frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { transform, .. }) => Some((transform.m31, transform.m32)),
+        _ => None,
+      })
+      .collect();
+    assert!(!origins.is_empty());
+    assert!(origins.iter().all(|o| *o == (10., 0.)));
+  }
+}
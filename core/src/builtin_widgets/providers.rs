@@ -699,6 +699,32 @@ impl Render for ProvidersRender {
   fn only_sized_by_parent(&self) -> bool { self.render.only_sized_by_parent() }
 
   fn get_transform(&self) -> Option<Transform> { self.render.get_transform() }
+
+  fn needs_layer(&self) -> bool { self.render.needs_layer() }
+
+  fn debug_name(&self) -> &'static str { self.render.debug_name() }
+
+  fn semantics(&self) -> Option<Semantics> { self.render.semantics() }
+
+  fn z_index(&self) -> i32 { self.render.z_index() }
+
+  fn hit_bounds(&self, size: Size) -> Option<Rect> { self.render.hit_bounds(size) }
+
+  fn intrinsic_width(&self, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let Self { render, providers } = self;
+    providers.setup_providers(ctx.as_mut());
+    let width = render.intrinsic_width(kind, height, ctx);
+    providers.restore_providers(ctx.as_mut());
+    width
+  }
+
+  fn intrinsic_height(&self, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let Self { render, providers } = self;
+    providers.setup_providers(ctx.as_mut());
+    let height = render.intrinsic_height(kind, width, ctx);
+    providers.restore_providers(ctx.as_mut());
+    height
+  }
 }
 
 pub(crate) struct Setup {
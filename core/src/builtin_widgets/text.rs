@@ -71,6 +71,40 @@ impl Render for Text {
   #[inline]
   fn only_sized_by_parent(&self) -> bool { false }
 
+  fn semantics(&self) -> Option<Semantics> {
+    Some(Semantics::new(A11yRole::Text).with_label(self.text.clone()))
+  }
+
+  fn intrinsic_width(&self, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let style = Provider::of::<TextStyle>(ctx).unwrap();
+    let bounds = Size::new(f32::INFINITY, height.unwrap_or(f32::INFINITY));
+    match kind {
+      IntrinsicSize::MaxContent => {
+        self
+          .measure(self.text.substr(..), &style, bounds)
+          .width
+      }
+      // The longest word is the narrowest the text can be without breaking a word.
+      IntrinsicSize::MinContent => self
+        .text
+        .split_whitespace()
+        .map(|w| {
+          self
+            .measure(w.to_string().into(), &style, bounds)
+            .width
+        })
+        .fold(0., f32::max),
+    }
+  }
+
+  fn intrinsic_height(&self, _: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let style = Provider::of::<TextStyle>(ctx).unwrap();
+    let bounds = Size::new(width.unwrap_or(f32::INFINITY), f32::INFINITY);
+    self
+      .measure(self.text.substr(..), &style, bounds)
+      .height
+  }
+
   fn paint(&self, ctx: &mut PaintingCtx) {
     let box_rect = Rect::from_size(ctx.box_size().unwrap());
     if ctx
@@ -95,6 +129,17 @@ impl Text {
   pub fn glyphs(&self) -> Option<Ref<VisualGlyphs>> {
     Ref::filter_map(self.glyphs.borrow(), |v| v.as_ref()).ok()
   }
+
+  fn measure(&self, text: Substr, style: &TextStyle, bounds: Size) -> Size {
+    AppCtx::typography_store().borrow_mut().measure(
+      text,
+      style,
+      bounds,
+      self.text_align,
+      GlyphBaseline::Middle,
+      PlaceLineDirection::TopToBottom,
+    )
+  }
 }
 
 macro_rules! define_text_with_theme_style {
@@ -127,6 +172,11 @@ define_text_with_theme_style!(H6, title_small);
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
+  use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+  };
+
   use ribir::{core::test_helper::*, material as ribir_material, prelude::*};
   use ribir_dev_helper::*;
 
@@ -147,6 +197,93 @@ mod tests {
     LayoutCase::default().with_size(Size::new(50., 45.))
   );
 
+  #[derive(MultiChild)]
+  struct IntrinsicProbe(Rc<RefCell<Vec<(f32, f32, f32)>>>);
+
+  impl Render for IntrinsicProbe {
+    fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+      let (ctx, children) = ctx.split_children();
+      for c in children {
+        let min = ctx.child_intrinsic_width(c, IntrinsicSize::MinContent, None);
+        let max = ctx.child_intrinsic_width(c, IntrinsicSize::MaxContent, None);
+        let width = ctx
+          .perform_child_layout(c, BoxClamp::UNLIMITED)
+          .width;
+        self.0.borrow_mut().push((min, max, width));
+      }
+      clamp.min
+    }
+  }
+
+  #[test]
+  fn text_intrinsic_width() {
+    reset_test_env!();
+
+    let widths = Rc::new(RefCell::new(vec![]));
+    let c_widths = widths.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let probe = IntrinsicProbe(c_widths.clone());
+      @$probe {
+        @Text { text: "Hello ribir!" }
+        @Text { text: "Hello" }
+        @Text { text: "ribir!" }
+      }
+    });
+    wnd.draw_frame();
+
+    let widths = widths.borrow();
+    let (min, max, line_width) = widths[0];
+    let longest_word = widths[1].2.max(widths[2].2);
+    assert_eq!(max, line_width);
+    assert_eq!(min, longest_word);
+    assert!(min < max);
+  }
+
+  #[derive(SingleChild)]
+  struct LayoutCounter(Rc<Cell<usize>>);
+
+  impl Render for LayoutCounter {
+    fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+      self.0.set(self.0.get() + 1);
+      ctx
+        .perform_single_child_layout(clamp)
+        .unwrap_or_default()
+    }
+  }
+
+  #[test]
+  fn restyle_without_typography() {
+    reset_test_env!();
+
+    let (color, w_color) = split_value(Color::RED);
+    let (text_layouts, w_text_layouts) = split_value(0);
+    let layouts = Rc::new(Cell::new(0));
+    let c_layouts = layouts.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let counter = LayoutCounter(c_layouts.clone());
+      @$counter {
+        @Text {
+          text: "Hello ribir!",
+          foreground: pipe!(*$color),
+          on_performed_layout: move |_| *$w_text_layouts.write() += 1,
+        }
+      }
+    });
+    wnd.draw_frame();
+    let layout_cnt = layouts.get();
+    let text_layout_cnt = *text_layouts.read();
+    assert_eq!(text_layout_cnt, 1);
+    let size = wnd.layout_info_by_path(&[0, 0]).unwrap().size;
+
+    // Only the color changed, the text is repainted with the glyphs of the last
+    // layout.
+    *w_color.write() = Color::BLUE;
+    wnd.draw_frame();
+    assert_eq!(layouts.get(), layout_cnt);
+    assert_eq!(*text_layouts.read(), text_layout_cnt);
+    assert_eq!(wnd.layout_info_by_path(&[0, 0]).unwrap().size, size);
+  }
+
   widget_image_tests!(
     default_text,
     WidgetTester::new(fn_widget! {
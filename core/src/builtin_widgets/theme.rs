@@ -237,7 +237,17 @@ fn typography_theme() -> TypographyTheme {
     };
     let overflow = TextOverflow::Overflow;
     TextTheme {
-      text: TextStyle { line_height, font_size, letter_space, font_face, overflow },
+      text: TextStyle {
+        line_height: line_height.into(),
+        font_size,
+        letter_space,
+        font_face,
+        overflow,
+        font_features: vec![],
+        palette_index: 0,
+        text_transform: TextTransform::None,
+        case_locale: CaseLocale::Default,
+      },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
         decoration_color: Color::BLACK.with_alpha(0.87).into(),
@@ -0,0 +1,80 @@
+use crate::{prelude::*, wrap_render::*};
+
+/// Widget use to change the paint order of its host among its siblings. The
+/// siblings are painted by the ascending `z_index`, and the ones with the same
+/// `z_index` are painted in the tree order. The hit test follows the reverse
+/// paint order, so the topmost painted widget is hit first.
+///
+/// The default `z_index` of a widget is `0`.
+#[derive(Declare, Clone)]
+pub struct ZIndex {
+  #[declare(default)]
+  pub z_index: i32,
+}
+
+impl_compose_child_for_wrap_render!(ZIndex, DirtyPhase::Paint);
+
+impl WrapRender for ZIndex {
+  fn z_index(&self, _: &dyn Render) -> i32 { self.z_index }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn paint_and_hit_by_z_index() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(vec![]);
+    let item = move |name: &'static str, z_index: i32, color: Color| {
+      let w_taps = w_taps.clone_writer();
+      fn_widget! {
+        @ZIndex {
+          z_index,
+          @Container {
+            size: Size::new(50., 50.),
+            background: color,
+            on_tap: move |_| $w_taps.write().push(name),
+          }
+        }
+      }
+    };
+    // Three boxes stacked at the same position.
+    let w = fn_widget! {
+      @MockStack {
+        @ { item("red", 1, Color::RED) }
+        @ { item("green", -1, Color::GREEN) }
+        @ { item("blue", 0, Color::BLUE) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let colors: Vec<_> = frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Paint { brush: CommandBrush::Color(c), .. },
+          ..
+        }) => Some(*c),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(colors, [Color::GREEN, Color::BLUE, Color::RED]);
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (10., 10.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    // The topmost one is hit.
+    assert_eq!(*taps.read(), ["red"]);
+  }
+}
@@ -1,10 +1,18 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+  },
+};
+
 use ribir_geom::{Point, Size};
 
 use super::{WidgetCtx, WidgetCtxImpl};
 use crate::{
   prelude::ProviderCtx,
-  widget::{BoxClamp, WidgetTree},
-  widget_tree::WidgetId,
+  widget::{BoxClamp, IntrinsicSize, Render, WidgetTree},
+  widget_tree::{LayoutInfo, WidgetId},
   window::DelayEvent,
 };
 
@@ -19,6 +27,31 @@ pub struct LayoutCtx<'a> {
   /// `LayoutCtx` always in a mutable borrow.
   tree: &'a mut WidgetTree,
   provider_ctx: ProviderCtx,
+  /// The original layout information of the widgets touched by the intrinsic
+  /// size query in progress, they are restored after the query finished.
+  measuring: Option<HashMap<WidgetId, Option<LayoutInfo>, ahash::RandomState>>,
+}
+
+/// A token to cancel the layout in progress of a window, see
+/// [`Window::layout_cancel_token`](crate::window::Window::layout_cancel_token).
+///
+/// The layout checks the token before it descends into every child. Once the
+/// token is canceled, the rest of the layout is skipped, and the subtree whose
+/// layout was interrupted is marked dirty to be redone in the next layout
+/// pass, so a burst of inputs doesn't wait for the stale layouts to complete.
+/// The token can be canceled from any thread, and it's reset when the layout
+/// aborts.
+#[derive(Clone, Default)]
+pub struct LayoutCancelToken(Arc<AtomicBool>);
+
+impl LayoutCancelToken {
+  /// Abort the layout in progress, or the next layout if no layout is running.
+  pub fn cancel(&self) { self.0.store(true, Ordering::Relaxed) }
+
+  pub fn is_canceled(&self) -> bool { self.0.load(Ordering::Relaxed) }
+
+  /// Reset the token and return whether it was canceled.
+  pub(crate) fn take(&self) -> bool { self.0.swap(false, Ordering::Relaxed) }
 }
 
 impl<'a> WidgetCtxImpl for LayoutCtx<'a> {
@@ -36,7 +69,7 @@ impl<'a> LayoutCtx<'a> {
     } else {
       ProviderCtx::default()
     };
-    Self { id, tree, provider_ctx }
+    Self { id, tree, provider_ctx, measuring: None }
   }
 
   /// Perform layout of the widget of the context and return its size.
@@ -50,19 +83,29 @@ impl<'a> LayoutCtx<'a> {
     debug_assert!(clamp.min.is_finite());
     let size = id.assert_get(tree2).perform_layout(clamp, self);
     debug_assert!(size.is_finite());
-    let info = self.tree.store.layout_info_or_default(id);
+    let info = self.layout_info_mut(id);
     info.clamp = clamp;
     info.size = Some(size);
 
-    self
-      .window()
-      .add_delay_event(DelayEvent::PerformedLayout(id));
+    // The layout of a canceled pass is incomplete and will be redone, and the
+    // layout of a measurement is discarded.
+    if !self.tree.layout_cancel.is_canceled() && self.measuring.is_none() {
+      self
+        .window()
+        .add_delay_event(DelayEvent::PerformedLayout(id));
+    }
 
     size
   }
 
   /// Perform layout of the `child` and return its size.
+  ///
+  /// If the layout is canceled, the child is skipped and the minimum size of
+  /// the `clamp` is returned.
   pub fn perform_child_layout(&mut self, child: WidgetId, clamp: BoxClamp) -> Size {
+    if self.tree.layout_cancel.is_canceled() {
+      return clamp.min;
+    }
     let size = self
       .get_calculated_size(child, clamp)
       .unwrap_or_else(|| {
@@ -85,7 +128,11 @@ impl<'a> LayoutCtx<'a> {
   /// its parent.
   #[inline]
   pub fn update_position(&mut self, child: WidgetId, pos: Point) {
-    self.tree.store.layout_info_or_default(child).pos = pos;
+    let info = self.layout_info_mut(child);
+    if info.pos != pos {
+      info.pos = pos;
+      self.tree.layout_generation = self.tree.layout_generation.wrapping_add(1);
+    }
   }
 
   /// Return the position of the widget relative to its parent.
@@ -104,7 +151,7 @@ impl<'a> LayoutCtx<'a> {
   /// Only use this method if you are certain of its effects.
   #[inline]
   pub fn update_size(&mut self, child: WidgetId, size: Size) {
-    self.tree.store.layout_info_or_default(child).size = Some(size);
+    self.layout_info_mut(child).size = Some(size);
   }
 
   /// Split a children iterator from the context, returning a tuple of `&mut
@@ -144,9 +191,70 @@ impl<'a> LayoutCtx<'a> {
   #[inline]
   pub fn force_child_relayout(&mut self, child: WidgetId) -> bool {
     assert_eq!(child.parent(self.tree), Some(self.id));
+    self.record_layout_info(child);
     self.tree.store.force_layout(child).is_some()
   }
 
+  /// Query the intrinsic width of the `child`, the `height` is the height the
+  /// child will be laid out with if it's known.
+  ///
+  /// The query does not change the layout result of the `child`.
+  pub fn child_intrinsic_width(
+    &mut self, child: WidgetId, kind: IntrinsicSize, height: Option<f32>,
+  ) -> f32 {
+    self.with_child_render(child, |r, ctx| r.intrinsic_width(kind, height, ctx))
+  }
+
+  /// Query the intrinsic height of the `child`, the `width` is the width the
+  /// child will be laid out with if it's known.
+  ///
+  /// The query does not change the layout result of the `child`.
+  pub fn child_intrinsic_height(
+    &mut self, child: WidgetId, kind: IntrinsicSize, width: Option<f32>,
+  ) -> f32 {
+    self.with_child_render(child, |r, ctx| r.intrinsic_height(kind, width, ctx))
+  }
+
+  fn with_child_render<R>(
+    &mut self, child: WidgetId, f: impl FnOnce(&dyn Render, &mut Self) -> R,
+  ) -> R {
+    // Safety: the `tree` just use to get the widget of `child`, and `tree2` not
+    // drop or modify it during the query.
+    let tree2 = unsafe { &*(self.tree as *mut WidgetTree) };
+    // The query may lay out the descendants to measure them, start a
+    // measurement to restore their layout after the outermost query.
+    let outermost = self.measuring.is_none();
+    if outermost {
+      self.measuring = Some(<_>::default());
+    }
+    let id = std::mem::replace(&mut self.id, child);
+    let res = f(child.assert_get(tree2).as_render(), self);
+    self.id = id;
+    self.provider_ctx.pop_providers_for(self.id);
+    if let Some(journal) = self.measuring.take().filter(|_| outermost) {
+      for (id, info) in journal {
+        self.tree.store.restore(id, info);
+      }
+    }
+    res
+  }
+
+  /// Keep the original layout information of `id` before it's modified by a
+  /// measurement.
+  fn record_layout_info(&mut self, id: WidgetId) {
+    if let Some(journal) = self.measuring.as_mut() {
+      let store = &self.tree.store;
+      journal
+        .entry(id)
+        .or_insert_with(|| store.layout_info(id).cloned());
+    }
+  }
+
+  fn layout_info_mut(&mut self, id: WidgetId) -> &mut LayoutInfo {
+    self.record_layout_info(id);
+    self.tree.store.layout_info_or_default(id)
+  }
+
   fn get_calculated_size(&self, child: WidgetId, clamp: BoxClamp) -> Option<Size> {
     let info = self.tree.store.layout_info(child)?;
     if info.clamp == clamp { info.size } else { None }
@@ -160,3 +268,44 @@ impl<'w> AsRef<ProviderCtx> for LayoutCtx<'w> {
 impl<'w> AsMut<ProviderCtx> for LayoutCtx<'w> {
   fn as_mut(&mut self) -> &mut ProviderCtx { &mut self.provider_ctx }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  #[derive(Declare, SingleChild)]
+  struct MeasureOnly;
+
+  impl Render for MeasureOnly {
+    fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+      let child = ctx.assert_single_child();
+      let width = ctx.child_intrinsic_width(child, IntrinsicSize::MaxContent, None);
+      clamp.clamp(Size::new(width, 0.))
+    }
+  }
+
+  #[test]
+  fn measure_not_commit_layout() {
+    reset_test_env!();
+
+    let (performed, w_performed) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MeasureOnly {
+        @MockMulti {
+          @MockBox {
+            size: Size::new(30., 10.),
+            on_performed_layout: move |_| *$w_performed.write() += 1,
+          }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let size = wnd.layout_info_by_path(&[0]).unwrap().size;
+    assert_eq!(size, Some(Size::new(30., 0.)));
+    // The child is only measured, it and its descendants are never laid out.
+    assert!(wnd.layout_info_by_path(&[0, 0]).is_none());
+    assert!(wnd.layout_info_by_path(&[0, 0, 0]).is_none());
+    assert_eq!(*performed.read(), 0);
+  }
+}
@@ -10,6 +10,7 @@ use crate::{
 };
 
 pub(crate) mod dispatcher;
+pub(crate) mod hit_index;
 pub use dispatcher::GrabPointer;
 pub mod custom_event;
 pub use custom_event::*;
@@ -20,15 +21,21 @@ mod keyboard;
 pub use keyboard::*;
 mod character;
 pub use character::*;
+mod drag;
 mod wheel;
+pub use drag::*;
+mod gesture_arena;
 use smallvec::SmallVec;
 pub use wheel::*;
 mod ime_pre_edit;
 pub use ime_pre_edit::*;
 mod lifecycle;
 pub use lifecycle::*;
+mod shortcut;
+pub use shortcut::*;
 
 pub(crate) mod focus_mgr;
+pub use focus_mgr::{FocusDirection, FocusState};
 mod listener_impl_helper;
 
 pub struct CommonEvent {
@@ -136,6 +143,19 @@ pub enum Event {
   PointerLeave(PointerEvent),
   Tap(PointerEvent),
   TapCapture(PointerEvent),
+  /// Event fired when the pointer pressed on the widget and moved farther than
+  /// the drag threshold.
+  DragStart(DragEvent),
+  /// Event fired when the pointer moves during a drag gesture.
+  DragUpdate(DragEvent),
+  /// Same as `DragStart` but emit in capture phase.
+  DragStartCapture(DragEvent),
+  /// Same as `DragUpdate` but emit in capture phase.
+  DragUpdateCapture(DragEvent),
+  /// Event fired when the pointer released after a drag gesture began.
+  DragEnd(DragEvent),
+  /// Same as `DragEnd` but emit in capture phase.
+  DragEndCapture(DragEvent),
   ImePreEdit(ImePreEditEvent),
   ImePreEditCapture(ImePreEditEvent),
   /// Firing the wheel event when the user rotates a wheel button on a pointing
@@ -207,6 +227,12 @@ impl std::ops::Deref for Event {
       | Event::PointerLeave(e)
       | Event::Tap(e)
       | Event::TapCapture(e) => e,
+      Event::DragStart(e)
+      | Event::DragStartCapture(e)
+      | Event::DragUpdate(e)
+      | Event::DragUpdateCapture(e)
+      | Event::DragEnd(e)
+      | Event::DragEndCapture(e) => e,
       Event::ImePreEdit(e) | Event::ImePreEditCapture(e) => e,
       Event::Wheel(e) | Event::WheelCapture(e) => e,
       Event::Chars(e) | Event::CharsCapture(e) => e,
@@ -240,6 +266,12 @@ impl std::ops::DerefMut for Event {
       | Event::PointerLeave(e)
       | Event::Tap(e)
       | Event::TapCapture(e) => e,
+      Event::DragStart(e)
+      | Event::DragStartCapture(e)
+      | Event::DragUpdate(e)
+      | Event::DragUpdateCapture(e)
+      | Event::DragEnd(e)
+      | Event::DragEndCapture(e) => e,
       Event::ImePreEdit(e) | Event::ImePreEditCapture(e) => e,
       Event::Wheel(e) | Event::WheelCapture(e) => e,
       Event::Chars(e) | Event::CharsCapture(e) => e,
@@ -265,6 +297,12 @@ impl Event {
       | Event::PointerLeave(_)
       | Event::Tap(_)
       | Event::TapCapture(_) => MixFlags::Pointer,
+      Event::DragStart(_)
+      | Event::DragUpdate(_)
+      | Event::DragEnd(_)
+      | Event::DragStartCapture(_)
+      | Event::DragUpdateCapture(_)
+      | Event::DragEndCapture(_) => MixFlags::Drag,
       Event::Wheel(_) | Event::WheelCapture(_) => MixFlags::Wheel,
       Event::ImePreEdit(_)
       | Event::ImePreEditCapture(_)
@@ -1,7 +1,15 @@
-use std::cell::RefCell;
+use std::{
+  cell::{Cell, RefCell},
+  ptr::NonNull,
+};
 
 use winit::event::{DeviceId, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
 
+use super::{
+  drag::DragTracker,
+  gesture_arena::{Gesture, GestureArena},
+  hit_index::HitIndex,
+};
 use crate::{
   prelude::*,
   window::{DelayEvent, WindowId},
@@ -24,6 +32,25 @@ impl GrabPointer {
     wnd.dispatcher.borrow().grab_pointer(wid)
   }
 
+  /// Grab the pointer input to the widget corresponding to the wid until the
+  /// pointer is up, so it keeps receiving the pointer events even if the
+  /// pointer leaves it, such as during a drag.
+  ///
+  /// The grab is released after the pointer up, grab it again in the pointer
+  /// up handler to keep it. Return false if some wid is already grabbed.
+  pub fn capture(wid: WidgetId, wnd: &Window) -> bool {
+    wnd.dispatcher.borrow().capture_pointer(wid)
+  }
+
+  /// Release the grab of [`GrabPointer::capture`], a grab by the handle is kept
+  /// until the handle is released.
+  pub fn release_capture(wnd: &Window) { wnd.dispatcher.borrow().release_pointer(); }
+
+  /// Return the widget grabbing the pointer input.
+  pub fn grabbing(wnd: &Window) -> Option<WidgetId> {
+    *wnd.dispatcher.borrow().grab_mouse_wid.borrow()
+  }
+
   /// Release the pointer input.
   pub fn release(self) {}
 }
@@ -32,12 +59,49 @@ impl Drop for GrabPointer {
   fn drop(&mut self) { self.0.borrow_mut().take(); }
 }
 
+/// The default maximum interval between two successive taps that are counted
+/// as a part of the same multi-tap sequence.
+const MULTI_TAP_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The default maximum distance, in logical pixels, between two successive taps
+/// that are counted as a part of the same multi-tap sequence.
+const MULTI_TAP_DISTANCE: f32 = 4.;
+
 pub(crate) struct Dispatcher {
   wnd_id: WindowId,
   pub(crate) info: DispatchInfo,
   pub(crate) entered_widgets: Vec<WidgetId>,
   grab_mouse_wid: Sc<RefCell<Option<WidgetId>>>,
   pointer_down_wid: Option<WidgetId>,
+  drag: Option<DragTracker>,
+  /// Resolve the tap and the drag of the pressed pointer, only one of them
+  /// fires.
+  arena: GestureArena,
+  multi_tap_interval: Duration,
+  multi_tap_distance: f32,
+  last_tap: Option<LastTap>,
+  /// The distance the cursor must move past the boundary of a hovered widget
+  /// before it's left.
+  hover_hysteresis: f32,
+  /// The last hit widget, reused if the cursor and the layout are not changed.
+  hit_cache: Cell<Option<HitCache>>,
+  /// The pointer is grabbed by `GrabPointer::capture`, release it after the
+  /// pointer up.
+  grab_until_up: Cell<bool>,
+}
+
+#[derive(Clone, Copy)]
+struct HitCache {
+  pos: Point,
+  layout_generation: usize,
+  hit: Option<WidgetId>,
+}
+
+struct LastTap {
+  wid: WidgetId,
+  at: Instant,
+  pos: Point,
+  count: usize,
 }
 
 impl Dispatcher {
@@ -48,9 +112,34 @@ impl Dispatcher {
       entered_widgets: vec![],
       grab_mouse_wid: Sc::new(RefCell::new(None)),
       pointer_down_wid: None,
+      drag: None,
+      arena: GestureArena::default(),
+      multi_tap_interval: MULTI_TAP_INTERVAL,
+      multi_tap_distance: MULTI_TAP_DISTANCE,
+      last_tap: None,
+      hover_hysteresis: 0.,
+      hit_cache: Cell::new(None),
+      grab_until_up: Cell::new(false),
     }
   }
 
+  /// Set the thresholds to recognize successive taps as a multi-tap sequence.
+  /// A tap counts as the next one of the sequence only if it's on the same
+  /// widget, within `interval` of the previous tap and not farther than
+  /// `distance` from it.
+  pub(crate) fn set_multi_tap_threshold(&mut self, interval: Duration, distance: f32) {
+    self.multi_tap_interval = interval;
+    self.multi_tap_distance = distance;
+  }
+
+  pub(crate) fn multi_tap_threshold(&self) -> (Duration, f32) {
+    (self.multi_tap_interval, self.multi_tap_distance)
+  }
+
+  pub(crate) fn set_hover_hysteresis(&mut self, distance: f32) { self.hover_hysteresis = distance; }
+
+  pub(crate) fn hover_hysteresis(&self) -> f32 { self.hover_hysteresis }
+
   pub(crate) fn grab_pointer(&self, wid: WidgetId) -> Option<GrabPointer> {
     if self.grab_mouse_wid.borrow().is_none() {
       *self.grab_mouse_wid.borrow_mut() = Some(wid);
@@ -60,6 +149,22 @@ impl Dispatcher {
     }
   }
 
+  pub(crate) fn capture_pointer(&self, wid: WidgetId) -> bool {
+    let mut grab = self.grab_mouse_wid.borrow_mut();
+    if grab.is_some() {
+      return false;
+    }
+    *grab = Some(wid);
+    self.grab_until_up.set(true);
+    true
+  }
+
+  pub(crate) fn release_pointer(&self) {
+    if self.grab_until_up.take() {
+      self.grab_mouse_wid.borrow_mut().take();
+    }
+  }
+
   fn window(&self) -> Sc<Window> {
     AppCtx::get_window(self.wnd_id).expect("The window of the `Dispatcher` already dropped")
   }
@@ -96,8 +201,12 @@ impl Dispatcher {
     if let Some(id) = wnd.focusing() {
       let e = DelayEvent::KeyBoard { key, state, physical_key, is_repeat, location, id };
       wnd.add_delay_event(e);
-    } else if key == VirtualKey::Named(NamedKey::Tab) && state == ElementState::Pressed {
-      wnd.add_delay_event(DelayEvent::TabFocusMove);
+    } else if state == ElementState::Pressed {
+      if key == VirtualKey::Named(NamedKey::Tab) {
+        wnd.add_delay_event(DelayEvent::TabFocusMove);
+      } else {
+        wnd.add_delay_event(DelayEvent::Shortcut(key));
+      }
     }
   }
 
@@ -125,11 +234,23 @@ impl Dispatcher {
         .add_delay_event(DelayEvent::GrabPointerDown(grab_pointer));
     } else {
       self.pointer_down_wid = None;
+      self.drag = None;
       if let Some(hit) = hit {
+        let wnd = self.window();
         self.pointer_down_wid = Some(hit);
-        self
-          .window()
-          .add_delay_event(DelayEvent::PointerDown(hit));
+        // Only recognize the drag if someone is listening to it, so a tap with a
+        // little jitter is not canceled for nothing.
+        let listen_drag = hit.ancestors(wnd.tree()).any(|id| {
+          id.query_all_iter::<MixBuiltin>(wnd.tree())
+            .any(|m| m.contain_flag(MixFlags::Drag))
+        });
+        if listen_drag {
+          self.drag = Some(DragTracker::new(hit, self.info.cursor_pos, wnd.now()));
+          self.arena.open([Gesture::Tap, Gesture::Drag]);
+        } else {
+          self.arena.open([Gesture::Tap]);
+        }
+        wnd.add_delay_event(DelayEvent::PointerDown(hit));
       }
     }
   }
@@ -138,13 +259,27 @@ impl Dispatcher {
     let wnd = self.window();
     let grab_pointer = *self.grab_mouse_wid.borrow();
     if let Some(grab_pointer) = grab_pointer {
+      // Release the capture before the pointer up handlers run, so they can
+      // capture it again.
+      self.release_pointer();
       wnd.add_delay_event(DelayEvent::GrabPointerUp(grab_pointer));
     } else {
       if let Some(hit) = hit {
         wnd.add_delay_event(DelayEvent::PointerUp(hit));
+      }
+      if let Some(e) = self
+        .drag
+        .take()
+        .and_then(|drag| drag.end(wnd.now()))
+      {
+        wnd.add_delay_event(e);
+      }
+      let tap_wins = self.arena.sweep() == Some(Gesture::Tap);
+      if let Some(hit) = hit.filter(|_| tap_wins) {
         if let Some(wid) = self.pointer_down_wid {
           if let Some(p) = wid.lowest_common_ancestor(hit, wnd.tree()) {
-            wnd.add_delay_event(DelayEvent::Tap(p));
+            let count = self.tap_count(p);
+            wnd.add_delay_event(DelayEvent::Tap { wid: p, count });
           }
         }
       }
@@ -152,6 +287,39 @@ impl Dispatcher {
     }
   }
 
+  /// The `gesture` wins the arena, cancel the losers.
+  fn accept_gesture(&mut self, gesture: Gesture) {
+    for loser in self.arena.accept(gesture) {
+      match loser {
+        Gesture::Tap => {
+          if let Some(wid) = self.pointer_down_wid {
+            self
+              .window()
+              .add_delay_event(DelayEvent::PointerCancel(wid));
+          }
+        }
+        Gesture::Drag => self.drag = None,
+      }
+    }
+  }
+
+  fn tap_count(&mut self, wid: WidgetId) -> usize {
+    let at = self.window().now();
+    let pos = self.info.cursor_pos;
+    let count = match self.last_tap.take() {
+      Some(last)
+        if last.wid == wid
+          && at.duration_since(last.at) <= self.multi_tap_interval
+          && (pos - last.pos).length() <= self.multi_tap_distance =>
+      {
+        last.count + 1
+      }
+      _ => 1,
+    };
+    self.last_tap = Some(LastTap { wid, at, pos, count });
+    count
+  }
+
   pub fn cursor_move_to(&mut self, position: Point) {
     self.info.cursor_pos = position;
     let grab_pointer = *self.grab_mouse_wid.borrow();
@@ -161,11 +329,26 @@ impl Dispatcher {
         .add_delay_event(DelayEvent::GrabPointerMove(grab_pointer));
     } else {
       let new_hit = self.hit_widget();
-      self.pointer_enter_leave_dispatch(new_hit);
+      self.pointer_enter_leave_dispatch(self.hover_widget(new_hit));
+      let wnd = self.window();
       if let Some(hit) = new_hit {
-        self
-          .window()
-          .add_delay_event(DelayEvent::PointerMove(hit));
+        wnd.add_delay_event(DelayEvent::PointerMove(hit));
+      }
+      if self
+        .drag
+        .as_ref()
+        .is_some_and(|d| d.wid().is_dropped(wnd.tree()))
+      {
+        self.drag = None;
+      }
+      if let Some(drag) = self.drag.as_mut() {
+        let events = drag.move_to(position, wnd.now());
+        if matches!(events.first(), Some(DelayEvent::DragStart { .. })) {
+          self.accept_gesture(Gesture::Drag);
+        }
+        events
+          .into_iter()
+          .for_each(|e| wnd.add_delay_event(e));
       }
     }
   }
@@ -191,11 +374,13 @@ impl Dispatcher {
             let wnd = self.window();
             let tree = wnd.tree();
 
+            // Click to focus the nearest focusable ancestor, or clear the focus if
+            // clicking on an empty space.
             let nearest_focus = hit.and_then(|wid| {
-              wid.ancestors(tree).find(|id| {
-                id.query_all_iter::<MixBuiltin>(tree)
-                  .any(|m| m.contain_flag(MixFlags::Focus))
-              })
+              wnd
+                .focus_mgr
+                .borrow()
+                .focusable_ancestor(wid, tree)
             });
             if let Some(focus_id) = nearest_focus {
               wnd.focus_mgr.borrow_mut().focus(focus_id, tree);
@@ -259,65 +444,169 @@ impl Dispatcher {
     self.entered_widgets = new_hit.map_or(vec![], |wid| wid.ancestors(tree).collect::<Vec<_>>());
   }
 
-  fn hit_widget(&self) -> Option<WidgetId> {
-    fn deepest_test(ctx: &mut HitTestCtx, pos: &mut Point) -> Option<WidgetId> {
-      // Safety: The widget tree remains read-only throughout the entire hit testing
-      // process.
-      let tree = unsafe { &*(ctx.tree() as *const WidgetTree) };
-      let mut hit_target = None;
-      loop {
-        let id = ctx.id();
-        let r = id.assert_get(tree);
-        let HitTest { hit, can_hit_child } = r.hit_test(ctx, *pos);
-
-        if hit {
-          hit_target = Some(id);
-        }
+  /// Return the widget hovered by the cursor when it hits `hit`. The deepest
+  /// entered widget keeps hovered until the cursor moves farther than the
+  /// hover hysteresis past its boundary, unless the cursor enters another
+  /// widget out of it.
+  fn hover_widget(&self, hit: Option<WidgetId>) -> Option<WidgetId> {
+    let distance = self.hover_hysteresis;
+    if distance <= 0. {
+      return hit;
+    }
+    let wnd = self.window();
+    let tree = wnd.tree();
+    let pos = self.info.cursor_pos;
+    let keep = self.entered_widgets.iter().copied().find(|wid| {
+      !wid.is_dropped(tree)
+        && tree
+          .global_rect(*wid)
+          .is_some_and(|rc| rc.inflate(distance, distance).contains(pos))
+    });
+    match keep {
+      Some(keep) if hit.is_none_or(|hit| hit.ancestor_of(keep, tree)) => Some(keep),
+      _ => hit,
+    }
+  }
 
-        if hit || can_hit_child {
-          if let Some(c) = id.last_child(tree) {
-            *pos = ctx.map_from_parent(*pos);
-            ctx.set_id(c);
-            continue;
-          }
-        }
+  fn hit_widget(&self) -> Option<WidgetId> {
+    let wnd = self.window();
+    let tree = wnd.tree();
+    let pos = self.info.cursor_pos;
+    let layout_generation = tree.layout_generation;
+    let cached = self.hit_cache.get().filter(|c| {
+      c.pos == pos
+        && c.layout_generation == layout_generation
+        && c.hit.is_none_or(|w| !w.is_dropped(tree))
+    });
+    if let Some(c) = cached {
+      return c.hit;
+    }
 
-        break;
-      }
+    let hit = self.hit_test_walk();
+    self
+      .hit_cache
+      .set(Some(HitCache { pos, layout_generation, hit }));
+    hit
+  }
 
-      hit_target
-    }
+  /// Walk the tree to find the deepest widget under the cursor.
+  fn hit_test_walk(&self) -> Option<WidgetId> {
+    hit_test_at(self.window().tree(), self.info.cursor_pos)
+  }
+}
 
-    let mut ctx = HitTestCtx::new(self.window().tree);
-    let mut pos = self.info.cursor_pos;
-    let mut hit_target = deepest_test(&mut ctx, &mut pos);
+/// Return the deepest widget at the `pos`, the `pos` is relative to the
+/// window.
+pub(crate) fn hit_test_at(tree: &WidgetTree, pos: Point) -> Option<WidgetId> {
+  hit_test_in(tree, pos, &tree.hit_index())
+}
 
-    let (ctx, tree) = ctx.split_tree();
-    while hit_target.is_some() && Some(ctx.id()) != hit_target {
-      ctx.finish();
+/// Hit test the tree at the `pos` relative to the window, the subtrees that
+/// the `index` tells can't be hit are skipped.
+pub(crate) fn hit_test_in(tree: &WidgetTree, mut pos: Point, index: &HitIndex) -> Option<WidgetId> {
+  let global = pos;
+  // The children not tested yet of every descended level, in the reverse paint
+  // order, so the paint order of a widget is computed only once. The position
+  // before descending is kept to restore it when the walk goes back, instead of
+  // mapping it back by the transforms that may lose the precision.
+  let mut levels = vec![];
+  let deepest_test = |ctx: &mut HitTestCtx, pos: &mut Point, levels: &mut Vec<_>| {
+    let mut hit_target = None;
+    loop {
       let id = ctx.id();
-      if let Some(sibling) = id.previous_sibling(tree) {
-        ctx.set_id(sibling);
-        if let Some(hit) = deepest_test(ctx, &mut pos) {
-          hit_target = Some(hit);
-        }
-      } else if let Some(p) = id.parent(tree) {
-        ctx.finish();
-        ctx.set_id(p);
-        pos = ctx.map_to_parent(pos);
+      let r = id.assert_get(tree);
+      let HitTest { hit, can_hit_child } = if index.may_hit(id, global) {
+        r.hit_test(ctx, *pos)
       } else {
-        break;
+        HitTest { hit: false, can_hit_child: false }
+      };
+
+      if hit {
+        hit_target = Some(id);
       }
+
+      if hit || can_hit_child {
+        let mut children = id.rev_paint_children(tree);
+        if let Some(c) = children.next() {
+          levels.push((children, *pos));
+          *pos = ctx.map_from_parent(*pos);
+          ctx.set_id(c);
+          continue;
+        }
+      }
+
+      break;
     }
 
     hit_target
+  };
+
+  let mut ctx = HitTestCtx::new(NonNull::from(tree));
+  let mut hit_target = deepest_test(&mut ctx, &mut pos, &mut levels);
+
+  let (ctx, tree) = ctx.split_tree();
+  while hit_target.is_some() && Some(ctx.id()) != hit_target {
+    ctx.finish();
+    let id = ctx.id();
+    if let Some(sibling) = levels
+      .last_mut()
+      .and_then(|(children, _)| children.next())
+    {
+      ctx.set_id(sibling);
+      if let Some(hit) = deepest_test(ctx, &mut pos, &mut levels) {
+        hit_target = Some(hit);
+      }
+    } else if let Some((p, (_, parent_pos))) = id.parent(tree).zip(levels.pop()) {
+      ctx.finish();
+      ctx.set_id(p);
+      pos = parent_pos;
+    } else {
+      break;
+    }
   }
+
+  hit_target
+}
+
+/// Return all the widgets hit at the `pos` in the reverse paint order, the
+/// `pos` is relative to the window.
+pub(crate) fn hit_stack_at(tree: &WidgetTree, pos: Point) -> Vec<WidgetId> {
+  fn collect(
+    ctx: &mut HitTestCtx, pos: Point, global: Point, index: &HitIndex, stack: &mut Vec<WidgetId>,
+  ) {
+    let (ctx, tree) = ctx.split_tree();
+    let id = ctx.id();
+    if !index.may_hit(id, global) {
+      return;
+    }
+    let HitTest { hit, can_hit_child } = id.assert_get(tree).hit_test(ctx, pos);
+    if hit || can_hit_child {
+      let child_pos = ctx.map_from_parent(pos);
+      for c in id.rev_paint_children(tree) {
+        ctx.set_id(c);
+        collect(ctx, child_pos, global, index, stack);
+      }
+      ctx.set_id(id);
+    }
+    if hit {
+      stack.push(id);
+    }
+    ctx.finish();
+  }
+
+  let mut stack = vec![];
+  let mut ctx = HitTestCtx::new(NonNull::from(tree));
+  collect(&mut ctx, pos, pos, &tree.hit_index(), &mut stack);
+  stack
 }
 
 impl DispatchInfo {
   #[inline]
   pub fn modifiers(&self) -> ModifiersState { self.modifiers }
 
+  #[inline]
+  pub(crate) fn set_modifiers(&mut self, modifiers: ModifiersState) { self.modifiers = modifiers; }
+
   #[inline]
   pub fn global_pos(&self) -> Point { self.cursor_pos }
 
@@ -327,6 +616,7 @@ impl DispatchInfo {
 
 #[cfg(test)]
 mod tests {
+  use std::rc::Rc;
 
   use super::*;
   use crate::{reset_test_env, test_helper::*};
@@ -559,6 +849,41 @@ mod tests {
     assert_eq!(&*leave.read(), &[1, 2, 1, 2]);
   }
 
+  #[test]
+  fn hover_hysteresis() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pointer_enter: move |_| $w_records.write().push("enter"),
+        on_pointer_leave: move |_| $w_records.write().push("leave"),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    wnd.set_hover_hysteresis(10.);
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 50.).into() });
+      wnd.run_frame_tasks();
+    };
+
+    move_to(50.);
+    assert_eq!(*records.read(), ["enter"]);
+    // Jitter across the boundary within the hysteresis.
+    move_to(105.);
+    move_to(95.);
+    move_to(108.);
+    assert_eq!(*records.read(), ["enter"]);
+
+    move_to(115.);
+    assert_eq!(*records.read(), ["enter", "leave"]);
+  }
+
   #[test]
   fn capture_click() {
     reset_test_env!();
@@ -595,6 +920,55 @@ mod tests {
     assert_eq!(*click_path.read(), [1, 2, 3, 4]);
   }
 
+  #[test]
+  fn capture_cancel_event() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(vec![]);
+    let (intercept, w_intercept) = split_value(false);
+    let c_records = w_records.clone_writer();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pointer_down_capture: move |e| {
+          $w_records.write().push("parent capture");
+          if *$intercept {
+            e.stop_propagation();
+          }
+        },
+        on_pointer_down: move |_| $w_records.write().push("parent"),
+        @MockBox {
+          size: Size::new(100., 100.),
+          on_pointer_down_capture: move |_| $w_records.write().push("target capture"),
+          on_pointer_down: move |_| $w_records.write().push("target"),
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(400., 400.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: (50f64, 50f64).into(),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*records.read(), ["parent capture", "target capture", "target", "parent"]);
+
+    // The parent stops the propagation in the capture phase, neither the target
+    // nor the bubbling phase receives the event.
+    c_records.write().clear();
+    *w_intercept.write() = true;
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*records.read(), ["parent capture"]);
+  }
+
   #[test]
   fn click() {
     reset_test_env!();
@@ -651,6 +1025,72 @@ mod tests {
     assert_eq!(*click_path.read(), 1);
   }
 
+  type TapCounts = Watcher<Reader<Vec<usize>>>;
+
+  /// The clock of the window is frozen, advance it by the returned cell.
+  fn tap_counts_env() -> (TestWindow, TapCounts, Rc<Cell<Instant>>) {
+    let (counts, w_counts) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_tap: move |e| $w_counts.write().push(e.tap_count()),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    let now = Rc::new(Cell::new(Instant::now()));
+    let clock = now.clone();
+    wnd.set_clock(move || clock.get());
+    (wnd, counts, now)
+  }
+
+  fn tap_once(wnd: &TestWindow) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+  }
+
+  #[test]
+  fn fast_clicks_count_up() {
+    reset_test_env!();
+
+    let (wnd, counts, now) = tap_counts_env();
+    tap_once(&wnd);
+    now.set(now.get() + MULTI_TAP_INTERVAL);
+    tap_once(&wnd);
+    wnd.run_frame_tasks();
+
+    assert_eq!(*counts.read(), [1, 2]);
+  }
+
+  #[test]
+  fn slow_clicks_reset_count() {
+    reset_test_env!();
+
+    let (wnd, counts, now) = tap_counts_env();
+    tap_once(&wnd);
+    now.set(now.get() + MULTI_TAP_INTERVAL + Duration::from_millis(1));
+    tap_once(&wnd);
+    wnd.run_frame_tasks();
+
+    assert_eq!(*counts.read(), [1, 1]);
+  }
+
+  #[test]
+  fn far_clicks_reset_count() {
+    reset_test_env!();
+
+    let (wnd, counts, _) = tap_counts_env();
+    let device_id = unsafe { DeviceId::dummy() };
+    tap_once(&wnd);
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50, 50).into() });
+    tap_once(&wnd);
+    wnd.run_frame_tasks();
+
+    assert_eq!(*counts.read(), [1, 1]);
+  }
+
   #[test]
   fn focus_change_by_event() {
     reset_test_env!();
@@ -768,6 +1208,52 @@ mod tests {
     assert_eq!(dispatcher.hit_widget(), *expect_hit.read());
   }
 
+  #[test]
+  fn cached_hit_same_as_walk() {
+    reset_test_env!();
+    let (offset, w_offset) = split_value(0.);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          anchor: pipe!(Point::new(*$offset, 0.)),
+          @ {
+            (0..100).map(|i| @MockBox {
+              anchor: Point::new((i % 10) as f32 * 10., (i / 10) as f32 * 10.),
+              size: Size::new(10., 10.),
+            })
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    let assert_hits = |wnd: &TestWindow| {
+      let mut dispatcher = wnd.dispatcher.borrow_mut();
+      for pos in [(15., 5.), (55., 25.), (95., 95.), (31., 72.)] {
+        dispatcher.info.cursor_pos = Point::new(pos.0, pos.1);
+        let hit = dispatcher.hit_widget();
+        assert!(hit.is_some());
+        assert_eq!(hit, dispatcher.hit_test_walk());
+        // Hit the same position again, the result is from the cache.
+        assert_eq!(hit, dispatcher.hit_widget());
+      }
+    };
+    assert_hits(&wnd);
+
+    let pos = Point::new(5., 5.);
+    wnd.dispatcher.borrow_mut().info.cursor_pos = pos;
+    let before = wnd.dispatcher.borrow().hit_widget();
+
+    // The cache is invalid after the layout changed.
+    *w_offset.write() = 10.;
+    wnd.draw_frame();
+    let after = wnd.dispatcher.borrow().hit_widget();
+    assert_ne!(before, after);
+    assert_eq!(after, wnd.dispatcher.borrow().hit_test_walk());
+    assert_hits(&wnd);
+  }
+
   #[test]
   fn fix_transform_hit() {
     reset_test_env!();
@@ -810,4 +1296,103 @@ mod tests {
 
     assert_ne!(w.unwrap(), wnd.tree().root());
   }
+
+  #[test]
+  fn capture_pointer_out_of_bounds() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(vec![]);
+    let c_records = w_records.clone_writer();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(50., 50.),
+        on_pointer_down: move |e| {
+          assert!(GrabPointer::capture(e.current_target(), &e.window()));
+        },
+        on_pointer_move: move |e| $w_records.write().push(("move", e.global_pos())),
+        on_pointer_up: move |e| $w_records.write().push(("up", e.global_pos())),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |x: f64, y: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    };
+
+    move_to(10., 10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert!(GrabPointer::grabbing(&wnd).is_some());
+    c_records.write().clear();
+
+    // The cursor is out of the box, but the box still receives the events.
+    move_to(150., 150.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    let pos = Point::new(150., 150.);
+    assert_eq!(*records.read(), [("move", pos), ("up", pos)]);
+
+    // The capture is released after the pointer up.
+    assert!(GrabPointer::grabbing(&wnd).is_none());
+    c_records.write().clear();
+    move_to(160., 160.);
+    wnd.run_frame_tasks();
+    assert!(records.read().is_empty());
+  }
+
+  #[test]
+  fn hit_stack_by_paint_order() {
+    reset_test_env!();
+
+    let (ids, w_ids) = split_value(vec![]);
+    let item = move |name: &'static str, z_index: i32| {
+      let w_ids = w_ids.clone_writer();
+      fn_widget! {
+        @ZIndex {
+          z_index,
+          @MockBox {
+            size: Size::new(50., 50.),
+            on_mounted: move |e| $w_ids.write().push((e.id, name)),
+          }
+        }
+      }
+    };
+    // Three boxes stacked at the same position.
+    let w = fn_widget! {
+      @MockStack {
+        @ { item("red", 1) }
+        @ { item("green", -1) }
+        @ { item("blue", 0) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let mut dispatcher = wnd.dispatcher.borrow_mut();
+    dispatcher.info.cursor_pos = Point::new(10., 10.);
+    let stack = wnd.hit_stack(Point::new(10., 10.));
+    assert_eq!(stack.first().copied(), dispatcher.hit_test_walk());
+
+    let ids = ids.read();
+    let name_of = |wid: &WidgetId| {
+      let (_, name) = ids.iter().find(|(id, _)| id == wid)?;
+      Some(*name)
+    };
+    let names: Vec<_> = stack.iter().filter_map(name_of).collect();
+    assert_eq!(names, ["red", "blue", "green"]);
+    // The ancestors follow the overlapping boxes.
+    let tree = wnd.tree();
+    let ancestors = &stack[3..];
+    assert!(!ancestors.is_empty());
+    for w in ancestors {
+      assert!(w.ancestor_of(stack[0], tree));
+    }
+
+    // Nothing is hit outside the boxes, but their ancestors.
+    let stack = wnd.hit_stack(Point::new(80., 80.));
+    assert!(stack.iter().all(|w| name_of(w).is_none()));
+  }
 }
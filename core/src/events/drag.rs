@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+
+use smallvec::SmallVec;
+
+use crate::{impl_common_event_deref, prelude::*, window::DelayEvent};
+
+/// The distance, in logical pixels, the pointer must move after pressed before
+/// a drag gesture begins.
+pub const DRAG_THRESHOLD: f32 = 4.;
+
+/// Only the move samples in this duration before the pointer released are used
+/// to estimate the velocity of a drag gesture.
+const VELOCITY_SAMPLE_DURATION: Duration = Duration::from_millis(100);
+
+/// The event of a drag gesture.
+///
+/// A drag gesture begins when the pointer pressed on a widget and moves farther
+/// than [`DRAG_THRESHOLD`] from the pressed position. All the events of the
+/// gesture are dispatched to the widget under the initial press, even if the
+/// pointer leaves it, and bubble to its ancestors.
+#[derive(Debug)]
+pub struct DragEvent {
+  start: Point,
+  delta: Vector,
+  velocity: Vector,
+  pub common: CommonEvent,
+}
+
+impl_common_event_deref!(DragEvent);
+
+impl DragEvent {
+  /// The position where the pointer pressed to begin the drag gesture, in the
+  /// current target widget.
+  #[inline]
+  pub fn start_position(&self) -> Point { self.map_from_global(self.start) }
+
+  /// The pointer movement since the last drag event, it's always zero for the
+  /// `DragStart` and `DragEnd` events.
+  #[inline]
+  pub fn delta(&self) -> Vector { self.delta }
+
+  /// The estimated velocity of the pointer, in logical pixels per second, when
+  /// the drag ends. It's always zero for the `DragStart` and `DragUpdate`
+  /// events.
+  #[inline]
+  pub fn velocity(&self) -> Vector { self.velocity }
+
+  pub(crate) fn new(
+    start: Point, delta: Vector, velocity: Vector, id: WidgetId, wnd: &Window,
+  ) -> Self {
+    Self { start, delta, velocity, common: CommonEvent::new(id, wnd.tree) }
+  }
+}
+
+/// Trace the pointer from it pressed to released, to recognize a drag gesture.
+pub(crate) struct DragTracker {
+  wid: WidgetId,
+  press_pos: Point,
+  dragging: bool,
+  samples: VecDeque<(Instant, Point)>,
+}
+
+impl DragTracker {
+  pub(crate) fn new(wid: WidgetId, pos: Point, now: Instant) -> Self {
+    let mut samples = VecDeque::new();
+    samples.push_back((now, pos));
+    Self { wid, press_pos: pos, dragging: false, samples }
+  }
+
+  pub(crate) fn wid(&self) -> WidgetId { self.wid }
+
+  /// Trace the pointer moved to `pos` at `now`, return the drag events should
+  /// be emitted.
+  pub(crate) fn move_to(&mut self, pos: Point, now: Instant) -> SmallVec<[DelayEvent; 2]> {
+    let mut events = SmallVec::new();
+    let (wid, start) = (self.wid, self.press_pos);
+    if !self.dragging {
+      if (pos - self.press_pos).length() < DRAG_THRESHOLD {
+        return events;
+      }
+      self.dragging = true;
+      events.push(DelayEvent::DragStart { wid, start });
+    }
+
+    let last = self
+      .samples
+      .back()
+      .map_or(self.press_pos, |(_, p)| *p);
+    self.samples.push_back((now, pos));
+    // Keep the last sample out of the duration as the start of the movement.
+    while self
+      .samples
+      .get(1)
+      .is_some_and(|(at, _)| now.duration_since(*at) > VELOCITY_SAMPLE_DURATION)
+    {
+      self.samples.pop_front();
+    }
+    events.push(DelayEvent::DragUpdate { wid, start, delta: pos - last });
+
+    events
+  }
+
+  /// The pointer released at `now`, return the `DragEnd` event if the drag
+  /// gesture began.
+  pub(crate) fn end(self, now: Instant) -> Option<DelayEvent> {
+    self.dragging.then(|| DelayEvent::DragEnd {
+      wid: self.wid,
+      start: self.press_pos,
+      velocity: self.velocity(now),
+    })
+  }
+
+  fn velocity(&self, now: Instant) -> Vector {
+    let (Some((start_at, start)), Some((end_at, end))) =
+      (self.samples.front(), self.samples.back())
+    else {
+      return Vector::zero();
+    };
+
+    // The pointer stays still for a while before released.
+    if now.duration_since(*end_at) > VELOCITY_SAMPLE_DURATION {
+      return Vector::zero();
+    }
+    let secs = end_at.duration_since(*start_at).as_secs_f32();
+    if secs > 0. { (*end - *start) / secs } else { Vector::zero() }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  #[test]
+  fn drag_sequence() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(vec![]);
+    let (deltas, w_deltas) = split_value(vec![]);
+    let (velocity, w_velocity) = split_value(Vector::zero());
+    let (start, w_start) = split_value(Point::zero());
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_drag_start: move |e| {
+          *$w_start.write() = e.start_position();
+          $w_records.write().push("start");
+        },
+        on_drag_update: move |e| {
+          $w_deltas.write().push(e.delta());
+          $w_records.write().push("update");
+        },
+        on_drag_end: move |e| {
+          *$w_velocity.write() = e.velocity();
+          $w_records.write().push("end");
+        },
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let now = Rc::new(Cell::new(Instant::now()));
+    let clock = now.clone();
+    wnd.set_clock(move || clock.get());
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 10.).into() });
+    };
+
+    move_to(10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    now.set(now.get() + Duration::from_millis(5));
+    move_to(20.);
+    now.set(now.get() + Duration::from_millis(5));
+    // Leave the pressed widget, still dispatch to it.
+    move_to(130.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+
+    assert_eq!(*records.read(), ["start", "update", "update", "end"]);
+    assert_eq!(*start.read(), Point::new(10., 10.));
+    assert_eq!(*deltas.read(), [Vector::new(10., 0.), Vector::new(110., 0.)]);
+    assert!(velocity.read().x > 0.);
+    assert_eq!(velocity.read().y, 0.);
+  }
+
+  #[test]
+  fn no_drag_under_threshold() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(0);
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_drag_start: move |_| *$w_records.write() += 1,
+        on_drag_end: move |_| *$w_records.write() += 1,
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (2., 2.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+
+    assert_eq!(*records.read(), 0);
+  }
+}
@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 use indextree::{Arena, NodeId};
 
+use super::dispatcher::hit_test_at;
 use crate::{
   prelude::*,
   window::{DelayEvent, WindowId},
@@ -20,6 +21,30 @@ pub(crate) struct FocusManager {
   wnd_id: WindowId,
 }
 
+/// The direction on the screen to move the focus, see
+/// [`Window::request_focus_move`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+  Up,
+  Down,
+  Left,
+  Right,
+}
+
+/// The focus state of a window captured by [`Window::focus_snapshot`], restore
+/// it by [`Window::restore_focus`] to return the focus to where it was, such as
+/// after a transient popup closes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusState {
+  /// The focused widget and its ancestors, from the bottom up.
+  focus_widgets: Vec<WidgetId>,
+}
+
+impl FocusState {
+  /// The focused widget when the snapshot is taken.
+  pub fn focusing(&self) -> Option<WidgetId> { self.focus_widgets.first().copied() }
+}
+
 pub struct FocusHandle {
   wid: TrackId,
   wnd_id: WindowId,
@@ -480,6 +505,14 @@ impl FocusManager {
     self.refresh_focus(tree);
   }
 
+  /// Move the focus to the nearest focusable widget in the `dir` of the
+  /// focusing widget on the screen, keep the focus if there is no one.
+  pub fn move_focus(&mut self, dir: FocusDirection, tree: &WidgetTree) {
+    if let Some(wid) = self.spatial_neighbor(dir, tree) {
+      self.focus(wid, tree);
+    }
+  }
+
   pub fn focus(&mut self, wid: WidgetId, tree: &WidgetTree) {
     self.request_focus_to(Some(wid));
     self.refresh_focus(tree);
@@ -495,6 +528,81 @@ impl FocusManager {
   /// return the focusing widget.
   pub fn focusing(&self) -> Option<WidgetId> { self.focusing }
 
+  pub fn snapshot(&self) -> FocusState { FocusState { focus_widgets: self.focus_widgets.clone() } }
+
+  /// Restore the focus to the `state`. If the focused widget of the `state` is
+  /// removed, the nearest focusable ancestor that is still alive is focused, or
+  /// the focus is blurred if there is no one.
+  pub fn restore(&mut self, state: FocusState, tree: &WidgetTree) {
+    let focus = state
+      .focus_widgets
+      .iter()
+      .find(|wid| !wid.is_dropped(tree))
+      .and_then(|wid| self.focusable_ancestor(*wid, tree));
+    match focus {
+      Some(wid) => self.focus(wid, tree),
+      None => self.blur(tree),
+    }
+  }
+
+  /// Return the nearest focusable widget of the widget at the `pos`, the widget
+  /// itself or its ancestor. The `pos` is relative to the window.
+  pub fn focusable_at(&self, pos: Point, tree: &WidgetTree) -> Option<WidgetId> {
+    hit_test_at(tree, pos).and_then(|wid| self.focusable_ancestor(wid, tree))
+  }
+
+  /// Return the focusable widget that has the best score in the `dir` of the
+  /// focusing widget, see [`FocusDirection::score`]. The widgets with the same
+  /// score are chosen by the tab order, and then the tree order.
+  fn spatial_neighbor(&self, dir: FocusDirection, tree: &WidgetTree) -> Option<WidgetId> {
+    let focusing = self.focusing?;
+    let from = tree.global_rect(focusing)?;
+    let candidates: Vec<_> = self
+      .node_ids
+      .iter()
+      .filter(|(wid, node)| **wid != focusing && self.assert_get(**node).has_focus_node())
+      .filter(|(wid, _)| !wid.is_dropped(tree) && self.ignore_scope_id(**wid).is_none())
+      .filter_map(|(wid, node)| Some((*wid, *node, dir.score(&from, &tree.global_rect(*wid)?)?)))
+      .collect();
+    let best = candidates
+      .iter()
+      .map(|(.., score)| *score)
+      .min_by(f32::total_cmp)?;
+
+    // The positive tab indexes are ordered before the others.
+    let tab_order = |node| match self.tab_index(node) {
+      idx if idx > 0 => idx,
+      _ => i16::MAX,
+    };
+    let ties: Vec<_> = candidates
+      .into_iter()
+      .filter(|(.., score)| *score == best)
+      .map(|(wid, node, _)| (wid, tab_order(node)))
+      .collect();
+    if let [(wid, _)] = ties[..] {
+      return Some(wid);
+    }
+    let first_tab = ties.iter().map(|(_, tab)| *tab).min()?;
+    tree
+      .root()
+      .descendants(tree)
+      .find(|wid| ties.contains(&(*wid, first_tab)))
+  }
+
+  pub(crate) fn focusable_ancestor(&self, wid: WidgetId, tree: &WidgetTree) -> Option<WidgetId> {
+    wid
+      .ancestors(tree)
+      .find(|id| self.is_focusable(*id))
+  }
+
+  /// Return if the widget can be focused.
+  pub(crate) fn is_focusable(&self, wid: WidgetId) -> bool {
+    self
+      .node_ids
+      .get(&wid)
+      .is_some_and(|node| self.assert_get(*node).has_focus_node())
+  }
+
   pub fn refresh_focus(&mut self, tree: &WidgetTree) {
     let new_focus = self.next_focus(tree);
     if self.focus_widgets.first() != new_focus.as_ref() {
@@ -545,10 +653,37 @@ impl FocusManager {
   }
 }
 
+impl FocusDirection {
+  /// The score to move the focus from the `from` rect to the `to` rect, the
+  /// lower is the better. Return `None` if `to` is not totally in the
+  /// direction.
+  ///
+  /// The score is the distance in the direction plus the double distance in
+  /// the cross axis, so the widgets overlapping with `from` in the cross axis
+  /// are preferred.
+  fn score(self, from: &Rect, to: &Rect) -> Option<f32> {
+    let (gap, from_cross, to_cross) = match self {
+      FocusDirection::Up => (from.min_y() - to.max_y(), from.x_range(), to.x_range()),
+      FocusDirection::Down => (to.min_y() - from.max_y(), from.x_range(), to.x_range()),
+      FocusDirection::Left => (from.min_x() - to.max_x(), from.y_range(), to.y_range()),
+      FocusDirection::Right => (to.min_x() - from.max_x(), from.y_range(), to.y_range()),
+    };
+    if gap < 0. {
+      return None;
+    }
+    let cross_gap = (to_cross.start - from_cross.end)
+      .max(from_cross.start - to_cross.end)
+      .max(0.);
+    Some(gap + cross_gap * 2.)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::cell::RefCell;
 
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
   use super::*;
   use crate::{reset_test_env, test_helper::*};
 
@@ -856,6 +991,43 @@ mod tests {
     assert_eq!(*input.read(), "hello ribir");
   }
 
+  #[test]
+  fn click_to_focus() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        @MockBox {
+          tab_index: 0i16,
+          size: Size::new(50., 50.),
+          @MockBox { size: Size::new(20., 20.) }
+        }
+      }
+    });
+    wnd.draw_frame();
+    let focusable = wnd.widget_by_path(&[0, 0]);
+    assert_eq!(wnd.focusable_at(Point::new(10., 10.)), focusable);
+    assert_eq!(wnd.focusable_at(Point::new(80., 80.)), None);
+
+    let click = |pos: (f64, f64)| {
+      let device_id = unsafe { DeviceId::dummy() };
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: pos.into() });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    // Click on the child of the focusable widget.
+    click((10., 10.));
+    assert_eq!(wnd.focusing(), focusable);
+
+    // Click on the background.
+    click((80., 80.));
+    assert_eq!(wnd.focusing(), None);
+  }
+
   #[test]
   fn multi_focused_update() {
     reset_test_env!();
@@ -901,4 +1073,135 @@ mod tests {
     assert_eq!(*input.read(), "nice to see you");
     wnd.draw_frame();
   }
+
+  #[test]
+  fn restore_focus() {
+    reset_test_env!();
+
+    let (alive, w_alive) = split_value(true);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        tab_index: 0i16,
+        @MockBox { size: Size::new(10., 10.), auto_focus: true }
+        @MockBox { size: Size::new(10., 10.), tab_index: 0i16 }
+        @ { pipe! {
+          if *$alive {
+            @MockBox { size: Size::new(10., 10.), tab_index: 0i16 }.into_widget()
+          } else {
+            Void.into_widget()
+          }
+        }}
+      }
+    });
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    let parent = tree.content_root();
+    let first = parent.first_child(tree).unwrap();
+    let second = first.next_sibling(tree).unwrap();
+    let third = second.next_sibling(tree).unwrap();
+    let focus = |wnd: &TestWindow, wid| wnd.focus_mgr.borrow_mut().focus(wid, wnd.tree());
+    assert_eq!(wnd.focusing(), Some(first));
+
+    let state = wnd.focus_snapshot();
+    focus(&wnd, second);
+    assert_eq!(wnd.focusing(), Some(second));
+    wnd.restore_focus(state);
+    assert_eq!(wnd.focusing(), Some(first));
+
+    // The focused widget is removed, fall back to its focusable ancestor.
+    focus(&wnd, third);
+    let state = wnd.focus_snapshot();
+    focus(&wnd, second);
+    *w_alive.write() = false;
+    wnd.draw_frame();
+    wnd.restore_focus(state);
+    assert_eq!(wnd.focusing(), Some(parent));
+  }
+
+  #[test]
+  fn spatial_focus_move() {
+    reset_test_env!();
+
+    let (focused, w_focused) = split_value(0);
+    // A 3x3 grid of the focusable boxes, indexed by `row * 3 + col`.
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let w_focused = w_focused.clone_writer();
+        @MockStack {
+          @ {
+            (0..9).map(move |i| {
+              let w_focused = w_focused.clone_writer();
+              @MockBox {
+                size: Size::new(20., 20.),
+                anchor: Point::new((i % 3) as f32 * 30., (i / 3) as f32 * 30.),
+                tab_index: 0i16,
+                auto_focus: i == 0,
+                on_focus: move |_| *$w_focused.write() = i,
+              }
+            })
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    assert_eq!(*focused.read(), 0);
+
+    let mut move_to = |dir| {
+      wnd.request_focus_move(dir);
+      wnd.draw_frame();
+      *focused.read()
+    };
+    assert_eq!(move_to(FocusDirection::Down), 3);
+    assert_eq!(move_to(FocusDirection::Right), 4);
+    assert_eq!(move_to(FocusDirection::Right), 5);
+    assert_eq!(move_to(FocusDirection::Up), 2);
+    // No widget on the right, keep the focus.
+    assert_eq!(move_to(FocusDirection::Right), 2);
+    assert_eq!(move_to(FocusDirection::Left), 1);
+  }
+
+  #[test]
+  fn spatial_focus_tie() {
+    reset_test_env!();
+
+    // Two boxes have the same score below the focused one, the `right` is
+    // before the `left` in the tree.
+    let neighbor_below = |left_tab: i16| {
+      let (focused, w_focused) = split_value("");
+      let mut wnd = TestWindow::new_with_size(
+        fn_widget! {
+          let w_focused = w_focused.clone_writer();
+          let focusable = move |name, x: f32, y: f32, tab_index: i16| {
+            let w_focused = w_focused.clone_writer();
+            @MockBox {
+              size: Size::new(20., 20.),
+              anchor: Point::new(x, y),
+              tab_index,
+              auto_focus: name == "top",
+              on_focus: move |_| *$w_focused.write() = name,
+            }
+          };
+          @MockStack {
+            @ { focusable("top", 30., 0., 0) }
+            @ { focusable("right", 60., 30., 0) }
+            @ { focusable("left", 0., 30., left_tab) }
+          }
+        },
+        Size::new(100., 100.),
+      );
+      wnd.draw_frame();
+      assert_eq!(*focused.read(), "top");
+      wnd.request_focus_move(FocusDirection::Down);
+      wnd.draw_frame();
+      let name = *focused.read();
+      name
+    };
+
+    // The tree order.
+    assert_eq!(neighbor_below(0), "right");
+    // The tab order.
+    assert_eq!(neighbor_below(1), "left");
+  }
 }
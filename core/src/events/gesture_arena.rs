@@ -0,0 +1,102 @@
+use smallvec::SmallVec;
+
+/// The gestures recognized from a pointer sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Gesture {
+  Tap,
+  Drag,
+}
+
+/// The arena to resolve the gesture recognizers competing for the same pointer
+/// sequence.
+///
+/// The recognizers interested in the sequence enter the arena when the pointer
+/// is pressed. A recognizer accepts the sequence once it's sure about the
+/// gesture, such as the pointer moved farther than the drag threshold, then it
+/// wins the arena and the others lose. If no one accepts the sequence until the
+/// pointer is released, the earliest member still in the arena wins. Only the
+/// winner fires its gesture, so a sequence never fires both a drag and a tap.
+#[derive(Default)]
+pub(crate) struct GestureArena {
+  members: SmallVec<[Gesture; 2]>,
+  winner: Option<Gesture>,
+}
+
+impl GestureArena {
+  /// Open the arena for a new pointer sequence, the previous one is discarded.
+  pub(crate) fn open(&mut self, members: impl IntoIterator<Item = Gesture>) {
+    self.members = members.into_iter().collect();
+    self.winner = None;
+  }
+
+  /// The `gesture` accepts the sequence and wins the arena, return the losers
+  /// should be canceled. Nothing is returned if the `gesture` can't win, that
+  /// is it's not a member or the arena already has a winner.
+  pub(crate) fn accept(&mut self, gesture: Gesture) -> SmallVec<[Gesture; 1]> {
+    if self.winner.is_some() || !self.members.contains(&gesture) {
+      return SmallVec::new();
+    }
+    self.winner = Some(gesture);
+    self
+      .members
+      .drain(..)
+      .filter(|g| *g != gesture)
+      .collect()
+  }
+
+  /// Close the arena when the pointer is released, return the winner.
+  pub(crate) fn sweep(&mut self) -> Option<Gesture> {
+    let winner = self
+      .winner
+      .take()
+      .or_else(|| self.members.first().copied());
+    self.members.clear();
+    winner
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  #[test]
+  fn drag_wins_tap() {
+    reset_test_env!();
+
+    let (records, w_records) = split_value(vec![]);
+    let c_records = w_records.clone_writer();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_tap: move |_| $w_records.write().push("tap"),
+        on_pointer_cancel: move |_| $w_records.write().push("cancel"),
+        on_drag_start: move |_| $w_records.write().push("drag start"),
+        on_drag_end: move |_| $w_records.write().push("drag end"),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 10.).into() });
+    };
+
+    move_to(10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*records.read(), ["tap"]);
+
+    // The drag beyond the threshold wins, the tap is canceled instead of fired.
+    c_records.write().clear();
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    move_to(50.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*records.read(), ["cancel", "drag start", "drag end"]);
+  }
+}
@@ -0,0 +1,173 @@
+use ribir_geom::{Point, Rect, Transform};
+
+use crate::{prelude::*, widget_tree::WidgetTree};
+
+/// The tolerance of the bounds, so the rounding error of the transforms never
+/// skips a widget hit at its edge.
+const BOUNDS_TOLERANCE: f32 = 0.5;
+
+/// A bounds tree of the widgets to accelerate the hit test.
+///
+/// It stores the union of the hit bounds of every subtree in the window
+/// coordinate, so the hit test skips the subtrees out of the pointer without
+/// descending into them. It's rebuilt when a hit test needs it after the layout
+/// changes.
+#[derive(Default)]
+pub(crate) struct HitIndex {
+  layout_generation: Option<usize>,
+  /// The bounds of the subtree of a widget, `None` if the subtree may be hit
+  /// anywhere.
+  bounds: ahash::HashMap<WidgetId, Option<Rect>>,
+}
+
+impl HitIndex {
+  pub(crate) fn is_valid(&self, layout_generation: usize) -> bool {
+    self.layout_generation == Some(layout_generation)
+  }
+
+  pub(crate) fn rebuild(&mut self, tree: &WidgetTree) {
+    self.bounds.clear();
+    self.layout_generation = Some(tree.layout_generation);
+    self.collect(tree.root(), &Transform::identity(), tree);
+  }
+
+  /// Return false if neither the widget nor its descendants can be hit at the
+  /// `pos`, the `pos` is relative to the window. A widget not in the index may
+  /// be hit anywhere.
+  pub(crate) fn may_hit(&self, id: WidgetId, pos: Point) -> bool {
+    match self.bounds.get(&id) {
+      Some(Some(rect)) => rect
+        .inflate(BOUNDS_TOLERANCE, BOUNDS_TOLERANCE)
+        .contains(pos),
+      _ => true,
+    }
+  }
+
+  /// Collect the bounds of the subtree of `id`, the `to_global` maps the
+  /// coordinate of its parent to the window.
+  fn collect(&mut self, id: WidgetId, to_global: &Transform, tree: &WidgetTree) -> Option<Rect> {
+    let render = id.assert_get(tree);
+    let info = tree.store.layout_info(id);
+    let offset = info
+      .map_or(Point::zero(), |info| info.pos)
+      .to_vector();
+    let transform = render.get_transform();
+    let child_to_global = match info {
+      Some(_) => transform
+        .unwrap_or_else(Transform::identity)
+        .then_translate(offset)
+        .then(to_global),
+      None => *to_global,
+    };
+
+    let hit_bounds = info
+      .and_then(|info| info.size)
+      .map(|size| render.hit_bounds(size));
+    let mut bounds = match hit_bounds {
+      Some(Some(rect)) => {
+        let mut bounds = child_to_global.outer_transformed_rect(&rect);
+        // A wrapper may test the bounds before the transform is applied, keep
+        // the untransformed bounds too.
+        if transform.is_some() {
+          let rect = to_global.outer_transformed_rect(&rect.translate(offset));
+          bounds = bounds.union(&rect);
+        }
+        Some(bounds)
+      }
+      Some(None) => None,
+      None => Some(Rect::zero()),
+    };
+    for c in id.children(tree) {
+      let child = self.collect(c, &child_to_global, tree);
+      bounds = bounds.zip(child).map(|(a, b)| a.union(&b));
+    }
+
+    self.bounds.insert(id, bounds);
+    bounds
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    events::dispatcher::{hit_test_at, hit_test_in},
+    reset_test_env,
+    test_helper::*,
+  };
+
+  #[test]
+  fn index_hit_same_as_walk() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockStack {
+          @ {
+            (0..10).map(|row| @MockMulti {
+              anchor: Point::new(0., row as f32 * 20.),
+              transform: Transform::rotation(Angle::degrees(row as f32)),
+              @ {
+                (0..10).map(|i| @HitTestBehavior {
+                  hit_margin: EdgeInsets::all(i as f32),
+                  @MockBox { size: Size::new(8., 8.) }
+                })
+              }
+            })
+          }
+        }
+      },
+      Size::new(200., 200.),
+    );
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    let index = tree.hit_index();
+    let linear = HitIndex::default();
+    let mut hits = 0;
+    for x in (0..200).step_by(3) {
+      for y in (0..200).step_by(3) {
+        let pos = Point::new(x as f32, y as f32);
+        let hit = hit_test_in(tree, pos, &index);
+        assert_eq!(hit, hit_test_in(tree, pos, &linear));
+        hits += hit.is_some() as usize;
+      }
+    }
+    assert!(hits > 0);
+
+    // The rows far from the pointer are skipped.
+    let first_row = wnd.widget_by_path(&[0, 0]).unwrap();
+    let last_row = wnd.widget_by_path(&[0, 9]).unwrap();
+    let pos = Point::new(4., 4.);
+    assert!(index.may_hit(first_row, pos));
+    assert!(!index.may_hit(last_row, pos));
+  }
+
+  #[test]
+  fn rebuild_after_layout() {
+    reset_test_env!();
+
+    let (offset, w_offset) = split_value(0.);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockStack {
+          @MockBox {
+            anchor: pipe!(Point::new(*$offset, 0.)),
+            size: Size::new(10., 10.),
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    let pos = Point::new(55., 5.);
+    let child = wnd.widget_by_path(&[0, 0]).unwrap();
+    assert!(!wnd.tree().hit_index().may_hit(child, pos));
+
+    *w_offset.write() = 50.;
+    wnd.draw_frame();
+    assert!(wnd.tree().hit_index().may_hit(child, pos));
+    assert_eq!(hit_test_at(wnd.tree(), pos), Some(child));
+  }
+}
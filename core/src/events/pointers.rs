@@ -45,10 +45,23 @@ pub struct PointerEvent {
   /// Indicates if the pointer represents the primary pointer of this pointer
   /// type.
   pub is_primary: bool,
+  /// The number of successive taps, it's `1` for a single tap, `2` for a double
+  /// tap and so on. Always `0` for the pointer events that are not a tap.
+  tap_count: usize,
 
   pub common: CommonEvent,
 }
 
+impl PointerEvent {
+  /// Return the number of successive taps this tap event belongs to, `0` if
+  /// this is not a tap event.
+  ///
+  /// Successive taps are counted by the window when they happen within the
+  /// multi-tap thresholds, see `Window::set_multi_tap_threshold`.
+  #[inline]
+  pub fn tap_count(&self) -> usize { self.tap_count }
+}
+
 bitflags! {
   #[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
   pub struct MouseButtons: u8 {
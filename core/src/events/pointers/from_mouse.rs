@@ -22,9 +22,16 @@ impl PointerEvent {
       twist: 0.,
       point_type: PointerType::Mouse,
       is_primary: true,
+      tap_count: 0,
       common: CommonEvent::new(target, wnd.tree),
     }
   }
+
+  pub(crate) fn from_tap(target: WidgetId, count: usize, wnd: &Window) -> Self {
+    let mut e = Self::from_mouse(target, wnd);
+    e.tap_count = count;
+    e
+  }
 }
 
 impl From<MouseButton> for MouseButtons {
@@ -0,0 +1,156 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::prelude::*;
+
+/// A key pressed with a set of modifiers, such as `Ctrl+S`.
+///
+/// The character keys are case-insensitive, so `Ctrl+Shift+S` is described by
+/// the `s` key with the `CONTROL | SHIFT` modifiers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyCombination {
+  key: VirtualKey,
+  modifiers: ModifiersState,
+}
+
+/// The identity of a registered shortcut, use it to remove the shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortcutId(usize);
+
+/// The registry of the shortcuts of a window.
+///
+/// A shortcut is triggered by a key down event if no widget is focused, or the
+/// focused widget and its ancestors don't prevent the default of the event. So
+/// a focused widget that handles the key can suppress the shortcut by calling
+/// `prevent_default`.
+#[derive(Default)]
+pub struct Shortcuts {
+  next_id: usize,
+  handlers: Vec<(ShortcutId, KeyCombination, ShortcutHandler)>,
+}
+
+type ShortcutHandler = Rc<RefCell<dyn FnMut()>>;
+
+impl KeyCombination {
+  pub fn new(key: VirtualKey, modifiers: ModifiersState) -> Self {
+    let key = match key {
+      VirtualKey::Character(c) => VirtualKey::Character(c.to_lowercase().into()),
+      key => key,
+    };
+    let modifiers = modifiers
+      & (ModifiersState::CONTROL
+        | ModifiersState::SHIFT
+        | ModifiersState::ALT
+        | ModifiersState::SUPER);
+    Self { key, modifiers }
+  }
+
+  /// A combination of a character key with the `Ctrl` modifier.
+  pub fn ctrl(c: &str) -> Self {
+    Self::new(VirtualKey::Character(c.into()), ModifiersState::CONTROL)
+  }
+
+  pub fn key(&self) -> &VirtualKey { &self.key }
+
+  pub fn modifiers(&self) -> ModifiersState { self.modifiers }
+}
+
+impl Shortcuts {
+  /// Register a `handler` to be called when the `combination` is pressed.
+  pub fn register(
+    &mut self, combination: KeyCombination, handler: impl FnMut() + 'static,
+  ) -> ShortcutId {
+    let id = ShortcutId(self.next_id);
+    self.next_id += 1;
+    self
+      .handlers
+      .push((id, combination, Rc::new(RefCell::new(handler))));
+    id
+  }
+
+  /// Remove the shortcut, return `true` if it's registered.
+  pub fn remove(&mut self, id: ShortcutId) -> bool {
+    let len = self.handlers.len();
+    self.handlers.retain(|(i, ..)| *i != id);
+    len != self.handlers.len()
+  }
+
+  pub fn contains(&self, combination: &KeyCombination) -> bool {
+    self
+      .handlers
+      .iter()
+      .any(|(_, c, _)| c == combination)
+  }
+
+  /// Return the handlers of the `combination`. The handlers are cloned out, so
+  /// they can modify the shortcuts when they are called.
+  pub(crate) fn handlers_of(&self, combination: &KeyCombination) -> Vec<ShortcutHandler> {
+    self
+      .handlers
+      .iter()
+      .filter(|(_, c, _)| c == combination)
+      .map(|(.., h)| h.clone())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::ElementState;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn press_s(wnd: &TestWindow) {
+    for state in [ElementState::Pressed, ElementState::Released] {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::KeyS),
+        VirtualKey::Character("s".into()),
+        false,
+        KeyLocation::Standard,
+        state,
+      );
+    }
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn ctrl_s() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @MockBox { size: Size::zero() } });
+    wnd.draw_frame();
+    let (saved, w_saved) = split_value(0);
+    wnd
+      .shortcuts()
+      .register(KeyCombination::ctrl("s"), move || *w_saved.write() += 1);
+
+    press_s(&wnd);
+    assert_eq!(*saved.read(), 0);
+
+    wnd.processes_modifiers_changed(ModifiersState::CONTROL);
+    press_s(&wnd);
+    assert_eq!(*saved.read(), 1);
+  }
+
+  #[test]
+  fn suppressed_by_focused_widget() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::zero(),
+        auto_focus: true,
+        on_key_down: move |e| e.prevent_default(),
+      }
+    });
+    wnd.draw_frame();
+    let (saved, w_saved) = split_value(0);
+    wnd
+      .shortcuts()
+      .register(KeyCombination::ctrl("s"), move || *w_saved.write() += 1);
+
+    wnd.processes_modifiers_changed(ModifiersState::CONTROL);
+    press_s(&wnd);
+    assert_eq!(*saved.read(), 0);
+  }
+}
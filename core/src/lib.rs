@@ -3,6 +3,7 @@
 #[macro_use]
 extern crate bitflags;
 
+pub mod a11y;
 pub mod animation;
 pub mod builtin_widgets;
 pub mod clipboard;
@@ -49,6 +50,7 @@ pub mod prelude {
 
   pub use super::{
     Measure,
+    a11y::{A11yNode, A11yRole, Semantics},
     animation::*,
     builtin_widgets::*,
     class_names,
@@ -57,7 +59,7 @@ pub mod prelude {
     events::*,
     multi_class,
     overlay::{AutoClosePolicy, Overlay, OverlayStyle},
-    pipe::{BoxPipe, FinalChain, MapPipe, ModifiesPipe, Pipe},
+    pipe::{BoxPipe, FinalChain, FuturePipe, MapPipe, ModifiesPipe, Pipe},
     providers,
     query::*,
     state::*,
@@ -167,6 +167,25 @@ impl Overlay {
     );
   }
 
+  /// Show the overlay below the `anchor` widget, the top-left corner of the
+  /// overlay is aligned to the bottom-left corner of the anchor. It's useful
+  /// for the tooltips and the popups. If the overlay is showing, nothing will
+  /// happen.
+  pub fn show_below(&self, anchor: TrackId, wnd: Sc<Window>) {
+    if self.is_showing() {
+      return;
+    }
+    self.show_map(
+      move |w| {
+        FatObj::new(w)
+          .global_anchor_x(GlobalAnchorX::left_align_to(anchor.clone(), 0.))
+          .global_anchor_y(GlobalAnchorY::below(anchor.clone(), 0.))
+          .into_widget()
+      },
+      wnd,
+    );
+  }
+
   /// return whether the overlay is showing.
   pub fn is_showing(&self) -> bool { self.0.borrow().showing.is_some() }
 
@@ -362,4 +381,39 @@ mod tests {
     assert_eq!(*r_log.borrow(), &["mounted", "disposed"]);
     assert_eq!(wnd.tree().count(root), 3);
   }
+
+  #[test]
+  fn overlay_below_anchor() {
+    reset_test_env!();
+
+    let anchor = Rc::new(RefCell::new(None));
+    let c_anchor = anchor.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let mut anchor = @MockBox {
+        size: Size::new(40., 30.),
+        anchor: Anchor::left_top(10., 20.),
+      };
+      *c_anchor.borrow_mut() = Some($anchor.track_id());
+      anchor
+    });
+    wnd.draw_frame();
+
+    let overlay = Overlay::new(
+      fn_widget! { @MockBox { size: Size::new(20., 20.) } },
+      OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None },
+    );
+    let anchor = anchor.borrow_mut().take().unwrap();
+    overlay.show_below(anchor, wnd.0.clone());
+    wnd.draw_frame();
+
+    // The overlay is painted after the main tree, as the last child of the root.
+    let root = wnd.tree().root();
+    let overlay_id = root.last_child(wnd.tree()).unwrap();
+    let pos = wnd.map_to_global(Point::zero(), overlay_id);
+    assert_eq!(pos, Point::new(10., 50.));
+
+    overlay.close();
+    wnd.draw_frame();
+    assert!(!overlay.is_showing());
+  }
 }
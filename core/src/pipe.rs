@@ -1,10 +1,14 @@
 use std::{
-  cell::{Cell, UnsafeCell},
+  cell::{Cell, RefCell, UnsafeCell},
   convert::Infallible,
+  future::Future,
   ops::RangeInclusive,
+  pin::Pin,
   ptr::NonNull,
+  rc::Rc,
 };
 
+use futures::FutureExt;
 use ribir_algo::Sc;
 use rxrust::ops::box_it::BoxOp;
 use smallvec::SmallVec;
@@ -366,6 +370,65 @@ impl Pipe for ModifiesPipe {
   }
 }
 
+/// A pipe of the output of a future, its value is `None` until the future
+/// resolves, then it changes to the output.
+///
+/// The future is polled once when the pipe is unzipped, so a ready future
+/// provides its output as the initial value. Otherwise, it's spawned to the
+/// `AppCtx` scheduler. As a widget, it builds nothing until the future
+/// resolves, map the `None` to a placeholder widget if you need one.
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// async fn load() -> Widget<'static> { Void.into_widget() }
+///
+/// let _w = fn_widget! {
+///   FuturePipe::new(load()).map(|w| w.unwrap_or_else(|| Void.into_widget()))
+/// };
+/// ```
+pub struct FuturePipe<F>(F);
+
+impl<F: Future + 'static> FuturePipe<F> {
+  #[inline]
+  pub fn new(future: F) -> Self { Self(future) }
+}
+
+impl<F: Future + 'static> Pipe for FuturePipe<F> {
+  type Value = Option<F::Output>;
+
+  fn unzip(
+    self, scope: ModifyScope, init: Option<PipeWidgetBuildInit>,
+  ) -> (Self::Value, ValueStream<Self::Value>) {
+    let mut fut: Pin<Box<F>> = Box::pin(self.0);
+    if let Some(v) = fut.as_mut().now_or_never() {
+      return (Some(v), observable::empty().box_it());
+    }
+
+    let output = Rc::new(RefCell::new(None));
+    let c_output = output.clone();
+    let notifier: Subject<'static, ModifyScope, Infallible> = Subject::default();
+    let mut c_notifier = notifier.clone();
+    let _ = AppCtx::spawn_local(async move {
+      *c_output.borrow_mut() = Some(fut.await);
+      // Not complete the notifier, the widget pipe samples the modifies by the
+      // frame ticks, and a completed source drops the sampled one.
+      c_notifier.next(ModifyScope::BOTH);
+    });
+
+    let (_, stream) = ModifiesPipe::new(notifier.box_it()).unzip(scope, init);
+    let stream = stream.map(move |(s, _)| (s, output.borrow_mut().take()));
+    (None, stream.box_it())
+  }
+
+  #[inline]
+  fn box_unzip(
+    self: Box<Self>, scope: ModifyScope, init: Option<PipeWidgetBuildInit>,
+  ) -> (Self::Value, ValueStream<Self::Value>) {
+    (*self).unzip(scope, init)
+  }
+}
+
 impl<V: 'static> Pipe for Box<dyn Pipe<Value = V>> {
   type Value = V;
 
@@ -494,6 +557,14 @@ where
   fn into_widget(self) -> Widget<'static> { option_into_widget(self) }
 }
 
+impl<F, const M: usize> IntoWidget<'static, M> for FuturePipe<F>
+where
+  F: Future + 'static,
+  F::Output: IntoWidget<'static, M>,
+{
+  fn into_widget(self) -> Widget<'static> { option_into_widget(self) }
+}
+
 impl<const M: usize, V> IntoWidget<'static, M> for Box<dyn Pipe<Value = Option<V>>>
 where
   V: IntoWidget<'static, M>,
@@ -866,6 +937,30 @@ impl Render for PipeNode {
   }
 
   fn get_transform(&self) -> Option<Transform> { self.as_ref().data.get_transform() }
+
+  fn needs_layer(&self) -> bool { self.as_ref().data.needs_layer() }
+
+  fn debug_name(&self) -> &'static str { self.as_ref().data.debug_name() }
+
+  fn semantics(&self) -> Option<Semantics> { self.as_ref().data.semantics() }
+
+  fn z_index(&self) -> i32 { self.as_ref().data.z_index() }
+
+  fn hit_bounds(&self, size: Size) -> Option<Rect> { self.as_ref().data.hit_bounds(size) }
+
+  fn intrinsic_width(&self, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self
+      .as_ref()
+      .data
+      .intrinsic_width(kind, height, ctx)
+  }
+
+  fn intrinsic_height(&self, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self
+      .as_ref()
+      .data
+      .intrinsic_height(kind, width, ctx)
+  }
 }
 
 #[derive(Clone)]
@@ -1505,6 +1600,35 @@ mod tests {
     };
   }
 
+  #[test]
+  fn coalesce_modifies_in_a_frame() {
+    reset_test_env!();
+    let size = Stateful::new(Size::zero());
+    let c_size = size.clone_writer();
+    let build_cnt = Rc::new(Cell::new(0));
+    let c_build_cnt = build_cnt.clone();
+    let w = fn_widget! {
+      let c_build_cnt = c_build_cnt.clone();
+      let w = pipe!(*$size).map(move |size| {
+        c_build_cnt.set(c_build_cnt.get() + 1);
+        MockBox { size }.into_widget()
+      });
+      w.into_widget()
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!(build_cnt.get(), 1);
+
+    for i in 1..=100 {
+      *c_size.write() = Size::new(i as f32, i as f32);
+    }
+    wnd.draw_frame();
+    // The pipe is rebuilt only once with the last value.
+    assert_eq!(build_cnt.get(), 2);
+    wnd.assert_root_size(Size::new(100., 100.));
+  }
+
   #[test]
   fn multi_pipe_gen_single_pipe() {
     reset_test_env!();
@@ -1770,6 +1894,37 @@ mod tests {
     assert_eq!(&*w2.read(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
   }
 
+  #[test]
+  fn future_pipe_widget() {
+    reset_test_env!();
+
+    let (tx, rx) = futures::channel::oneshot::channel::<Size>();
+    let rx = futures::FutureExt::shared(rx);
+    let widget = fn_widget! {
+      let rx = rx.clone();
+      @MockMulti {
+        @ { FuturePipe::new(async { MockBox { size: Size::new(10., 10.) } }) }
+        @ {
+          FuturePipe::new(async move { MockBox { size: rx.await.unwrap() } })
+            .map(|w| w.unwrap_or(MockBox { size: Size::new(1., 1.) }))
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(widget);
+    wnd.draw_frame();
+    // The ready future is built directly, the pending one shows the placeholder.
+    let size_of = |idx| wnd.layout_info_by_path(&[0, idx]).unwrap().size;
+    assert_eq!(size_of(0), Some(Size::new(10., 10.)));
+    assert_eq!(size_of(1), Some(Size::new(1., 1.)));
+
+    tx.send(Size::new(20., 20.)).unwrap();
+    wnd.draw_frame();
+    let size_of = |idx| wnd.layout_info_by_path(&[0, idx]).unwrap().size;
+    assert_eq!(size_of(0), Some(Size::new(10., 10.)));
+    assert_eq!(size_of(1), Some(Size::new(20., 20.)));
+  }
+
   #[test]
   fn fix_pipe_in_multi_pipe_not_first() {
     reset_test_env!();
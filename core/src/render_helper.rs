@@ -49,6 +49,31 @@ where
 
   #[inline]
   fn get_transform(&self) -> Option<Transform> { self.proxy().get_transform() }
+
+  #[inline]
+  fn needs_layer(&self) -> bool { self.proxy().needs_layer() }
+
+  #[inline]
+  fn debug_name(&self) -> &'static str { self.proxy().debug_name() }
+
+  #[inline]
+  fn semantics(&self) -> Option<Semantics> { self.proxy().semantics() }
+
+  #[inline]
+  fn z_index(&self) -> i32 { self.proxy().z_index() }
+
+  #[inline]
+  fn hit_bounds(&self, size: Size) -> Option<Rect> { self.proxy().hit_bounds(size) }
+
+  #[inline]
+  fn intrinsic_width(&self, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self.proxy().intrinsic_width(kind, height, ctx)
+  }
+
+  #[inline]
+  fn intrinsic_height(&self, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self.proxy().intrinsic_height(kind, width, ctx)
+  }
 }
 
 impl<R: Render> RenderProxy for RefCell<R> {
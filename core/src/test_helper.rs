@@ -46,6 +46,21 @@ impl TestWindow {
     Self::new_wnd(root, Some(size))
   }
 
+  /// Create a window that fits the size of its content. The content is laid out
+  /// under a loose clamp bounded by `max`, then the window is resized to the
+  /// content size and drawn, it's useful to export a widget as an image.
+  pub fn fit_content(root: impl Into<GenWidget>, max: Size) -> Self {
+    let mut wnd = Self::new_wnd(root, Some(max));
+    wnd.draw_frame();
+    let content_size = wnd
+      .layout_info_by_path(&[0])
+      .and_then(|info| info.size)
+      .unwrap_or_default();
+    wnd.request_resize(content_size);
+    wnd.draw_frame();
+    wnd
+  }
+
   #[track_caller]
   pub fn assert_root_size(&self, size: Size) {
     let info = self.layout_info_by_path(&[0]).unwrap();
@@ -69,14 +84,10 @@ impl TestWindow {
   /// [0, 1, 2] the first node at the root level (must be 0), then down to its
   /// second child, then down to third child.
   pub fn layout_info_by_path(&self, path: &[usize]) -> Option<LayoutInfo> {
-    let tree = self.0.tree();
-    let mut node = tree.root();
-    for (level, idx) in path[..].iter().enumerate() {
-      node = node.children(tree).nth(*idx).unwrap_or_else(|| {
-        panic!("node no exist: {:?}", &path[0..level]);
-      });
-    }
-    tree.store.layout_info(node).cloned()
+    let node = self
+      .widget_by_path(path)
+      .unwrap_or_else(|| panic!("node no exist: {path:?}"));
+    self.0.tree().store.layout_info(node).cloned()
   }
 
   pub fn take_last_frame(&mut self) -> Option<Frame> {
@@ -36,3 +36,18 @@ pub enum FrameMsg {
   /// receive this message.
   Finish(Instant),
 }
+
+/// The time spent on each phase of a drawn frame, in microseconds. See
+/// [`Window::set_frame_callback`](crate::window::Window::set_frame_callback).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameTiming {
+  /// The time to run the frame tasks and regenerate the widgets before the
+  /// layout.
+  pub repair_us: u64,
+  /// The time to layout the widget tree.
+  pub layout_us: u64,
+  /// The time to paint the widget tree to the paint commands.
+  pub paint_us: u64,
+  /// The time to submit the paint commands to the shell window.
+  pub present_us: u64,
+}
@@ -23,6 +23,18 @@ pub struct HitTest {
   pub can_hit_child: bool,
 }
 
+/// The kind of the intrinsic size of a widget, the size it would like to be
+/// independent of the constraints from its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntrinsicSize {
+  /// The smallest size the widget can be without overflowing its content, for
+  /// example, the width of the longest word of a text.
+  MinContent,
+  /// The size the widget prefers to be if it has unlimited space, for example,
+  /// the width of a text without any wrap.
+  MaxContent,
+}
+
 /// RenderWidget is a widget which want to paint something or do a layout to
 /// calc itself size and update children positions.
 pub trait Render: 'static {
@@ -71,6 +83,60 @@ pub trait Render: 'static {
 
   /// Return a transform to map the coordinate to parent coordinate.
   fn get_transform(&self) -> Option<Transform> { None }
+
+  /// Whether the widget and its descendants need to be painted to a layer and
+  /// composited into the parent as a whole, such as a group opacity.
+  fn needs_layer(&self) -> bool { false }
+
+  /// The type name of the widget, used to debug the widget tree.
+  fn debug_name(&self) -> &'static str { std::any::type_name::<Self>() }
+
+  /// The semantics the widget contributes to the accessibility tree, the
+  /// widget is not in the tree if it's `None`. See [`Window::a11y_tree`].
+  fn semantics(&self) -> Option<Semantics> { None }
+
+  /// The paint order of the widget among its siblings, the siblings are
+  /// painted by the ascending `z_index` and then the tree order, and hit in
+  /// the reverse order.
+  fn z_index(&self) -> i32 { 0 }
+
+  /// The bounds out of which the widget is never hit, in its coordinate of a
+  /// box with `size`, or `None` if it may be hit anywhere. The hit test skips
+  /// a subtree if the pointer is out of the bounds of all its widgets.
+  ///
+  /// It's the box of the widget by default, override it if the `hit_test` may
+  /// hit out of the box.
+  fn hit_bounds(&self, size: Size) -> Option<Rect> { Some(Rect::from_size(size)) }
+
+  /// Return the intrinsic width of the widget, the `height` is the height the
+  /// widget will be laid out with if it's known.
+  ///
+  /// By default, the widget is measured by a layout under unbounded
+  /// constraints, so the `MinContent` and `MaxContent` are the same. The
+  /// layout of a measurement queried by the `LayoutCtx` is discarded after the
+  /// query, and no `PerformedLayout` event fires for it.
+  fn intrinsic_width(&self, _: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let mut clamp = BoxClamp::UNLIMITED;
+    if let Some(height) = height {
+      clamp = clamp.with_fixed_height(height);
+    }
+    self.perform_layout(clamp, ctx).width
+  }
+
+  /// Return the intrinsic height of the widget, the `width` is the width the
+  /// widget will be laid out with if it's known.
+  ///
+  /// By default, the widget is measured by a layout under unbounded
+  /// constraints, so the `MinContent` and `MaxContent` are the same. The
+  /// layout of a measurement queried by the `LayoutCtx` is discarded after the
+  /// query, and no `PerformedLayout` event fires for it.
+  fn intrinsic_height(&self, _: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    let mut clamp = BoxClamp::UNLIMITED;
+    if let Some(width) = width {
+      clamp = clamp.with_fixed_width(width);
+    }
+    self.perform_layout(clamp, ctx).height
+  }
 }
 
 /// The common type of all widget can convert to.
@@ -1,4 +1,8 @@
-use std::{cell::RefCell, cmp::Reverse, mem::MaybeUninit};
+use std::{
+  cell::{Ref, RefCell},
+  cmp::Reverse,
+  mem::MaybeUninit,
+};
 
 pub mod widget_id;
 use indextree::Arena;
@@ -8,7 +12,10 @@ mod layout_info;
 pub use layout_info::*;
 
 use self::widget::widget_id::new_node;
-use crate::{overlay::ShowingOverlays, prelude::*, render_helper::PureRender, window::WindowId};
+use crate::{
+  events::hit_index::HitIndex, overlay::ShowingOverlays, prelude::*, render_helper::PureRender,
+  window::WindowId,
+};
 
 /// This enum defines the dirty phases of the widget.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -32,8 +39,17 @@ pub(crate) struct WidgetTree {
   pub(crate) store: LayoutStore,
   pub(crate) dirty_set: DirtySet,
   pub(crate) dummy_id: WidgetId,
+  /// Increased every time the dirty widgets are processed or a widget is moved,
+  /// any cached result that depends on the layout of the tree is stale if it's
+  /// changed.
+  pub(crate) layout_generation: usize,
+  pub(crate) layout_cancel: LayoutCancelToken,
+  hit_index: RefCell<HitIndex>,
+  before_flush: Vec<BeforeFlushHook>,
 }
 
+type BeforeFlushHook = Box<dyn FnMut(&[WidgetId])>;
+
 /// A tool that help you to mark a widget as dirty
 #[derive(Clone)]
 pub(crate) struct DirtyMarker(DirtySet);
@@ -42,7 +58,7 @@ type TreeArena = Arena<Box<dyn RenderQueryable>>;
 
 impl WidgetTree {
   pub fn init(&mut self, wnd: &Window, content: GenWidget) -> WidgetId {
-    self.root.0.remove_subtree(&mut self.arena);
+    self.free_subtree(self.root);
     let _guard = BuildCtx::init(BuildCtx::empty(wnd.tree));
 
     let theme = AppCtx::app_theme().clone_writer();
@@ -82,6 +98,9 @@ impl WidgetTree {
 
   /// Do the work of computing the layout for all node which need, Return if any
   /// node has really computing the layout.
+  ///
+  /// The layout returns early if it's canceled by the `layout_cancel` token,
+  /// and the tree is left dirty.
   pub(crate) fn layout(&mut self, win_size: Size) {
     loop {
       let Some(mut needs_layout) = self.layout_list() else {
@@ -101,6 +120,20 @@ impl WidgetTree {
           let mut ctx = LayoutCtx::new(wid, self);
           ctx.perform_layout(clamp);
         }
+
+        if self.layout_cancel.take() {
+          // Drop the unfinished layout, and keep the widgets not laid out yet dirty, so
+          // they will be laid out in the next pass.
+          let marker = self.dirty_marker();
+          self
+            .dirty_set
+            .borrow_mut()
+            .insert(wid, DirtyPhase::LayoutSubtree);
+          for w in needs_layout {
+            marker.mark(w, DirtyPhase::Layout);
+          }
+          return;
+        }
       }
     }
   }
@@ -145,11 +178,89 @@ impl WidgetTree {
       self.display_node(prefix, c, display)
     });
   }
+
+  /// Dump the `sub_tree` to a graph in the Graphviz DOT language. Every node is
+  /// labeled with the type name of its render object and linked from its
+  /// parent.
+  pub(crate) fn to_dot(&self, sub_tree: WidgetId) -> String {
+    let mut dot = String::from("digraph WidgetTree {\n  node [shape=box];\n");
+    let mut indices = ahash::HashMap::default();
+    for (idx, wid) in sub_tree.descendants(self).enumerate() {
+      indices.insert(wid, idx);
+      let name = short_type_name(wid.assert_get(self).debug_name());
+      dot.push_str(&format!("  n{idx} [label=\"{name}\"];\n"));
+      let parent = wid.parent(self).filter(|_| wid != sub_tree);
+      if let Some(parent) = parent {
+        dot.push_str(&format!("  n{} -> n{idx};\n", indices[&parent]));
+      }
+    }
+    dot.push_str("}\n");
+    dot
+  }
+
+  /// Dump the layout result of the `sub_tree` in a stable text format, one
+  /// line for every node with its index path from the `sub_tree`, the type name
+  /// of its render object and its rect in the window coordinate.
+  pub(crate) fn layout_snapshot(&self, sub_tree: WidgetId) -> String {
+    let mut snapshot = String::new();
+    for wid in sub_tree.descendants(self) {
+      let path = wid
+        .index_path_from(sub_tree, self)
+        .unwrap_or_default();
+      let name = short_type_name(wid.assert_get(self).debug_name());
+      let rect = match self.global_rect(wid) {
+        Some(Rect { origin, size }) => {
+          format!("({}, {}, {}x{})", origin.x, origin.y, size.width, size.height)
+        }
+        None => "(not laid out)".to_string(),
+      };
+      snapshot.push_str(&format!("{path:?} {name} {rect}\n"));
+    }
+    snapshot
+  }
+
+  /// Add a hook to observe the changed widgets, it's called with the alive
+  /// dirty widgets every time before they are processed.
+  pub(crate) fn on_before_flush(&mut self, f: impl FnMut(&[WidgetId]) + 'static) {
+    self.before_flush.push(Box::new(f));
+  }
+
+  fn emit_before_flush(&mut self) {
+    if self.before_flush.is_empty() {
+      return;
+    }
+    let changed: Vec<_> = self
+      .dirty_set
+      .borrow()
+      .keys()
+      .filter(|id| !id.is_dropped(self))
+      .copied()
+      .collect();
+    // Take the hooks out, so a hook can add a new hook or mark the widgets dirty.
+    let mut hooks = std::mem::take(&mut self.before_flush);
+    hooks.iter_mut().for_each(|f| f(&changed));
+    hooks.append(&mut self.before_flush);
+    self.before_flush = hooks;
+  }
+
+  /// The hit index of the current layout, it's rebuilt if the layout is
+  /// changed since the last build.
+  pub(crate) fn hit_index(&self) -> Ref<HitIndex> {
+    let generation = self.layout_generation;
+    if !self.hit_index.borrow().is_valid(generation) {
+      self.hit_index.borrow_mut().rebuild(self);
+    }
+    self.hit_index.borrow()
+  }
+
   pub(crate) fn layout_list(&mut self) -> Option<Vec<WidgetId>> {
     if self.dirty_set.borrow().is_empty() {
       return None;
     }
 
+    self.emit_before_flush();
+
+    self.layout_generation = self.layout_generation.wrapping_add(1);
     let mut needs_layout = vec![];
 
     for (id, dirty) in self.dirty_set.borrow_mut().drain() {
@@ -211,7 +322,12 @@ impl WidgetTree {
 
   pub(crate) fn remove_subtree(&mut self, id: WidgetId) {
     assert_ne!(id, self.root(), "You should detach the root widget before remove it.");
+    self.free_subtree(id);
+  }
 
+  /// Remove the nodes of the subtree from the arena, so their slots can be
+  /// reused, and drop their layout information.
+  fn free_subtree(&mut self, id: WidgetId) {
     id.0.descendants(&self.arena).for_each(|id| {
       self.store.remove(WidgetId(id));
     });
@@ -244,7 +360,18 @@ impl WidgetTree {
     let dummy_id = new_node(&mut arena, Box::new(PureRender(Void)));
     dummy_id.0.remove(&mut arena);
 
-    Self { root, dummy_id, wnd_id, arena, store: <_>::default(), dirty_set: <_>::default() }
+    Self {
+      root,
+      dummy_id,
+      wnd_id,
+      arena,
+      store: <_>::default(),
+      dirty_set: <_>::default(),
+      layout_generation: 0,
+      layout_cancel: <_>::default(),
+      hit_index: <_>::default(),
+      before_flush: vec![],
+    }
   }
 }
 
@@ -282,6 +409,22 @@ impl Render for Root {
   }
 }
 
+/// Strip the module paths of a type name, e.g.
+/// `ribir_core::pipe::PipeNode<alloc::string::String>` to `PipeNode<String>`.
+fn short_type_name(name: &str) -> String {
+  let mut short = String::new();
+  let mut segments = name.split("::").peekable();
+  while let Some(seg) = segments.next() {
+    if segments.peek().is_some() {
+      // Drop the module name at the end of the segment.
+      short.push_str(seg.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_'));
+    } else {
+      short.push_str(seg);
+    }
+  }
+  short
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -332,6 +475,34 @@ mod tests {
     assert_eq!(size, expect_size);
   }
 
+  #[test]
+  fn before_flush_hook() {
+    reset_test_env!();
+
+    let (size, w_size) = split_value(Size::new(10., 10.));
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: pipe!(*$size) }
+        @MockBox { size: Size::new(10., 10.) }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let (changed, w_changed) = split_value(vec![]);
+    wnd
+      .tree_mut()
+      .on_before_flush(move |ids| w_changed.write().push(ids.to_vec()));
+    *w_size.write() = Size::new(20., 20.);
+    wnd.draw_frame();
+
+    let first = wnd.widget_by_path(&[0, 0]).unwrap();
+    let second = wnd.widget_by_path(&[0, 1]).unwrap();
+    let changed = changed.read();
+    assert!(changed.iter().any(|ids| ids.contains(&first)));
+    assert!(!changed.iter().any(|ids| ids.contains(&second)));
+  }
+
   #[test]
   fn fix_dropped_child_expr_widget() {
     reset_test_env!();
@@ -492,6 +663,74 @@ mod tests {
     assert_eq!(len_1_widget, len_100_widget);
   }
 
+  #[test]
+  fn reuse_slots_of_dropped_widgets() {
+    reset_test_env!();
+
+    let trigger = Stateful::new(0);
+    let c_trigger = trigger.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @ {
+          pipe!(*$trigger).map(|_| {
+            (0..10).map(|_| MockBox { size: Size::new(1., 1.) })
+          })
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let mut churn = || {
+      for _ in 0..50 {
+        *c_trigger.write() += 1;
+        wnd.draw_frame();
+      }
+      let tree = wnd.tree();
+      (tree.arena.len(), tree.store.len())
+    };
+    // The dropped widgets free their slots, so the arena doesn't grow.
+    let counts = churn();
+    assert_eq!(churn(), counts);
+  }
+
+  #[test]
+  fn cancel_layout() {
+    reset_test_env!();
+
+    #[derive(Default)]
+    struct CancelOnce(std::cell::Cell<bool>);
+
+    impl Render for CancelOnce {
+      fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+        if !self.0.replace(true) {
+          ctx.window().layout_cancel_token().cancel();
+        }
+        clamp.min
+      }
+    }
+
+    let wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @ { CancelOnce::default() }
+        @MockBox { size: Size::new(20., 20.) }
+      }
+    });
+    let tree = wnd.tree_mut();
+    tree.layout(Size::new(100., 100.));
+
+    // The layout is aborted in the second child, and left dirty to be redone.
+    assert!(tree.is_dirty());
+    assert!(!wnd.layout_cancel_token().is_canceled());
+    let last = wnd.widget_by_path(&[0, 2]).unwrap();
+    assert!(tree.store.layout_box_size(last).is_none());
+
+    tree.layout(Size::new(100., 100.));
+    assert!(!tree.is_dirty());
+    assert_eq!(wnd.layout_info_by_path(&[0, 0]).unwrap().size, Some(Size::new(10., 10.)));
+    assert_eq!(wnd.layout_info_by_path(&[0, 2]).unwrap().size, Some(Size::new(20., 20.)));
+  }
+
   #[test]
   fn paint_phase_dirty() {
     reset_test_env!();
@@ -533,4 +772,123 @@ mod tests {
     assert_eq!(*layout_cnt.read(), 1);
     assert_eq!(c_paint_cnt.read().paint_cnt.get(), 2);
   }
+
+  #[test]
+  fn widget_path_round_trip() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @MockMulti {
+          @MockBox { size: Size::new(20., 20.) }
+          @MockBox { size: Size::new(30., 30.) }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let id = wnd.widget_by_path(&[0, 1, 1]).unwrap();
+    assert_eq!(wnd.widget_size(id), Some(Size::new(30., 30.)));
+    assert_eq!(wnd.widget_path(id), Some(vec![0, 1, 1]));
+
+    let root = wnd.tree().root();
+    assert_eq!(wnd.widget_by_path(&[]), Some(root));
+    assert_eq!(wnd.widget_path(root), Some(vec![]));
+
+    // The out of range indices.
+    assert_eq!(wnd.widget_by_path(&[0, 2]), None);
+    assert_eq!(wnd.widget_by_path(&[0, 0, 0]), None);
+
+    // The paths relative to a subtree.
+    let sub = wnd.widget_by_path(&[0, 1]).unwrap();
+    assert_eq!(WidgetId::from_path(sub, &[1], &wnd), Some(id));
+    assert_eq!(id.path_from(sub, &wnd), Some(vec![1]));
+    let first = wnd.widget_by_path(&[0, 0]).unwrap();
+    assert_eq!(first.path_from(sub, &wnd), None);
+  }
+
+  #[test]
+  fn ancestor_of_type() {
+    reset_test_env!();
+
+    struct Marker;
+    let mut wnd = TestWindow::new(fn_widget! {
+      let outer = @MockBox {
+        size: Size::new(100., 100.),
+        @MockMulti {
+          @MockBox { size: Size::new(10., 10.) }
+        }
+      };
+      outer
+        .into_widget()
+        .attach_data(Box::new(Queryable(Marker)))
+    });
+    wnd.draw_frame();
+
+    let outer = wnd.widget_by_path(&[0]).unwrap();
+    let leaf = wnd.widget_by_path(&[0, 0, 0]).unwrap();
+    assert_eq!(leaf.ancestor_of_type::<Marker>(&wnd), Some(outer));
+    // The widget itself is not its ancestor.
+    assert_eq!(outer.ancestor_of_type::<Marker>(&wnd), None);
+  }
+
+  #[test]
+  fn dump_to_dot() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @MockBox { size: Size::new(20., 20.) }
+      }
+    });
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    let dot = tree.to_dot(tree.content_root());
+    assert!(dot.starts_with("digraph WidgetTree {"));
+    assert!(dot.contains("n0 [label=\"MockMulti\"];"));
+    assert!(dot.contains("n1 [label=\"MockBox\"];"));
+    assert!(dot.contains("n2 [label=\"MockBox\"];"));
+    assert!(dot.contains("n0 -> n1;"));
+    assert!(dot.contains("n0 -> n2;"));
+    assert!(!dot.contains("n1 -> n2;"));
+  }
+
+  #[test]
+  fn layout_snapshot() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @MockMulti {
+          @MockBox { size: Size::new(20.5, 20.) }
+          @MockBox { size: Size::new(30., 30.) }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    assert_eq!(
+      tree.layout_snapshot(tree.content_root()),
+      "[] MockMulti (0, 0, 60.5x30)
+[0] MockBox (0, 0, 10x10)
+[1] MockMulti (10, 0, 50.5x30)
+[1, 0] MockBox (10, 0, 20.5x20)
+[1, 1] MockBox (30.5, 0, 30x30)
+"
+    );
+  }
+
+  #[test]
+  fn strip_type_name() {
+    assert_eq!(short_type_name("ribir_core::test_helper::MockBox"), "MockBox");
+    assert_eq!(
+      short_type_name("ribir_core::pipe::PipeNode<alloc::string::String, u8>"),
+      "PipeNode<String, u8>"
+    );
+  }
 }
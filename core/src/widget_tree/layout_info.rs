@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use ribir_geom::ZERO_SIZE;
+use ribir_geom::{Rect, ZERO_SIZE, rect_corners};
 
 use super::{Lerp, WidgetId, WidgetTree};
 use crate::prelude::{INFINITY_SIZE, Point, Size};
@@ -114,6 +114,18 @@ impl LayoutStore {
 
   pub(crate) fn remove(&mut self, id: WidgetId) -> Option<LayoutInfo> { self.data.remove(&id) }
 
+  /// Restore the layout info of `id` to `info`, remove it if `info` is `None`.
+  pub(crate) fn restore(&mut self, id: WidgetId, info: Option<LayoutInfo>) {
+    match info {
+      Some(info) => {
+        self.data.insert(id, info);
+      }
+      None => {
+        self.data.remove(&id);
+      }
+    }
+  }
+
   pub(crate) fn layout_box_size(&self, id: WidgetId) -> Option<Size> {
     self.layout_info(id).and_then(|info| info.size)
   }
@@ -166,6 +178,14 @@ impl WidgetTree {
       .fold(pos, |pos, p| self.map_to_parent(p, pos))
   }
 
+  /// Return the axis-aligned bounding box of the widget box in the window
+  /// coordinate, the transforms of the widget and its ancestors are applied.
+  pub(crate) fn global_rect(&self, widget: WidgetId) -> Option<Rect> {
+    let size = self.store.layout_box_size(widget)?;
+    let corners = rect_corners(&Rect::from_size(size)).map(|p| self.map_to_global(p, widget));
+    Some(Rect::from_points(corners))
+  }
+
   pub(crate) fn map_from_global(&self, pos: Point, widget: WidgetId) -> Point {
     let stack = widget.ancestors(self).collect::<Vec<_>>();
     stack
@@ -304,6 +324,40 @@ mod tests {
     assert_eq!([3, 2, 1, 3, 2, 1], &**layout_order.read());
   }
 
+  #[test]
+  fn global_rect() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @OffsetBox {
+        size: Size::new(100., 100.),
+        offset: Point::new(10., 20.),
+        @MockBox {
+          size: Size::new(50., 50.),
+          transform: Transform::translation(5., 5.),
+          @MockBox {
+            size: Size::new(20., 10.),
+            transform: Transform::rotation(Angle::degrees(90.)),
+          }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let translated = wnd.widget_by_path(&[0, 0]).unwrap();
+    assert_eq!(
+      wnd.widget_global_rect(translated),
+      Some(Rect::new(Point::new(15., 25.), Size::splat(50.)))
+    );
+
+    // The rotated box is the bounding box of the rotated rect.
+    let rotated = wnd.widget_by_path(&[0, 0, 0]).unwrap();
+    let rect = wnd.widget_global_rect(rotated).unwrap();
+    assert!((rect.origin - Point::new(5., 25.)).length() < 1e-3);
+    assert!((rect.size.width - 10.).abs() < 1e-3);
+    assert!((rect.size.height - 20.).abs() < 1e-3);
+  }
+
   #[test]
   fn relayout_size() {
     reset_test_env!();
@@ -365,4 +419,41 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*cnt.read(), 2);
   }
+
+  #[test]
+  fn fixed_subtree_not_relayout_by_sibling() {
+    reset_test_env!();
+
+    let (fixed_cnt, w_fixed_cnt) = split_value(0);
+    let (sibling_cnt, w_sibling_cnt) = split_value(0);
+    let (size, w_size) = split_value(Size::zero());
+    let w = fn_widget! {
+      @MockMulti {
+        @OffsetBox {
+          size: Size::new(50., 50.),
+          offset: Point::zero(),
+          @MockBox {
+            size: Size::new(20., 20.),
+            on_performed_layout: move |_| *$w_fixed_cnt.write() += 1,
+          }
+        }
+        @MockBox {
+          size: pipe!(*$size),
+          on_performed_layout: move |_| *$w_sibling_cnt.write() += 1,
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!((*fixed_cnt.read(), *sibling_cnt.read()), (1, 1));
+
+    // The parent relayout for the sibling, but the fixed subtree reuses its
+    // size because the clamp passed to it is not changed.
+    for i in 1..=3 {
+      *w_size.write() = Size::splat(i as f32);
+      wnd.draw_frame();
+    }
+    assert_eq!((*fixed_cnt.read(), *sibling_cnt.read()), (1, 4));
+  }
 }
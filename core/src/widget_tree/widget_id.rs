@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use indextree::{Node, NodeId};
+use indextree::{Node, NodeEdge, NodeId};
 use smallvec::{SmallVec, smallvec};
 
 use super::*;
@@ -61,6 +61,30 @@ impl Clone for TrackId {
 }
 
 impl WidgetId {
+  /// Return the widget by a child index path from the `root` in the window,
+  /// `[0, 2]` means the third child of the first child of the `root`. Return
+  /// `None` if any index is out of range.
+  pub fn from_path(root: WidgetId, path: &[usize], wnd: &Window) -> Option<WidgetId> {
+    root.descendant_by_path(path, wnd.tree())
+  }
+
+  /// Return the child index path from the `root` to this widget in the window,
+  /// the reverse of [`WidgetId::from_path`]. Return `None` if this widget is
+  /// not a descendant of the `root`.
+  pub fn path_from(self, root: WidgetId, wnd: &Window) -> Option<Vec<usize>> {
+    self.index_path_from(root, wnd.tree())
+  }
+
+  /// Return the nearest ancestor of this widget in the window that can be
+  /// queried as type `T`, this widget itself is not included.
+  pub fn ancestor_of_type<T: 'static>(self, wnd: &Window) -> Option<WidgetId> {
+    let tree = wnd.tree();
+    self
+      .ancestors(tree)
+      .skip(1)
+      .find(|p| p.contain_type::<T>(tree))
+  }
+
   /// Returns a reference to the node data.
   pub(crate) fn get<'a, 'b>(self, tree: &'a WidgetTree) -> Option<&'a (dyn RenderQueryable + 'b)> {
     tree.arena.get(self.0).map(|n| &**n.get())
@@ -145,7 +169,6 @@ impl WidgetId {
     self.node_feature(tree, |node| node.previous_sibling())
   }
 
-  #[allow(unused)]
   pub(crate) fn ancestor_of(self, other: WidgetId, tree: &WidgetTree) -> bool {
     other.ancestors(tree).any(|p| self == p)
   }
@@ -187,6 +210,47 @@ impl WidgetId {
     self.0.descendants(&tree.arena).map(WidgetId)
   }
 
+  /// Return the descendant by a child index path from this widget, `[0, 2]`
+  /// means the third child of the first child. Return `None` if any index is
+  /// out of range.
+  pub(crate) fn descendant_by_path(self, path: &[usize], tree: &WidgetTree) -> Option<WidgetId> {
+    path
+      .iter()
+      .try_fold(self, |node, idx| node.children(tree).nth(*idx))
+  }
+
+  /// Return the child index path from the `ancestor` to this widget, the
+  /// reverse of [`WidgetId::descendant_by_path`]. Return `None` if this widget
+  /// is not a descendant of the `ancestor`.
+  pub(crate) fn index_path_from(self, ancestor: WidgetId, tree: &WidgetTree) -> Option<Vec<usize>> {
+    let mut path = vec![];
+    let mut node = self;
+    while node != ancestor {
+      let parent = node.parent(tree)?;
+      let idx = parent.children(tree).position(|c| c == node)?;
+      path.push(idx);
+      node = parent;
+    }
+    path.reverse();
+    Some(path)
+  }
+
+  /// Return an iterator of the subtree of this widget in post-order, the
+  /// children are visited before their parent, and the siblings are visited in
+  /// order.
+  pub(crate) fn post_order_descendants(
+    self, tree: &WidgetTree,
+  ) -> impl Iterator<Item = WidgetId> + '_ {
+    assert!(!self.is_dropped(tree));
+    self
+      .0
+      .traverse(&tree.arena)
+      .filter_map(|edge| match edge {
+        NodeEdge::End(id) => Some(WidgetId(id)),
+        NodeEdge::Start(_) => None,
+      })
+  }
+
   pub(crate) fn on_widget_mounted(self, tree: &WidgetTree) {
     tree
       .window()
@@ -279,6 +343,9 @@ impl WidgetId {
   pub(crate) fn paint_subtree(self, tree: &WidgetTree, painter: &mut Painter) {
     let mut ctx = PaintingCtx::new(self, tree, painter);
     let mut painting = vec![];
+    // The children of every painting widget in the z order, `None` if they are
+    // painted in the tree order.
+    let mut z_orders = vec![];
     loop {
       let id = ctx.id();
       if ctx.painter().alpha() != 0. {
@@ -289,9 +356,19 @@ impl WidgetId {
             .painter()
             .save()
             .translate(layout_box.min_x(), layout_box.min_y());
+          if render.needs_layer() {
+            // The layer ends when the painter is restored after the subtree.
+            ctx.painter().begin_composite_layer();
+          }
           render.paint(&mut ctx);
 
-          if let Some(c) = id.first_child(tree) {
+          let mut z_order = id.z_ordered_children(tree).map(Vec::into_iter);
+          let first = match &mut z_order {
+            Some(order) => order.next(),
+            None => id.first_child(tree),
+          };
+          z_orders.push(z_order);
+          if let Some(c) = first {
             ctx.switch_to(c);
             continue;
           }
@@ -299,11 +376,16 @@ impl WidgetId {
       }
 
       while let Some(painting) = painting.pop() {
+        z_orders.pop();
         ctx.painter().restore();
         ctx.switch_to(painting);
         ctx.finish();
 
-        if let Some(sibling) = painting.next_sibling(tree) {
+        let sibling = match z_orders.last_mut() {
+          Some(Some(order)) => order.next(),
+          _ => painting.next_sibling(tree),
+        };
+        if let Some(sibling) = sibling {
           ctx.switch_to(sibling);
           break;
         }
@@ -314,6 +396,52 @@ impl WidgetId {
       }
     }
   }
+
+  /// Return the children sorted by their `z_index` and then the tree order, or
+  /// `None` if all the children have the same `z_index`.
+  pub(crate) fn z_ordered_children(self, tree: &WidgetTree) -> Option<Vec<WidgetId>> {
+    let z_index = |w: &WidgetId| w.assert_get(tree).z_index();
+    let mut children = self.children(tree);
+    let first = z_index(&children.next()?);
+    if children.all(|c| z_index(&c) == first) {
+      return None;
+    }
+
+    let mut children: Vec<_> = self.children(tree).collect();
+    children.sort_by_cached_key(z_index);
+    Some(children)
+  }
+
+  /// The children from the topmost to the bottommost, the reverse of their
+  /// paint order. The order is computed once, so iterate it instead of looking
+  /// up the paint sibling of every child.
+  pub(crate) fn rev_paint_children(self, tree: &WidgetTree) -> RevPaintChildren<'_> {
+    match self.z_ordered_children(tree) {
+      Some(order) => RevPaintChildren::ZOrdered(order.into_iter().rev()),
+      None => RevPaintChildren::Tree { next: self.last_child(tree), tree },
+    }
+  }
+}
+
+/// The iterator of [`WidgetId::rev_paint_children`].
+pub(crate) enum RevPaintChildren<'a> {
+  ZOrdered(std::iter::Rev<std::vec::IntoIter<WidgetId>>),
+  Tree { next: Option<WidgetId>, tree: &'a WidgetTree },
+}
+
+impl Iterator for RevPaintChildren<'_> {
+  type Item = WidgetId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self {
+      RevPaintChildren::ZOrdered(order) => order.next(),
+      RevPaintChildren::Tree { next, tree } => {
+        let child = (*next)?;
+        *next = child.previous_sibling(tree);
+        Some(child)
+      }
+    }
+  }
 }
 
 pub(crate) fn new_node(
@@ -1,5 +1,5 @@
 use std::{
-  cell::{Cell, RefCell},
+  cell::{Cell, RefCell, RefMut},
   collections::VecDeque,
   convert::Infallible,
   ptr::NonNull,
@@ -14,11 +14,11 @@ pub use winit::window::CursorIcon;
 
 use crate::{
   events::{
-    dispatcher::Dispatcher,
+    dispatcher::{Dispatcher, hit_stack_at},
     focus_mgr::{FocusManager, FocusType},
   },
   prelude::*,
-  ticker::{FrameMsg, FrameTicker},
+  ticker::{FrameMsg, FrameTicker, FrameTiming},
 };
 
 /// Window is the root to represent.
@@ -55,10 +55,57 @@ pub struct Window {
   ///
   /// This widgets it's detached from its parent, but still need to paint.
   pub(crate) delay_drop_widgets: RefCell<Vec<(Option<WidgetId>, TrackId)>>,
+  shortcuts: RefCell<Shortcuts>,
+  background: Cell<Option<Color>>,
+  /// The depth of the nested `batch` calls, the frame tasks and the layout are
+  /// paused while it's not zero.
+  batch_depth: Cell<u32>,
+  frame_callback: RefCell<Option<FrameCallback>>,
+  resize_debounce: Cell<ResizeDebounce>,
+  /// The source of the current time for the time-dependent logic of the
+  /// window, replaceable to drive it deterministically in tests.
+  clock: RefCell<Box<dyn Fn() -> Instant>>,
 
   flags: Cell<WindowFlags>,
 }
 
+type FrameCallback = Box<dyn FnMut(FrameTiming)>;
+
+/// The relayout of a continuous resize is deferred until the resize settles
+/// for `settle`, or `max_defer` elapses since the first deferred resize. A
+/// resize not following another one in `settle` is laid out immediately.
+#[derive(Clone, Copy)]
+struct ResizeDebounce {
+  settle: Duration,
+  max_defer: Duration,
+  /// The time of the last resize.
+  last: Option<Instant>,
+  /// The time of the first resize that not laid out yet.
+  pending: Option<Instant>,
+}
+
+impl ResizeDebounce {
+  /// The time the deferred relayout should happen, `None` if no relayout is
+  /// deferred.
+  fn deadline(&self) -> Option<Instant> {
+    let last = self.last?;
+    self
+      .pending
+      .map(|first| (last + self.settle).min(first + self.max_defer))
+  }
+}
+
+impl Default for ResizeDebounce {
+  fn default() -> Self {
+    Self {
+      settle: Duration::from_millis(50),
+      max_defer: Duration::from_millis(200),
+      last: None,
+      pending: None,
+    }
+  }
+}
+
 bitflags! {
   #[derive(Clone, Copy)]
   #[doc="A set of flags to control the window behavior."]
@@ -129,6 +176,14 @@ impl Window {
       .dispatch_keyboard_input(physical_key, key, is_repeat, location, state);
   }
 
+  pub fn processes_modifiers_changed(&self, modifiers: ModifiersState) {
+    self
+      .dispatcher
+      .borrow_mut()
+      .info
+      .set_modifiers(modifiers);
+  }
+
   pub fn processes_receive_chars(&self, chars: String) {
     self
       .dispatcher
@@ -150,6 +205,36 @@ impl Window {
       .dispatch_mouse_input(device_id, state, button);
   }
 
+  /// Set the thresholds to recognize successive taps as a multi-tap sequence.
+  /// A tap is counted as the next tap of the sequence only if it's within
+  /// `interval` of the previous tap and not farther than `distance` (in
+  /// logical pixels) from it, otherwise the tap count restarts from one.
+  pub fn set_multi_tap_threshold(&self, interval: Duration, distance: f32) {
+    self
+      .dispatcher
+      .borrow_mut()
+      .set_multi_tap_threshold(interval, distance);
+  }
+
+  /// Return the interval and distance thresholds of the multi-tap recognition.
+  pub fn multi_tap_threshold(&self) -> (Duration, f32) {
+    self.dispatcher.borrow().multi_tap_threshold()
+  }
+
+  /// Set the distance, in logical pixels, the cursor must move past the
+  /// boundary of a hovered widget before the pointer leave event fires, so the
+  /// jittery cursor around the boundary doesn't flicker the hover state. The
+  /// pointer enter event always fires immediately. It's zero by default.
+  pub fn set_hover_hysteresis(&self, distance: f32) {
+    self
+      .dispatcher
+      .borrow_mut()
+      .set_hover_hysteresis(distance);
+  }
+
+  /// Return the hover hysteresis distance of the window.
+  pub fn hover_hysteresis(&self) -> f32 { self.dispatcher.borrow().hover_hysteresis() }
+
   /// Request switch the focus to next widget.
   pub fn request_next_focus(&self) {
     self
@@ -166,6 +251,16 @@ impl Window {
       .focus_prev_widget(self.tree());
   }
 
+  /// Request to move the focus to the nearest focusable widget in the `dir` of
+  /// the focusing widget on the screen, such as by the arrow keys of a remote
+  /// control.
+  pub fn request_focus_move(&self, dir: FocusDirection) {
+    self
+      .focus_mgr
+      .borrow_mut()
+      .move_focus(dir, self.tree());
+  }
+
   /// Execute the callback when the next frame begins.
   pub fn once_next_frame(&self, f: impl FnOnce() + 'static) {
     self.once_on_lifecycle(f, |msg| matches!(msg, FrameMsg::NewFrame(_)))
@@ -218,9 +313,13 @@ impl Window {
   /// Draw an image what current render tree represent.
   #[track_caller]
   pub fn draw_frame(&self, force: bool) -> bool {
+    if self.is_batching() {
+      return false;
+    }
     AppCtx::run_until_stalled();
     let mut ticker = self.frame_ticker.clone();
-    ticker.next(FrameMsg::NewFrame(Instant::now()));
+    let start = Instant::now();
+    ticker.next(FrameMsg::NewFrame(start));
     self.run_frame_tasks();
 
     self.update_painter_viewport();
@@ -228,24 +327,36 @@ impl Window {
     if force || draw {
       let root = self.tree().root();
 
-      let surface = {
+      let surface = self.background.get().unwrap_or_else(|| {
         let _guard = BuildCtx::init_for(root, self.tree);
         Palette::of(BuildCtx::get()).surface()
-      };
+      });
       self.shell_wnd.borrow_mut().begin_frame(surface);
 
-      ticker.next(FrameMsg::BeforeLayout(Instant::now()));
+      let layout_start = Instant::now();
+      ticker.next(FrameMsg::BeforeLayout(layout_start));
       self.layout();
 
+      let paint_start = Instant::now();
       self.tree().draw();
       self.draw_delay_drop_widgets();
 
-      let mut shell = self.shell_wnd.borrow_mut();
-      let inner_size = shell.inner_size();
-      let mut painter = self.painter.borrow_mut();
-      shell.draw_commands(Rect::from_size(inner_size), &painter.finish());
+      let present_start = Instant::now();
+      {
+        let mut shell = self.shell_wnd.borrow_mut();
+        let inner_size = shell.inner_size();
+        let mut painter = self.painter.borrow_mut();
+        shell.draw_commands(Rect::from_size(inner_size), &painter.finish());
+        shell.end_frame();
+      }
 
-      shell.end_frame();
+      let us = |from: Instant, to: Instant| to.duration_since(from).as_micros() as u64;
+      self.emit_frame_timing(FrameTiming {
+        repair_us: us(start, layout_start),
+        layout_us: us(layout_start, paint_start),
+        paint_us: us(paint_start, present_start),
+        present_us: us(present_start, Instant::now()),
+      });
     }
 
     AppCtx::end_frame();
@@ -256,6 +367,9 @@ impl Window {
   }
 
   pub fn layout(&self) {
+    if self.is_batching() {
+      return;
+    }
     loop {
       self.run_frame_tasks();
 
@@ -281,6 +395,13 @@ impl Window {
   }
 
   pub fn update_painter_viewport(&self) {
+    if self.is_resizing() {
+      return;
+    }
+    let mut debounce = self.resize_debounce.get();
+    debounce.pending = None;
+    self.resize_debounce.set(debounce);
+
     let size = self.shell_wnd.borrow().inner_size();
     if self.painter.borrow().viewport().size != size {
       let tree = self.tree_mut();
@@ -295,6 +416,69 @@ impl Window {
 
   pub fn need_draw(&self) -> bool { self.tree().is_dirty() || self.running_animates.get() > 0 }
 
+  /// Called when the shell window is resized, the surface is resized to the
+  /// `size` immediately, but the relayout of a continuous resize is deferred
+  /// until the resize settles. The first resize is not deferred.
+  pub fn on_resize(&self, size: Size) {
+    self.shell_wnd.borrow_mut().on_resize(size);
+    let mut debounce = self.resize_debounce.get();
+    let now = self.now();
+    let continuous = debounce
+      .last
+      .is_some_and(|last| now.duration_since(last) < debounce.settle);
+    let wake = continuous && debounce.pending.is_none();
+    if continuous {
+      debounce.pending.get_or_insert(now);
+    }
+    debounce.last = Some(now);
+    self.resize_debounce.set(debounce);
+
+    if wake {
+      // Wake up the event loop at the deadline to lay out the settled size, the
+      // deadline moves on while the resize continues.
+      let id = self.id();
+      let wait = move || {
+        let wnd = AppCtx::get_window(id)?;
+        let now = wnd.now();
+        let deadline = wnd.resize_debounce.get().deadline()?;
+        (deadline > now).then(|| deadline - now)
+      };
+      let _ = AppCtx::spawn_local(async move {
+        while let Some(wait) = wait() {
+          timer::Timer::new_timer_future(wait).await;
+        }
+      });
+    }
+  }
+
+  /// Set the intervals to debounce the resize of the window. The relayout is
+  /// deferred until no resize happens in `settle`, or at most `max_defer`
+  /// since the first resize.
+  pub fn set_resize_debounce(&self, settle: Duration, max_defer: Duration) {
+    let mut debounce = self.resize_debounce.get();
+    debounce.settle = settle;
+    debounce.max_defer = max_defer;
+    self.resize_debounce.set(debounce);
+  }
+
+  /// Return if the window is in a continuous resize, and the relayout is
+  /// deferred.
+  pub fn is_resizing(&self) -> bool {
+    self
+      .resize_debounce
+      .get()
+      .deadline()
+      .is_some_and(|deadline| self.now() < deadline)
+  }
+
+  /// The current time read from the clock of the window.
+  pub(crate) fn now(&self) -> Instant { (self.clock.borrow())() }
+
+  #[cfg(test)]
+  pub(crate) fn set_clock(&self, clock: impl Fn() -> Instant + 'static) {
+    *self.clock.borrow_mut() = Box::new(clock);
+  }
+
   pub fn new(shell_wnd: Box<dyn ShellWindow>) -> Sc<Self> {
     let wnd_id = shell_wnd.id();
     let focus_mgr = RefCell::new(FocusManager::new(wnd_id));
@@ -314,6 +498,12 @@ impl Window {
       priority_task_queue: PriorityTaskQueue::default(),
       shell_wnd: RefCell::new(shell_wnd),
       delay_drop_widgets: <_>::default(),
+      shortcuts: <_>::default(),
+      background: Cell::new(None),
+      batch_depth: Cell::new(0),
+      frame_callback: <_>::default(),
+      resize_debounce: <_>::default(),
+      clock: RefCell::new(Box::new(Instant::now)),
       flags: Cell::new(WindowFlags::DEFAULT),
       pre_edit: <_>::default(),
     };
@@ -431,10 +621,10 @@ impl Window {
           self.emit(id, &mut e);
         }
         DelayEvent::Disposed { id, parent } => {
-          id.descendants(self.tree())
+          // Dispose the children before their parent.
+          id.post_order_descendants(self.tree())
             .collect::<Vec<_>>()
             .into_iter()
-            .rev()
             .for_each(|id| {
               if Some(id) == self.focusing() {
                 self.focus_mgr.borrow_mut().blur_on_dispose();
@@ -466,8 +656,12 @@ impl Window {
         }
         DelayEvent::FocusIn { bottom, up } => {
           let top = up.unwrap_or_else(|| self.tree().root());
-          self.top_down_emit(&mut Event::FocusInCapture(FocusEvent::new(top, self.tree)), bottom);
-          self.bottom_up_emit(&mut Event::FocusIn(FocusEvent::new(bottom, self.tree)), up);
+          let mut e = Event::FocusInCapture(FocusEvent::new(top, self.tree));
+          let propagation = self.top_down_emit(&mut e, bottom);
+          drop(e);
+          if propagation {
+            self.bottom_up_emit(&mut Event::FocusIn(FocusEvent::new(bottom, self.tree)), up);
+          }
         }
         DelayEvent::Blur(id) => {
           let mut e = Event::Blur(FocusEvent::new(id, self.tree));
@@ -475,8 +669,12 @@ impl Window {
         }
         DelayEvent::FocusOut { bottom, up } => {
           let top = up.unwrap_or_else(|| self.tree().root());
-          self.top_down_emit(&mut Event::FocusOutCapture(FocusEvent::new(top, self.tree)), bottom);
-          self.bottom_up_emit(&mut Event::FocusOut(FocusEvent::new(bottom, self.tree)), up);
+          let mut e = Event::FocusOutCapture(FocusEvent::new(top, self.tree));
+          let propagation = self.top_down_emit(&mut e, bottom);
+          drop(e);
+          if propagation {
+            self.bottom_up_emit(&mut Event::FocusOut(FocusEvent::new(bottom, self.tree)), up);
+          }
         }
         DelayEvent::KeyBoard { id, physical_key, key, is_repeat, location, state } => {
           let root = self.tree().root();
@@ -486,20 +684,27 @@ impl Window {
             ElementState::Pressed => Event::KeyDownCapture(event),
             ElementState::Released => Event::KeyUpCapture(event),
           };
-          self.top_down_emit(&mut event, id);
+          let propagation = self.top_down_emit(&mut event, id);
           drop(event);
           let event = KeyboardEvent::new(self, id, physical_key, key, is_repeat, location);
           let mut event = match state {
             ElementState::Pressed => Event::KeyDown(event),
             ElementState::Released => Event::KeyUp(event),
           };
-          self.bottom_up_emit(&mut event, None);
+          if propagation {
+            self.bottom_up_emit(&mut event, None);
+          }
           if let Event::KeyDown(e) = event {
-            if !e.is_prevent_default() && *e.key() == VirtualKey::Named(NamedKey::Tab) {
-              self.add_delay_event(DelayEvent::TabFocusMove);
+            if !e.is_prevent_default() {
+              if *e.key() == VirtualKey::Named(NamedKey::Tab) {
+                self.add_delay_event(DelayEvent::TabFocusMove);
+              } else {
+                self.trigger_shortcuts(e.key());
+              }
             }
           }
         }
+        DelayEvent::Shortcut(key) => self.trigger_shortcuts(&key),
         DelayEvent::TabFocusMove => {
           let pressed_shift = {
             let dispatcher = self.dispatcher.borrow();
@@ -519,19 +724,23 @@ impl Window {
 
         DelayEvent::Chars { id, chars } => {
           let event = CharsEvent::new(chars.clone(), self.tree().root(), self);
-          self.top_down_emit(&mut Event::CharsCapture(event), id);
-          self.bottom_up_emit(&mut Event::Chars(CharsEvent::new(chars, id, self)), None);
+          if self.top_down_emit(&mut Event::CharsCapture(event), id) {
+            self.bottom_up_emit(&mut Event::Chars(CharsEvent::new(chars, id, self)), None);
+          }
         }
         DelayEvent::Wheel { id, delta_x, delta_y } => {
           let event = WheelEvent::new(delta_x, delta_y, self.tree().root(), self);
-          self.top_down_emit(&mut Event::WheelCapture(event), id);
-          self.bottom_up_emit(&mut Event::Wheel(WheelEvent::new(delta_x, delta_y, id, self)), None);
+          if self.top_down_emit(&mut Event::WheelCapture(event), id) {
+            let mut e = Event::Wheel(WheelEvent::new(delta_x, delta_y, id, self));
+            self.bottom_up_emit(&mut e, None);
+          }
         }
         DelayEvent::PointerDown(id) => {
           let root = self.tree().root();
           let event = PointerEvent::from_mouse(root, self);
-          self.top_down_emit(&mut Event::PointerDownCapture(event), id);
-          self.bottom_up_emit(&mut Event::PointerDown(PointerEvent::from_mouse(id, self)), None);
+          if self.top_down_emit(&mut Event::PointerDownCapture(event), id) {
+            self.bottom_up_emit(&mut Event::PointerDown(PointerEvent::from_mouse(id, self)), None);
+          }
           self
             .focus_mgr
             .borrow_mut()
@@ -539,20 +748,23 @@ impl Window {
         }
         DelayEvent::PointerMove(id) => {
           let event = PointerEvent::from_mouse(self.tree().root(), self);
-          self.top_down_emit(&mut Event::PointerMoveCapture(event), id);
-          self.bottom_up_emit(&mut Event::PointerMove(PointerEvent::from_mouse(id, self)), None);
+          if self.top_down_emit(&mut Event::PointerMoveCapture(event), id) {
+            self.bottom_up_emit(&mut Event::PointerMove(PointerEvent::from_mouse(id, self)), None);
+          }
         }
         DelayEvent::PointerUp(id) => {
           let event = PointerEvent::from_mouse(self.tree().root(), self);
-          self.top_down_emit(&mut Event::PointerUpCapture(event), id);
-          let event = PointerEvent::from_mouse(id, self);
-          self.bottom_up_emit(&mut Event::PointerUp(event), None);
+          if self.top_down_emit(&mut Event::PointerUpCapture(event), id) {
+            let event = PointerEvent::from_mouse(id, self);
+            self.bottom_up_emit(&mut Event::PointerUp(event), None);
+          }
         }
-        DelayEvent::_PointerCancel(id) => {
+        DelayEvent::PointerCancel(id) => {
           let event = PointerEvent::from_mouse(self.tree().root(), self);
-          self.top_down_emit(&mut Event::PointerCancelCapture(event), id);
-          let event = PointerEvent::from_mouse(id, self);
-          self.bottom_up_emit(&mut Event::PointerCancel(event), None);
+          if self.top_down_emit(&mut Event::PointerCancelCapture(event), id) {
+            let event = PointerEvent::from_mouse(id, self);
+            self.bottom_up_emit(&mut Event::PointerCancel(event), None);
+          }
         }
         DelayEvent::PointerEnter { bottom, up } => {
           let top = up.unwrap_or_else(|| self.tree().root());
@@ -561,18 +773,42 @@ impl Window {
         DelayEvent::PointerLeave { bottom, up } => {
           self.bottom_up_emit(&mut Event::PointerLeave(PointerEvent::from_mouse(bottom, self)), up);
         }
-        DelayEvent::Tap(wid) => {
-          let event = PointerEvent::from_mouse(self.tree().root(), self);
-          self.top_down_emit(&mut Event::TapCapture(event), wid);
-          let event = PointerEvent::from_mouse(wid, self);
-          self.bottom_up_emit(&mut Event::Tap(event), None);
+        DelayEvent::Tap { wid, count } => {
+          let event = PointerEvent::from_tap(self.tree().root(), count, self);
+          if self.top_down_emit(&mut Event::TapCapture(event), wid) {
+            let event = PointerEvent::from_tap(wid, count, self);
+            self.bottom_up_emit(&mut Event::Tap(event), None);
+          }
+        }
+        DelayEvent::DragStart { wid, start } => {
+          let (root, zero) = (self.tree().root(), Vector::zero());
+          let event = DragEvent::new(start, zero, zero, root, self);
+          if self.top_down_emit(&mut Event::DragStartCapture(event), wid) {
+            let mut e = Event::DragStart(DragEvent::new(start, zero, zero, wid, self));
+            self.bottom_up_emit(&mut e, None);
+          }
+        }
+        DelayEvent::DragUpdate { wid, start, delta } => {
+          let event = DragEvent::new(start, delta, Vector::zero(), self.tree().root(), self);
+          if self.top_down_emit(&mut Event::DragUpdateCapture(event), wid) {
+            let mut e = Event::DragUpdate(DragEvent::new(start, delta, Vector::zero(), wid, self));
+            self.bottom_up_emit(&mut e, None);
+          }
+        }
+        DelayEvent::DragEnd { wid, start, velocity } => {
+          let event = DragEvent::new(start, Vector::zero(), velocity, self.tree().root(), self);
+          if self.top_down_emit(&mut Event::DragEndCapture(event), wid) {
+            let mut e = Event::DragEnd(DragEvent::new(start, Vector::zero(), velocity, wid, self));
+            self.bottom_up_emit(&mut e, None);
+          }
         }
         DelayEvent::ImePreEdit { wid, pre_edit } => {
           let root = self.tree().root();
           let ime_event = ImePreEditEvent::new(pre_edit.clone(), root, self);
-          self.top_down_emit(&mut Event::ImePreEditCapture(ime_event), wid);
-          let ime_event = ImePreEditEvent::new(pre_edit, wid, self);
-          self.bottom_up_emit(&mut Event::ImePreEdit(ime_event), None);
+          if self.top_down_emit(&mut Event::ImePreEditCapture(ime_event), wid) {
+            let ime_event = ImePreEditEvent::new(pre_edit, wid, self);
+            self.bottom_up_emit(&mut Event::ImePreEdit(ime_event), None);
+          }
         }
         DelayEvent::GrabPointerDown(wid) => {
           let mut e = Event::PointerDown(PointerEvent::from_mouse(wid, self));
@@ -603,7 +839,9 @@ impl Window {
       })
   }
 
-  fn top_down_emit(&self, e: &mut Event, bottom: WidgetId) {
+  /// Emit the event from the target down to the `bottom`, return if the event
+  /// still propagates, the bubbling phase is skipped if not.
+  fn top_down_emit(&self, e: &mut Event, bottom: WidgetId) -> bool {
     let tree = self.tree();
     let path = bottom
       .ancestors(tree)
@@ -622,6 +860,7 @@ impl Window {
           e.is_propagation()
         })
     });
+    e.is_propagation()
   }
 
   fn bottom_up_emit(&self, e: &mut Event, up: Option<WidgetId>) {
@@ -644,9 +883,29 @@ impl Window {
       });
   }
 
+  /// The shortcuts registry of the window, see [`Shortcuts`].
+  pub fn shortcuts(&self) -> RefMut<'_, Shortcuts> { self.shortcuts.borrow_mut() }
+
+  /// The token to cancel the layout in progress of the window, see
+  /// [`LayoutCancelToken`]. The canceled layout is redone with the latest state
+  /// before the frame is painted.
+  pub fn layout_cancel_token(&self) -> LayoutCancelToken { self.tree().layout_cancel.clone() }
+
+  fn trigger_shortcuts(&self, key: &VirtualKey) {
+    let modifiers = self.dispatcher.borrow().info.modifiers();
+    let combination = KeyCombination::new(key.clone(), modifiers);
+    let handlers = self.shortcuts.borrow().handlers_of(&combination);
+    for h in handlers {
+      (h.borrow_mut())();
+    }
+  }
+
   /// Run all async tasks need finished in current frame and emit all delay
   /// events.
   pub fn run_frame_tasks(&self) {
+    if self.is_batching() {
+      return;
+    }
     loop {
       self.frame_pool.borrow_mut().run();
 
@@ -674,6 +933,45 @@ impl Window {
 
   pub fn widget_pos(&self, id: WidgetId) -> Option<Point> { self.tree().store.layout_box_pos(id) }
 
+  /// Return the bounding box of the widget in the window coordinate, the
+  /// transforms of the widget and its ancestors are applied.
+  pub fn widget_global_rect(&self, id: WidgetId) -> Option<Rect> { self.tree().global_rect(id) }
+
+  /// Return all the widgets hit at the `pos` from the topmost to the root, in
+  /// the reverse paint order. Unlike the hit test of the pointer events, the
+  /// widgets covered by others are also returned. The `pos` is relative to the
+  /// window.
+  pub fn hit_stack(&self, pos: Point) -> Vec<WidgetId> { hit_stack_at(self.tree(), pos) }
+
+  /// Dump the subtree of `id` to a graph in the Graphviz DOT language, to
+  /// debug the structure of the widget tree. Dump the whole tree if `id` is
+  /// `None`.
+  pub fn widget_tree_dot(&self, id: Option<WidgetId>) -> String {
+    let tree = self.tree();
+    tree.to_dot(id.unwrap_or_else(|| tree.root()))
+  }
+
+  /// Dump the layout result of the subtree of `id` in a stable text format, to
+  /// compare the layout in the snapshot tests. Dump the whole tree if `id` is
+  /// `None`.
+  pub fn layout_snapshot(&self, id: Option<WidgetId>) -> String {
+    let tree = self.tree();
+    tree.layout_snapshot(id.unwrap_or_else(|| tree.root()))
+  }
+
+  /// Return the widget by a child index path from the root of the window,
+  /// `[0, 1]` means the second child of the first child of the root. Return
+  /// `None` if any index is out of range.
+  pub fn widget_by_path(&self, path: &[usize]) -> Option<WidgetId> {
+    WidgetId::from_path(self.tree().root(), path, self)
+  }
+
+  /// Return the child index path from the root of the window to the widget,
+  /// the reverse of [`Window::widget_by_path`].
+  pub fn widget_path(&self, id: WidgetId) -> Option<Vec<usize>> {
+    id.path_from(self.tree().root(), self)
+  }
+
   pub(crate) fn tree(&self) -> &WidgetTree {
     // Safety: Please refer to the comments in `WidgetTree::tree_mut` for more
     // information.
@@ -696,6 +994,27 @@ impl Window {
   /// Return the current focused widget id.
   pub fn focusing(&self) -> Option<WidgetId> { self.focus_mgr.borrow().focusing() }
 
+  /// Capture the current focus state, to restore it later by
+  /// [`Window::restore_focus`].
+  pub fn focus_snapshot(&self) -> FocusState { self.focus_mgr.borrow().snapshot() }
+
+  /// Return the focus to the `state` captured by [`Window::focus_snapshot`].
+  pub fn restore_focus(&self, state: FocusState) {
+    self
+      .focus_mgr
+      .borrow_mut()
+      .restore(state, self.tree());
+  }
+
+  /// Return the nearest focusable widget at the `pos`, that is the widget hit
+  /// at the `pos` or its nearest focusable ancestor.
+  pub fn focusable_at(&self, pos: Point) -> Option<WidgetId> {
+    self
+      .focus_mgr
+      .borrow()
+      .focusable_at(pos, self.tree())
+  }
+
   /// The device pixel ratio of Window interface returns the ratio of the
   /// resolution in physical pixels to the logic pixels for the current display
   /// device.
@@ -711,6 +1030,64 @@ impl Window {
     self
   }
 
+  /// Sets the color to clear the window with before painting the widgets. The
+  /// surface color of the palette is used if no background is set.
+  ///
+  /// A transparent background is only visible if the platform supports
+  /// transparent windows.
+  pub fn set_background(&self, color: Color) -> &Self {
+    self.background.set(Some(color));
+    let tree = self.tree();
+    tree
+      .dirty_marker()
+      .mark(tree.root(), DirtyPhase::Paint);
+    self
+  }
+
+  /// Returns the background color set by [`Window::set_background`].
+  pub fn background(&self) -> Option<Color> { self.background.get() }
+
+  /// Runs `f` with the frame tasks and the layout of the window paused, so the
+  /// state changes in it are processed once after it returns, instead of
+  /// relayout for every change. The nested batches are processed when the
+  /// outermost one returns, and a redraw is requested if the window is dirty.
+  ///
+  /// The batch is ended even if `f` panics.
+  pub fn batch<R>(&self, f: impl FnOnce() -> R) -> R {
+    self.batch_depth.set(self.batch_depth.get() + 1);
+    let _guard = BatchGuard(self);
+    f()
+  }
+
+  /// Sets a callback to receive the time spent on each phase of every drawn
+  /// frame, such as to show a FPS overlay. It replaces the previous one.
+  pub fn set_frame_callback(&self, f: impl FnMut(FrameTiming) + 'static) {
+    *self.frame_callback.borrow_mut() = Some(Box::new(f));
+  }
+
+  fn emit_frame_timing(&self, timing: FrameTiming) {
+    let Some(mut f) = self.frame_callback.take() else { return };
+    f(timing);
+    // Put it back if the callback doesn't set a new one.
+    self.frame_callback.borrow_mut().get_or_insert(f);
+  }
+
+  /// Adds a hook to observe which widgets changed in every frame, such as to
+  /// mirror the tree to a devtools or an accessibility tree without polling the
+  /// whole tree. The hook is called with the changed widgets before they are
+  /// laid out.
+  pub fn on_before_flush(&self, f: impl FnMut(&[WidgetId]) + 'static) {
+    self.tree_mut().on_before_flush(f);
+  }
+
+  /// Export the accessibility tree of the window, the root node is of the
+  /// `Window` role. The tree is exported from the last layout, so draw the
+  /// frame first if the widgets are changed.
+  pub fn a11y_tree(&self) -> A11yNode { A11yNode::export(self.tree().root(), self) }
+
+  /// Returns if the window is running a [`Window::batch`].
+  pub fn is_batching(&self) -> bool { self.batch_depth.get() > 0 }
+
   /// Returns the cursor icon of the window.
   pub fn get_cursor(&self) -> CursorIcon { self.shell_wnd.borrow().cursor() }
 
@@ -796,6 +1173,26 @@ impl Window {
   }
 }
 
+/// End a [`Window::batch`] when dropped, so a panic in the batch doesn't leave
+/// the window paused.
+struct BatchGuard<'a>(&'a Window);
+
+impl Drop for BatchGuard<'_> {
+  fn drop(&mut self) {
+    let wnd = self.0;
+    wnd.batch_depth.set(wnd.batch_depth.get() - 1);
+    if wnd.is_batching() || std::thread::panicking() {
+      return;
+    }
+    wnd.run_frame_tasks();
+    if wnd.need_draw() {
+      // Wake up the event loop to draw the dirty windows, the changes may be
+      // made out of an event that the shell redraws after.
+      let _ = AppCtx::spawn_local(async {});
+    }
+  }
+}
+
 impl Drop for Window {
   // Safety: We retain ownership of the box in the constructor and release it
   // here.
@@ -832,6 +1229,7 @@ pub(crate) enum DelayEvent {
     state: ElementState,
   },
   TabFocusMove,
+  Shortcut(VirtualKey),
   Chars {
     id: WidgetId,
     chars: String,
@@ -844,7 +1242,7 @@ pub(crate) enum DelayEvent {
   PointerDown(WidgetId),
   PointerMove(WidgetId),
   PointerUp(WidgetId),
-  _PointerCancel(WidgetId),
+  PointerCancel(WidgetId),
   PointerEnter {
     bottom: WidgetId,
     up: Option<WidgetId>,
@@ -853,7 +1251,24 @@ pub(crate) enum DelayEvent {
     bottom: WidgetId,
     up: Option<WidgetId>,
   },
-  Tap(WidgetId),
+  Tap {
+    wid: WidgetId,
+    count: usize,
+  },
+  DragStart {
+    wid: WidgetId,
+    start: Point,
+  },
+  DragUpdate {
+    wid: WidgetId,
+    start: Point,
+    delta: Vector,
+  },
+  DragEnd {
+    wid: WidgetId,
+    start: Point,
+    velocity: Vector,
+  },
   ImePreEdit {
     wid: WidgetId,
     pre_edit: ImePreEdit,
@@ -878,6 +1293,7 @@ impl From<WindowId> for u64 {
 }
 #[cfg(test)]
 mod tests {
+  use std::rc::Rc;
 
   use super::*;
   use crate::{reset_test_env, test_helper::*};
@@ -899,6 +1315,69 @@ mod tests {
     wnd.assert_root_size(new_size);
   }
 
+  #[test]
+  fn debounce_resize() {
+    reset_test_env!();
+
+    let (layouts, w_layouts) = split_value(0);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockBox {
+          size: INFINITY_SIZE,
+          on_performed_layout: move |_| *$w_layouts.write() += 1,
+        }
+      },
+      Size::new(100., 100.),
+    );
+    let now = Rc::new(Cell::new(Instant::now()));
+    let clock = now.clone();
+    wnd.set_clock(move || clock.get());
+    let advance = |ms| now.set(now.get() + Duration::from_millis(ms));
+    wnd.set_resize_debounce(Duration::from_millis(200), Duration::from_secs(10));
+    wnd.draw_frame();
+    let laid_out = *layouts.read();
+
+    // The first resize is laid out immediately.
+    wnd.on_resize(Size::new(110., 100.));
+    wnd.draw_frame();
+    assert_eq!(*layouts.read(), laid_out + 1);
+    assert!(!wnd.is_resizing());
+
+    for width in [120., 130.] {
+      advance(10);
+      wnd.on_resize(Size::new(width, 100.));
+      wnd.draw_frame();
+      // The surface tracks every size, but the layout is deferred.
+      assert_eq!(wnd.size(), Size::new(width, 100.));
+      assert_eq!(*layouts.read(), laid_out + 1);
+      // No redraw is requested until the resize settles.
+      assert!(!wnd.need_draw());
+    }
+    assert!(wnd.is_resizing());
+
+    advance(250);
+    assert!(!wnd.is_resizing());
+    wnd.draw_frame();
+    assert_eq!(*layouts.read(), laid_out + 2);
+    wnd.assert_root_size(Size::new(130., 100.));
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn fit_window_to_content() {
+    reset_test_env!();
+
+    let size = Size::new(120., 80.);
+    let wnd = TestWindow::fit_content(fn_widget! { MockBox { size } }, Size::new(500., 500.));
+    assert_eq!(wnd.size(), size);
+    wnd.assert_root_size(size);
+
+    // The content is clamped by the max size.
+    let max = Size::new(100., 100.);
+    let wnd = TestWindow::fit_content(fn_widget! { MockBox { size: INFINITY_SIZE } }, max);
+    assert_eq!(wnd.size(), max);
+  }
+
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
   #[test]
   fn fire_tasks_before_new_window() {
@@ -918,4 +1397,163 @@ mod tests {
 
     wnd.draw_frame();
   }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn dispose_children_before_parent() {
+    reset_test_env!();
+
+    let (trigger, w_trigger) = split_value(0);
+    let order = Stateful::new(vec![]);
+    let c_order = order.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let c_order = c_order.clone_writer();
+      let record = move |name: &'static str| {
+        let order = c_order.clone_writer();
+        move |_: &mut LifecycleEvent| order.write().push(name)
+      };
+      @MockMulti {
+        @ { pipe!(*$trigger).map(move |_| {
+          @MockMulti {
+            on_disposed: record("parent"),
+            @MockMulti {
+              on_disposed: record("first"),
+              @MockBox { size: Size::zero(), on_disposed: record("leaf") }
+            }
+            @MockBox { size: Size::zero(), on_disposed: record("second") }
+          }
+        })}
+      }
+    });
+    wnd.draw_frame();
+    assert!(order.read().is_empty());
+
+    *w_trigger.write() += 1;
+    wnd.draw_frame();
+    assert_eq!(*order.read(), ["leaf", "first", "second", "parent"]);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test]
+  fn custom_background() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! { @MockBox { size: Size::new(10., 10.) } },
+      Size::new(10., 10.),
+    );
+    wnd.set_background(Color::RED);
+    wnd.draw_frame();
+
+    let img = ribir_dev_helper::capture_wnd_image!(wnd);
+    assert!(
+      img
+        .pixel_bytes()
+        .chunks(4)
+        .all(|p| p == [255, 0, 0, 255])
+    );
+  }
+
+  #[test]
+  fn batch_updates() {
+    reset_test_env!();
+
+    let (width, w_width) = split_value(0.);
+    let (layouts, w_layouts) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: pipe!(Size::new(*$width, 1.)),
+        on_performed_layout: move |_| *$w_layouts.write() += 1,
+      }
+    });
+    wnd.draw_frame();
+    let base = *layouts.read();
+
+    wnd.batch(|| {
+      for _ in 0..50 {
+        *w_width.write() += 1.;
+        // The layout is paused until the batch returns.
+        wnd.layout();
+      }
+      wnd.batch(|| *w_width.write() += 1.);
+      assert!(wnd.is_batching());
+      assert_eq!(*layouts.read(), base);
+    });
+    assert!(!wnd.is_batching());
+
+    wnd.draw_frame();
+    assert_eq!(*layouts.read(), base + 1);
+    assert_eq!(wnd.layout_info_by_path(&[0]).unwrap().size, Some(Size::new(51., 1.)));
+  }
+
+  #[test]
+  fn batch_end_by_panic() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @MockBox { size: Size::new(1., 1.) } });
+    wnd.draw_frame();
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      wnd.batch(|| wnd.batch(|| panic!("panic in the batch")))
+    }));
+    assert!(res.is_err());
+    assert!(!wnd.is_batching());
+  }
+
+  #[test]
+  fn batch_request_redraw() {
+    reset_test_env!();
+
+    let (width, w_width) = split_value(1.);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox { size: pipe!(Size::new(*$width, 1.)) }
+    });
+    wnd.draw_frame();
+    assert!(!wnd.need_draw());
+
+    wnd.batch(|| *w_width.write() = 2.);
+    assert!(wnd.need_draw());
+    // A task is spawned to wake up the event loop to draw the window.
+    assert!(AppCtx::run_until_stalled() > 0);
+    wnd.draw_frame();
+    assert_eq!(wnd.layout_info_by_path(&[0]).unwrap().size, Some(Size::new(2., 1.)));
+  }
+
+  #[test]
+  fn frame_timing_callback() {
+    reset_test_env!();
+
+    let (width, w_width) = split_value(1.);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let width = width.clone_watcher();
+      @MockMulti {
+        @ {
+          (0..100).map(move |_| {
+            let width = width.clone_watcher();
+            @MockBox { size: pipe!(Size::new(*$width, 10.)) }
+          })
+        }
+      }
+    });
+    let timings = Rc::new(RefCell::new(vec![]));
+    let c_timings = timings.clone();
+    wnd.set_frame_callback(move |t| c_timings.borrow_mut().push(t));
+
+    wnd.draw_frame();
+    assert_eq!(timings.borrow().len(), 1);
+    // No frame is drawn if nothing changed.
+    wnd.draw_frame();
+    assert_eq!(timings.borrow().len(), 1);
+
+    *w_width.write() = 2.;
+    let start = Instant::now();
+    wnd.draw_frame();
+    let elapsed = start.elapsed().as_micros() as u64;
+    let timings = timings.borrow();
+    assert_eq!(timings.len(), 2);
+    // The phases are measured in order within the frame, so they never exceed
+    // the time to draw the frame.
+    let FrameTiming { repair_us, layout_us, paint_us, present_us } = timings[1];
+    assert!(repair_us + layout_us + paint_us + present_us <= elapsed);
+  }
 }
@@ -1,4 +1,4 @@
-use ribir_geom::{Point, Size, Transform};
+use ribir_geom::{Point, Rect, Size, Transform};
 use smallvec::SmallVec;
 use widget_id::RenderQueryable;
 
@@ -31,6 +31,26 @@ pub trait WrapRender {
 
   fn get_transform(&self, host: &dyn Render) -> Option<Transform> { host.get_transform() }
 
+  fn needs_layer(&self, host: &dyn Render) -> bool { host.needs_layer() }
+
+  fn semantics(&self, host: &dyn Render) -> Option<Semantics> { host.semantics() }
+
+  fn z_index(&self, host: &dyn Render) -> i32 { host.z_index() }
+
+  fn hit_bounds(&self, host: &dyn Render, size: Size) -> Option<Rect> { host.hit_bounds(size) }
+
+  fn intrinsic_width(
+    &self, host: &dyn Render, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    host.intrinsic_width(kind, height, ctx)
+  }
+
+  fn intrinsic_height(
+    &self, host: &dyn Render, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    host.intrinsic_height(kind, width, ctx)
+  }
+
   fn combine_child(
     this: impl StateWriter<Value = Self>, mut child: Widget, dirty: DirtyPhase,
   ) -> Widget
@@ -106,6 +126,32 @@ impl Render for RenderPair {
   fn dirty_phase(&self) -> DirtyPhase { self.host.dirty_phase() }
 
   fn get_transform(&self) -> Option<Transform> { self.wrapper.get_transform(self.host.as_render()) }
+
+  fn needs_layer(&self) -> bool { self.wrapper.needs_layer(self.host.as_render()) }
+
+  fn debug_name(&self) -> &'static str { self.host.debug_name() }
+
+  fn semantics(&self) -> Option<Semantics> { self.wrapper.semantics(self.host.as_render()) }
+
+  fn z_index(&self) -> i32 { self.wrapper.z_index(self.host.as_render()) }
+
+  fn hit_bounds(&self, size: Size) -> Option<Rect> {
+    self
+      .wrapper
+      .hit_bounds(self.host.as_render(), size)
+  }
+
+  fn intrinsic_width(&self, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self
+      .wrapper
+      .intrinsic_width(self.host.as_render(), kind, height, ctx)
+  }
+
+  fn intrinsic_height(&self, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self
+      .wrapper
+      .intrinsic_height(self.host.as_render(), kind, width, ctx)
+  }
 }
 
 impl<R> WrapRender for R
@@ -130,6 +176,32 @@ where
   fn get_transform(&self, host: &dyn Render) -> Option<Transform> {
     self.read().get_transform(host)
   }
+
+  fn needs_layer(&self, host: &dyn Render) -> bool { self.read().needs_layer(host) }
+
+  fn semantics(&self, host: &dyn Render) -> Option<Semantics> { self.read().semantics(host) }
+
+  fn z_index(&self, host: &dyn Render) -> i32 { self.read().z_index(host) }
+
+  fn hit_bounds(&self, host: &dyn Render, size: Size) -> Option<Rect> {
+    self.read().hit_bounds(host, size)
+  }
+
+  fn intrinsic_width(
+    &self, host: &dyn Render, kind: IntrinsicSize, height: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    self
+      .read()
+      .intrinsic_width(host, kind, height, ctx)
+  }
+
+  fn intrinsic_height(
+    &self, host: &dyn Render, kind: IntrinsicSize, width: Option<f32>, ctx: &mut LayoutCtx,
+  ) -> f32 {
+    self
+      .read()
+      .intrinsic_height(host, kind, width, ctx)
+  }
 }
 
 #[macro_export]
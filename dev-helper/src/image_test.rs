@@ -52,6 +52,57 @@ macro_rules! test_case_name {
   }};
 }
 
+/// The maximum difference of a color channel that two pixels are still treated
+/// as the same by [`assert_image_eq`].
+pub const PIXEL_TOLERANCE: u8 = 2;
+
+/// Compare the `actual` image with the `golden` image pixel by pixel, return
+/// `None` if they are the same, otherwise return a diff image.
+///
+/// The pixels that differ beyond [`PIXEL_TOLERANCE`] are highlighted in red in
+/// the diff image, and the others are the faded pixels of the `golden` image.
+/// If the sizes or the color formats of the images are different, all the
+/// pixels of the diff image are highlighted.
+pub fn assert_image_eq(actual: &PixelImage, golden: &PixelImage) -> Option<PixelImage> {
+  const HIGHLIGHT: [u8; 4] = [255, 0, 0, 255];
+
+  let (width, height) = (actual.width(), actual.height());
+  if width != golden.width()
+    || height != golden.height()
+    || actual.color_format() != golden.color_format()
+  {
+    let data = HIGHLIGHT.repeat((width * height) as usize);
+    return Some(PixelImage::new(data.into(), width, height, ColorFormat::Rgba8));
+  }
+
+  let bytes = actual.color_format().pixel_per_bytes() as usize;
+  let mut same = true;
+  let data: Vec<u8> = actual
+    .pixel_bytes()
+    .chunks(bytes)
+    .zip(golden.pixel_bytes().chunks(bytes))
+    .flat_map(|(actual, golden)| {
+      let differ = actual
+        .iter()
+        .zip(golden)
+        .any(|(a, g)| a.abs_diff(*g) > PIXEL_TOLERANCE);
+      if differ {
+        same = false;
+        HIGHLIGHT
+      } else {
+        let [r, g, b, a] = match *golden {
+          [alpha] => [0, 0, 0, alpha],
+          [r, g, b, a] => [r, g, b, a],
+          _ => unreachable!(),
+        };
+        [r, g, b, a / 4]
+      }
+    })
+    .collect();
+
+  (!same).then(|| PixelImage::new(data.into(), width, height, ColorFormat::Rgba8))
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub struct ImageTest<'a> {
   test_img: PixelImage,
@@ -224,3 +275,33 @@ pub fn wgpu_render_commands(
     draw_img(&mut backend)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn image(pixels: &[[u8; 4]], width: u32) -> PixelImage {
+    let data = pixels.concat();
+    let height = pixels.len() as u32 / width;
+    PixelImage::new(data.into(), width, height, ColorFormat::Rgba8)
+  }
+
+  #[test]
+  fn diff_image() {
+    let golden = image(&[[0, 0, 255, 255]; 4], 2);
+    assert!(assert_image_eq(&golden, &golden).is_none());
+
+    // A difference within the tolerance is ignored.
+    let actual = image(&[[0, 0, 254, 255], [0, 0, 255, 255], [0, 0, 255, 255], [255; 4]], 2);
+    let diff = assert_image_eq(&actual, &golden).unwrap();
+    assert_eq!(diff.width(), 2);
+    assert_eq!(diff.height(), 2);
+    assert_eq!(
+      diff.pixel_bytes(),
+      &[[0, 0, 255, 63], [0, 0, 255, 63], [0, 0, 255, 63], [255, 0, 0, 255]].concat()[..]
+    );
+
+    let smaller = image(&[[0, 0, 255, 255]; 2], 2);
+    assert!(assert_image_eq(&smaller, &golden).is_some());
+  }
+}
@@ -80,10 +80,7 @@ macro_rules! assert_widget_eq_image {
     let img_path = $crate::test_case_name!($name, "png");
 
     let mut wnd = $widget_tester.create_wnd();
-
-    let Frame { commands, viewport, surface } = wnd.take_last_frame().unwrap();
-    let viewport = viewport.to_i32().cast_unit();
-    let img = $crate::wgpu_render_commands(&commands, viewport, surface);
+    let img = $crate::capture_wnd_image!(wnd);
 
     let mut img_test = $crate::ImageTest::new(img, &img_path);
     if let Some(c) = $widget_tester.comparison {
@@ -92,3 +89,17 @@ macro_rules! assert_widget_eq_image {
     img_test.test();
   };
 }
+
+/// Render the last frame of the test window by the wgpu backend, and return the
+/// image. Compare it with a golden image by
+/// [`assert_image_eq`](crate::assert_image_eq).
+///
+/// Panics if the window has no frame to take.
+#[macro_export]
+macro_rules! capture_wnd_image {
+  ($wnd:expr) => {{
+    let Frame { commands, viewport, surface } = $wnd.take_last_frame().unwrap();
+    let viewport = viewport.to_i32().cast_unit();
+    $crate::wgpu_render_commands(&commands, viewport, surface)
+  }};
+}
@@ -1,18 +1,19 @@
 use std::error::Error;
 
 use guillotiere::euclid::Vector2D;
+use ribir_algo::Resource;
 use ribir_geom::{
   DeviceRect, DeviceSize, Point, Transform, rect_corners, transform_to_device_rect,
 };
 use ribir_painter::{
-  Color, CommandBrush, PaintCommand, PaintPath, PaintPathAction, PainterBackend, PaintingStyle,
-  PathCommand, PixelImage, Vertex, VertexBuffers, image::ColorFormat,
+  Color, CommandBrush, CustomCommand, PaintCommand, PaintPath, PaintPathAction, PainterBackend,
+  PaintingStyle, Path, PathCommand, PixelImage, Vertex, VertexBuffers, image::ColorFormat,
 };
 
 use crate::{
   ColorAttr, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex, ImgPrimitive,
   LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer, RadialGradientPrimIndex,
-  RadialGradientPrimitive,
+  RadialGradientPrimitive, SweepGradientPrimIndex, SweepGradientPrimitive,
 };
 
 mod atlas;
@@ -32,6 +33,9 @@ pub struct GPUBackend<Impl: GPUBackendImpl> {
   linear_gradient_prims: Vec<LinearGradientPrimitive>,
   linear_gradient_stops: Vec<GradientStopPrimitive>,
   linear_gradient_vertices_buffer: VertexBuffers<LinearGradientPrimIndex>,
+  sweep_gradient_prims: Vec<SweepGradientPrimitive>,
+  sweep_gradient_stops: Vec<GradientStopPrimitive>,
+  sweep_gradient_vertices_buffer: VertexBuffers<SweepGradientPrimIndex>,
   current_phase: CurrentPhase,
   tex_ids_map: TextureIdxMap,
   viewport: DeviceRect,
@@ -48,6 +52,7 @@ enum CurrentPhase {
   Img,
   RadialGradient,
   LinearGradient,
+  SweepGradient,
 }
 
 struct ClipLayer {
@@ -55,6 +60,20 @@ struct ClipLayer {
   mask_head: i32,
 }
 
+/// The quality of the anti-aliasing to rasterize the paths to the alpha masks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AntiAliasQuality {
+  /// Every pixel is either fully covered or not.
+  None,
+  /// Sample every pixel 6 times.
+  #[default]
+  Normal,
+  /// Rasterize the masks at twice the resolution, then downsample them, so
+  /// every pixel is sampled 24 times. It's smoother on the curved edges but
+  /// takes extra texture memory and draw passes.
+  High,
+}
+
 /// Texture use to display.
 pub trait Texture {
   type Host;
@@ -140,6 +159,9 @@ where
       linear_gradient_vertices_buffer: VertexBuffers::with_capacity(256, 512),
       linear_gradient_stops: vec![],
       linear_gradient_prims: vec![],
+      sweep_gradient_vertices_buffer: VertexBuffers::with_capacity(256, 512),
+      sweep_gradient_stops: vec![],
+      sweep_gradient_prims: vec![],
       img_prims: vec![],
       current_phase: CurrentPhase::None,
       viewport: DeviceRect::zero(),
@@ -156,6 +178,56 @@ where
   #[inline]
   pub fn into_impl(self) -> Impl { self.gpu_impl }
 
+  /// Drop all the cached textures of paths and images to release the GPU
+  /// memory, for example, when the application is in the background. It should
+  /// be called between frames, the caches will be rebuilt when painting.
+  pub fn clear_caches(&mut self) { self.tex_mgr.clear_caches(&mut self.gpu_impl); }
+
+  /// Set the size of the first texture of the atlases, the atlases grow toward
+  /// the texture size limit as needed. It's an eighth of the limit by default,
+  /// a larger one avoids the early growth for the apps with many images, and a
+  /// smaller one saves the memory. The caches are dropped, so set it before
+  /// painting.
+  pub fn set_atlas_initial_size(&mut self, size: DeviceSize) {
+    self
+      .tex_mgr
+      .set_atlas_initial_size(size, &mut self.gpu_impl);
+  }
+
+  /// Set the minimum size in pixels to tessellate a path, the paths smaller
+  /// than it are tessellated at a larger scale to keep them smooth. The default
+  /// is 32 pixels.
+  ///
+  /// Set it to `0.` to disable the minimum, the paths are tessellated strictly
+  /// at their displayed size. It saves the atlas space and the CPU for a UI
+  /// full of tiny vector icons, at the cost of their quality.
+  pub fn set_min_tessellation_px(&mut self, px: f32) { self.tex_mgr.set_min_tessellation_px(px); }
+
+  /// Store and rasterize the `paths` and `images` ahead, such as during a
+  /// loading screen, so the first interactive frame hits the warm caches
+  /// rather than spikes. The paths are filled, and should be painted as the
+  /// same `Resource` to reuse the cache.
+  pub fn prewarm(
+    &mut self, paths: &[(Resource<Path>, Transform)], images: &[Resource<PixelImage>],
+  ) {
+    self
+      .tex_mgr
+      .prewarm(paths, images, &mut self.gpu_impl);
+  }
+
+  /// Set the anti-aliasing quality of the paths. It only affects the paths
+  /// rasterized after it, call [`GPUBackend::clear_caches`] to rasterize the
+  /// cached paths again.
+  pub fn set_anti_alias_quality(&mut self, quality: AntiAliasQuality) {
+    self
+      .tex_mgr
+      .set_anti_alias_quality(quality, &mut self.gpu_impl);
+  }
+
+  /// Draw the pending paths to the textures immediately, so the textures can be
+  /// read back in the middle of a frame.
+  pub fn flush(&mut self) { self.tex_mgr.flush(&mut self.gpu_impl); }
+
   fn draw_command(
     &mut self, cmd: &PaintCommand, global_matrix: &Transform, output_tex_size: DeviceSize,
     output: &mut Impl::Texture,
@@ -204,10 +276,16 @@ where
               add_rect_vertices(rect, output_tex_size, color_attr, buffer);
               self.current_phase = CurrentPhase::Color;
             }
-            CommandBrush::Image { img, opacity } => {
+            CommandBrush::Image { img, opacity, tint } => {
+              // The shader takes the only channel of the alpha image as the alpha.
+              assert!(
+                tint.is_none() || img.color_format() == ColorFormat::Alpha8,
+                "Only an alpha image can be tinted."
+              );
               let slice = self.tex_mgr.store_image(img, &mut self.gpu_impl);
               let ts = matrix.inverse().unwrap();
-              self.draw_img_slice(slice, &ts, mask_head, *opacity, output_tex_size, rect);
+              let (opacity, tint) = (*opacity, *tint);
+              self.draw_img_slice(slice, &ts, mask_head, opacity, tint, output_tex_size, rect);
             }
             CommandBrush::Radial(radial) => {
               let prim: RadialGradientPrimitive = RadialGradientPrimitive {
@@ -254,6 +332,23 @@ where
               add_rect_vertices(rect, output_tex_size, LinearGradientPrimIndex(prim_idx), buffer);
               self.current_phase = CurrentPhase::LinearGradient;
             }
+            CommandBrush::Sweep(sweep) => {
+              let prim = SweepGradientPrimitive {
+                transform: matrix.inverse().unwrap().to_array(),
+                center: sweep.center.to_array(),
+                start_angle: sweep.start_angle,
+                stop_start: self.sweep_gradient_stops.len() as u32,
+                stop_cnt: sweep.stops.len() as u32,
+                mask_head,
+              };
+              let stops = sweep.stops.iter().map(GradientStopPrimitive::new);
+              self.sweep_gradient_stops.extend(stops);
+              let prim_idx = self.sweep_gradient_prims.len() as u32;
+              self.sweep_gradient_prims.push(prim);
+              let buffer = &mut self.sweep_gradient_vertices_buffer;
+              add_rect_vertices(rect, output_tex_size, SweepGradientPrimIndex(prim_idx), buffer);
+              self.current_phase = CurrentPhase::SweepGradient;
+            }
           },
         }
       }
@@ -326,7 +421,22 @@ where
           .clip_layer_stack
           .last()
           .map_or(-1, |l| l.mask_head);
-        self.draw_img_slice(slice, &view_to_slice, mask_head, *opacity, output_tex_size, points);
+        let (ts, opacity) = (&view_to_slice, *opacity);
+        self.draw_img_slice(slice, ts, mask_head, opacity, None, output_tex_size, points);
+      }
+      PaintCommand::Custom(CustomCommand { rect, paint_bounds, draw }) => {
+        if self.skip_clip_cnt > 0 {
+          return;
+        }
+        let rect = transform_to_device_rect(rect, global_matrix);
+        let scissor = transform_to_device_rect(paint_bounds, global_matrix);
+        if let Some(scissor) = scissor.intersection(self.viewport()) {
+          // Draw the commands before it, so the custom content is over them.
+          self.new_draw_phase(output);
+          self
+            .gpu_impl
+            .draw_custom(output, draw, &rect, &scissor);
+        }
       }
     }
   }
@@ -405,11 +515,22 @@ where
       .indices
       .clear();
     self.linear_gradient_stops.clear();
+    self.sweep_gradient_prims.clear();
+    self.sweep_gradient_stops.clear();
+    self
+      .sweep_gradient_vertices_buffer
+      .vertices
+      .clear();
+    self
+      .sweep_gradient_vertices_buffer
+      .indices
+      .clear();
   }
 
+  #[allow(clippy::too_many_arguments)]
   fn draw_img_slice(
     &mut self, img_slice: TextureSlice, transform: &Transform, mask_head: i32, opacity: f32,
-    output_tex_size: DeviceSize, rect: [Point; 4],
+    tint: Option<Color>, output_tex_size: DeviceSize, rect: [Point; 4],
   ) {
     let img_start = img_slice.rect.origin.to_f32().to_array();
     let img_size = img_slice.rect.size.to_f32().to_array();
@@ -421,6 +542,7 @@ where
       img_size,
       mask_head_and_tex_idx,
       opacity,
+      tint: tint.map_or([0., 0., 0., -1.], Color::into_f32_components),
     };
     self.img_prims.push(prim);
     let buffer = &mut self.img_vertices_buffer;
@@ -452,6 +574,11 @@ where
           && self.linear_gradient_prims.len() < limits.max_linear_gradient_primitives
           && self.linear_gradient_stops.len() < limits.max_gradient_stop_primitives
       }
+      (CurrentPhase::SweepGradient, CommandBrush::Sweep(_)) => {
+        tex_used < limits.max_tex_load
+          && self.sweep_gradient_prims.len() < limits.max_sweep_gradient_primitives
+          && self.sweep_gradient_stops.len() < limits.max_gradient_stop_primitives
+      }
       _ => false,
     }
   }
@@ -558,6 +685,18 @@ where
         let rg = 0..self.linear_gradient_vertices_buffer.indices.len() as u32;
         gpu_impl.draw_linear_gradient_triangles(output, rg, color.take())
       }
+      CurrentPhase::SweepGradient
+        if !self
+          .sweep_gradient_vertices_buffer
+          .indices
+          .is_empty() =>
+      {
+        gpu_impl.load_sweep_gradient_primitives(&self.sweep_gradient_prims);
+        gpu_impl.load_sweep_gradient_stops(&self.sweep_gradient_stops);
+        gpu_impl.load_sweep_gradient_vertices(&self.sweep_gradient_vertices_buffer);
+        let rg = 0..self.sweep_gradient_vertices_buffer.indices.len() as u32;
+        gpu_impl.draw_sweep_gradient_triangles(output, rg, color.take())
+      }
       _ => {}
     }
   }
@@ -608,12 +747,14 @@ pub fn add_rect_vertices<Attr: Copy>(
 #[cfg(feature = "wgpu")]
 #[cfg(test)]
 mod tests {
+  use futures::executor::block_on;
   use ribir_algo::Resource;
   use ribir_dev_helper::*;
   use ribir_geom::*;
-  use ribir_painter::{Brush, Painter, Path, Svg};
+  use ribir_painter::{Brush, GradientStop, Painter, Path, Svg, color::SweepGradient};
 
   use super::*;
+  use crate::WgpuImpl;
 
   fn painter(bounds: Size) -> Painter { Painter::new(Rect::from_size(bounds)) }
 
@@ -813,4 +954,173 @@ mod tests {
     painter
   }
   painter_backend_eq_image_test!(draw_bundle_svg, comparison = 0.001);
+
+  #[test]
+  fn fill_and_stroke_circle() {
+    let mut painter = painter(Size::new(100., 100.));
+    painter
+      .set_fill_brush(Color::RED)
+      .set_stroke_brush(Color::BLUE)
+      .set_line_width(10.)
+      .circle(Point::new(50., 50.), 40.)
+      .fill_and_stroke();
+
+    let viewport = DeviceRect::from_size(DeviceSize::new(100, 100));
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let pixel = |x: usize, y: usize| {
+      let idx = (y * img.width() as usize + x) * 4;
+      &img.pixel_bytes()[idx..idx + 4]
+    };
+    assert_eq!(pixel(50, 50), Color::RED.into_components());
+    // The stroke is painted over the fill.
+    assert_eq!(pixel(50, 12), Color::BLUE.into_components());
+    assert_eq!(pixel(50, 7), Color::BLUE.into_components());
+    assert_eq!(pixel(50, 2), Color::TRANSPARENT.into_components());
+  }
+
+  #[test]
+  fn clip_by_circle() {
+    let mut painter = painter(Size::new(100., 100.));
+    painter
+      .clip(Path::circle(Point::new(50., 50.), 40.).into())
+      .set_fill_brush(Color::RED)
+      .rect(&Rect::from_size(Size::new(100., 100.)))
+      .fill();
+
+    let viewport = DeviceRect::from_size(DeviceSize::new(100, 100));
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let pixel = |x: usize, y: usize| {
+      let idx = (y * img.width() as usize + x) * 4;
+      &img.pixel_bytes()[idx..idx + 4]
+    };
+    assert_eq!(pixel(50, 50), Color::RED.into_components());
+    assert_eq!(pixel(50, 20), Color::RED.into_components());
+    // The corners of the rect are out of the circle.
+    assert_eq!(pixel(5, 5), Color::TRANSPARENT.into_components());
+    assert_eq!(pixel(95, 95), Color::TRANSPARENT.into_components());
+    assert_eq!(pixel(50, 5), Color::TRANSPARENT.into_components());
+  }
+
+  #[test]
+  fn tint_alpha_image() {
+    use std::borrow::Cow;
+
+    let mut painter = painter(Size::new(40., 10.));
+    let icon = PixelImage::new(Cow::Owned(vec![255; 100]), 10, 10, ColorFormat::Alpha8);
+    let icon = Resource::new(icon);
+    let rect = Rect::new(Point::zero(), Size::new(10., 10.));
+    painter.draw_tinted_img(icon.clone(), &rect, &None, Color::RED);
+    let rect = Rect::new(Point::new(20., 0.), Size::new(10., 10.));
+    painter.draw_tinted_img(icon, &rect, &None, Color::BLUE);
+
+    let mut backend = GPUBackend::new(block_on(WgpuImpl::headless()));
+    let viewport = DeviceRect::from_size(DeviceSize::new(40, 10));
+    let mut texture = backend
+      .get_impl_mut()
+      .new_texture(viewport.size, ColorFormat::Rgba8);
+    backend.begin_frame(Color::TRANSPARENT);
+    backend.draw_commands(viewport, &painter.finish(), &Transform::identity(), &mut texture);
+    let img = texture.copy_as_image(&viewport, backend.get_impl_mut());
+    // The same icon is drawn in two colors, it's only uploaded once.
+    assert_eq!(backend.tex_mgr.alpha_allocations(), 1);
+    backend.end_frame();
+    let img = block_on(img).unwrap();
+    let pixel = |x: usize, y: usize| {
+      let idx = (y * img.width() as usize + x) * 4;
+      &img.pixel_bytes()[idx..idx + 4]
+    };
+    assert_eq!(pixel(5, 5), Color::RED.into_components());
+    assert_eq!(pixel(25, 5), Color::BLUE.into_components());
+    assert_eq!(pixel(15, 5), Color::TRANSPARENT.into_components());
+  }
+
+  #[test]
+  fn fill_sweep_gradient_disk() {
+    let mut painter = painter(Size::new(100., 100.));
+    let gradient = SweepGradient {
+      center: Point::new(50., 50.),
+      start_angle: 0.,
+      stops: vec![GradientStop::new(Color::RED, 0.), GradientStop::new(Color::BLUE, 1.)],
+    };
+    painter
+      .set_fill_brush(Brush::SweepGradient(gradient))
+      .circle(Point::new(50., 50.), 40.)
+      .fill();
+
+    let viewport = DeviceRect::from_size(DeviceSize::new(100, 100));
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let pixel = |x: usize, y: usize| {
+      let idx = (y * img.width() as usize + x) * 4;
+      let p = &img.pixel_bytes()[idx..idx + 4];
+      Color::new(p[0], p[1], p[2], p[3])
+    };
+
+    // Just after the start angle on the right, the color is almost the first stop.
+    let right = pixel(85, 52);
+    assert!(right.red > 230 && right.blue < 25);
+    // The opposite side is half a turn, the middle of the two stops.
+    let left = pixel(15, 50);
+    assert!((left.red as i32 - left.blue as i32).abs() < 10);
+    // Just before a full turn, the color wraps to almost the last stop.
+    let wrapped = pixel(85, 48);
+    assert!(wrapped.blue > 230 && wrapped.red < 25);
+  }
+
+  #[test]
+  fn custom_draw_in_paint_order() {
+    use ribir_painter::CustomDraw;
+
+    use crate::WgpuCustomDraw;
+
+    let mut painter = painter(Size::new(40., 40.));
+    painter
+      .set_fill_brush(Color::BLUE)
+      .rect(&Rect::from_size(Size::new(40., 40.)))
+      .fill();
+    // Clear the visible region to red by the raw wgpu commands.
+    let clear = WgpuCustomDraw::new(|ctx| {
+      let DeviceRect { origin, size } = ctx.scissor;
+      let data = Color::RED
+        .into_components()
+        .repeat(size.area() as usize);
+      ctx.queue.write_texture(
+        wgpu::ImageCopyTexture {
+          texture: ctx.texture,
+          mip_level: 0,
+          origin: wgpu::Origin3d { x: origin.x as u32, y: origin.y as u32, z: 0 },
+          aspect: wgpu::TextureAspect::All,
+        },
+        &data,
+        wgpu::ImageDataLayout {
+          offset: 0,
+          bytes_per_row: Some(size.width as u32 * 4),
+          rows_per_image: None,
+        },
+        wgpu::Extent3d {
+          width: size.width as u32,
+          height: size.height as u32,
+          depth_or_array_layers: 1,
+        },
+      );
+    });
+    let rect = Rect::new(Point::new(10., 10.), Size::new(20., 20.));
+    painter.draw_custom(&rect, CustomDraw::new(clear));
+    // Painted after the custom content, so it's over it.
+    painter
+      .set_fill_brush(Color::GREEN)
+      .rect(&Rect::new(Point::new(25., 25.), Size::new(15., 15.)))
+      .fill();
+
+    let viewport = DeviceRect::from_size(DeviceSize::new(40, 40));
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let pixel = |x: usize, y: usize| {
+      let idx = (y * img.width() as usize + x) * 4;
+      &img.pixel_bytes()[idx..idx + 4]
+    };
+    assert_eq!(pixel(15, 15), Color::RED.into_components());
+    assert_eq!(pixel(28, 15), Color::RED.into_components());
+    assert_eq!(pixel(5, 5), Color::BLUE.into_components());
+    assert_eq!(pixel(35, 15), Color::BLUE.into_components());
+    assert_eq!(pixel(28, 28), Color::GREEN.into_components());
+  }
 }
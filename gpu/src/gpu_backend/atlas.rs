@@ -9,9 +9,15 @@ use slab::Slab;
 use super::Texture;
 use crate::GPUBackendImpl;
 
+/// The relative tolerance of the scale to reuse a cached handle.
+const SCALE_EPSILON: f32 = 1e-3;
+/// The count of the consecutive frames an extra page keeps empty before it's
+/// released.
+const PAGE_RELEASE_FRAMES: u32 = 8;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub(super) enum AtlasDist {
-  Atlas(Allocation),
+  Atlas { page: usize, alloc: Allocation },
   Extra(usize),
 }
 
@@ -21,19 +27,35 @@ pub struct AtlasHandle {
   pub dist: AtlasDist,
 }
 
+#[derive(Clone)]
 pub(crate) struct AtlasConfig {
   label: &'static str,
+  /// The size of the first page.
+  initial_size: DeviceSize,
+  /// The new pages grow from this size toward the `max_size` to fit the
+  /// allocation.
   min_size: DeviceSize,
   max_size: DeviceSize,
 }
 
+/// A texture of the atlas. The allocations of a page never move, so a slice of
+/// the page is valid until it's deallocated.
+struct Page<T> {
+  /// The allocator of the page, or `None` if the page is an extra texture that
+  /// stores only a single allocation.
+  allocator: Option<AtlasAllocator>,
+  texture: T,
+  /// How many frames the page has been empty.
+  empty_frames: u32,
+}
+
 pub(crate) struct Atlas<K, T: Texture> {
   config: AtlasConfig,
-  atlas_allocator: AtlasAllocator,
-  texture: T,
+  /// The pages of the atlas, the key of a page is its texture id. The first
+  /// page is always kept, and a new page is appended when an allocation not
+  /// fits the existing pages.
+  pages: Slab<Page<T>>,
   cache: FrameCache<K, AtlasHandle>,
-  /// Extra textures which store only single allocation.
-  extras: Slab<T>,
   /// All allocations in the current frame and not cached.
   islands: ahash::HashSet<AtlasDist>,
 }
@@ -44,24 +66,18 @@ where
   T::Host: GPUBackendImpl<Texture = T>,
 {
   pub fn new(config: AtlasConfig, format: ColorFormat, gpu_impl: &mut T::Host) -> Self {
-    let min_size = config.min_size;
-    let texture = gpu_impl.new_texture(min_size, format);
-    Self {
-      config,
-      texture,
-      atlas_allocator: AtlasAllocator::new(min_size.cast_unit()),
-      cache: FrameCache::new(),
-      extras: Slab::default(),
-      islands: <_>::default(),
-    }
+    let mut pages = Slab::default();
+    pages.insert(Page::new(config.initial_size, format, gpu_impl));
+    Self { config, pages, cache: FrameCache::new(), islands: <_>::default() }
   }
 
   pub fn get(&mut self, key: &K, scale: f32) -> Option<&AtlasHandle> {
     self
       .cache
       .get(key)
-      // filter out the handle that scale is too small.
-      .filter(|h| h.scale >= scale * 0.95)
+      // filter out the handle that scale is too small, but allow a tiny jitter of
+      // the scale.
+      .filter(|h| h.scale >= scale * (1. - SCALE_EPSILON))
   }
 
   /// Cache a handle to the atlas. If the key already exists, the old handle
@@ -97,65 +113,76 @@ where
 
     h
   }
-  /// Allocate a rect in the atlas
+  /// Allocate a rect in the atlas.
+  ///
+  /// If the existing pages have no room for the `size`, a new page is appended
+  /// instead of growing and rearranging the existing pages, so the slices
+  /// allocated before are still valid.
   pub fn allocate(&mut self, size: DeviceSize, gpu_impl: &mut T::Host) -> AtlasDist {
-    let current_size = self.size();
     let alloc_size = size.to_i32().cast_unit();
-    let mut alloc = self.atlas_allocator.allocate(alloc_size);
-
-    if alloc.is_none() {
-      let expand_size = (current_size * 2)
-        .max(current_size)
-        .min(self.config.max_size);
-      if expand_size != self.texture.size() {
-        self.atlas_allocator.grow(expand_size.cast_unit());
-        let mut new_tex = gpu_impl.new_texture(expand_size, self.texture.color_format());
-        // Copy old texture to new texture item by item, not copy whole texture. Because
-        // the new texture will overlap with the old texture. And we promise to the
-        // gpu backend implementation that our operations not overlap in one texture in
-        // one frame. So the implementation can batch and reorder the operations to
-        // improve the performance.
-        self
-          .atlas_allocator
-          .for_each_allocated_rectangle(|_, rect| {
-            gpu_impl.copy_texture_from_texture(
-              &mut new_tex,
-              rect.min.cast_unit(),
-              &self.texture,
-              &rect.to_rect().cast_unit(),
-            );
-          });
-
-        self.texture = new_tex;
-        alloc = self.atlas_allocator.allocate(alloc_size);
-      }
-    }
+    let in_page = self.pages.iter_mut().find_map(|(page, p)| {
+      let alloc = p.allocator.as_mut()?.allocate(alloc_size)?;
+      Some(AtlasDist::Atlas { page, alloc })
+    });
 
-    let dist = if let Some(alloc) = alloc {
-      AtlasDist::Atlas(alloc)
+    let dist = if let Some(dist) = in_page {
+      dist
     } else {
-      let texture = gpu_impl.new_texture(size, self.texture.color_format());
-      let id = self.extras.insert(texture);
-      AtlasDist::Extra(id)
+      let format = self.pages[0].texture.color_format();
+      if self.is_good_size_to_alloc(size) {
+        let max_size = self.config.max_size;
+        let mut page_size = self.config.min_size;
+        while size.greater_than(page_size).any() && page_size != max_size {
+          page_size = (page_size * 2).min(max_size);
+        }
+        let mut page = Page::new(page_size, format, gpu_impl);
+        let alloc = page
+          .allocator
+          .as_mut()
+          .and_then(|a| a.allocate(alloc_size))
+          .expect("The new page must have room for the allocation.");
+        AtlasDist::Atlas { page: self.pages.insert(page), alloc }
+      } else {
+        let texture = gpu_impl.new_texture(size, format);
+        let id = self
+          .pages
+          .insert(Page { allocator: None, texture, empty_frames: 0 });
+        AtlasDist::Extra(id)
+      }
     };
     self.islands.insert(dist);
 
     dist
   }
 
+  /// Return the page and the rect that the next `allocate` of `size` will
+  /// occupy, or `None` if the existing pages have no room for it. The placement
+  /// of the allocator is deterministic, so the tests can assert the exact rect
+  /// of an allocation.
+  #[cfg(test)]
+  pub(crate) fn next_free_rect(&self, size: DeviceSize) -> Option<(usize, DeviceRect)> {
+    let alloc_size = size.to_i32().cast_unit();
+    self.pages.iter().find_map(|(page, p)| {
+      let alloc = p.allocator.clone()?.allocate(alloc_size)?;
+      Some((page, alloc.rectangle.to_rect().cast_unit()))
+    })
+  }
+
   /// Get a mut reference of a texture that `id` point to. The `id` get from
   /// `AtlasHandle::tex_id`
-  pub fn get_texture_mut(&mut self, id: usize) -> &mut T {
-    if id == 0 { &mut self.texture } else { &mut self.extras[id - 1] }
-  }
+  pub fn get_texture_mut(&mut self, id: usize) -> &mut T { &mut self.pages[id].texture }
 
   /// Get a reference of a texture that `id` point to. The `id` get from
   /// `AtlasHandle::tex_id`
-  pub fn get_texture(&self, id: usize) -> &T {
-    if id == 0 { &self.texture } else { &self.extras[id - 1] }
-  }
+  pub fn get_texture(&self, id: usize) -> &T { &self.pages[id].texture }
+
+  /// The size of the first page of the atlas.
+  #[cfg(test)]
+  pub(crate) fn size(&self) -> DeviceSize { self.pages[0].texture.size() }
 
-  pub fn size(&self) -> DeviceSize { self.texture.size() }
+  /// The count of the allocations cached in the atlas.
+  #[cfg(test)]
+  pub(crate) fn cached_count(&self) -> usize { self.cache.len() }
 
   /// The max size of the atlas can be.
   pub fn max_size(&self) -> DeviceSize { self.config.max_size }
@@ -165,37 +192,99 @@ where
       && size.area() <= self.config.max_size.area() / 4
   }
 
-  pub(crate) fn end_frame(&mut self) { self.end_frame_with(|_| {}) }
+  /// Set the size of the first page, see [`AtlasConfig::with_initial_size`].
+  /// It's applied when the atlas is cleared.
+  pub(crate) fn set_initial_size(&mut self, size: DeviceSize) {
+    self.config = self.config.clone().with_initial_size(size);
+  }
+
+  /// Drop all the cached handles and the pages, only keep a first page of the
+  /// initial size.
+  pub(crate) fn clear(&mut self, gpu_impl: &mut T::Host) {
+    self.cache.clear();
+    self.islands.clear();
+    let format = self.pages[0].texture.color_format();
+    self.pages.clear();
+    self
+      .pages
+      .insert(Page::new(self.config.initial_size, format, gpu_impl));
+  }
+
+  pub(crate) fn end_frame(&mut self) { self.end_frame_with(|_, _| {}) }
 
-  pub(crate) fn end_frame_with(&mut self, mut on_deallocate: impl FnMut(DeviceRect)) {
+  /// End the frame and release the allocations not used, the `on_deallocate`
+  /// is called with the texture id and the rect of the released allocations
+  /// that are in a living page.
+  pub(crate) fn end_frame_with(&mut self, mut on_deallocate: impl FnMut(usize, DeviceRect)) {
+    let mut released = vec![];
     self
       .cache
       .end_frame(self.config.label)
       .map(|h| h.dist)
       .chain(self.islands.drain())
       .for_each(|dist| match dist {
-        AtlasDist::Atlas(alloc) => {
-          on_deallocate(alloc.rectangle.to_rect().cast_unit());
-          self.atlas_allocator.deallocate(alloc.id);
+        AtlasDist::Atlas { page, alloc } => {
+          if let Some(allocator) = self.pages[page].allocator.as_mut() {
+            allocator.deallocate(alloc.id);
+          }
+          released.push((page, alloc.rectangle.to_rect().cast_unit()));
         }
         AtlasDist::Extra(id) => {
-          self.extras.remove(id);
+          self.pages.remove(id);
         }
       });
+
+    // Release the pages that have been empty for a while, except the first one.
+    // A page is not released as soon as it's empty, so the allocations of the
+    // following frames can reuse it instead of creating a new texture.
+    self.pages.retain(|id, p| {
+      if id == 0 {
+        return true;
+      }
+      match p.allocator.as_ref() {
+        Some(a) if a.is_empty() => p.empty_frames += 1,
+        _ => p.empty_frames = 0,
+      }
+      p.empty_frames < PAGE_RELEASE_FRAMES
+    });
+    released
+      .into_iter()
+      .filter(|(page, _)| self.pages.contains(*page))
+      .for_each(|(page, rect)| on_deallocate(page, rect));
+  }
+}
+
+impl<T: Texture> Page<T>
+where
+  T::Host: GPUBackendImpl<Texture = T>,
+{
+  fn new(size: DeviceSize, format: ColorFormat, gpu_impl: &mut T::Host) -> Self {
+    let texture = gpu_impl.new_texture(size, format);
+    Self { allocator: Some(AtlasAllocator::new(size.cast_unit())), texture, empty_frames: 0 }
   }
 }
 
 impl AtlasConfig {
   pub fn new(label: &'static str, max_size: DeviceSize) -> Self {
-    Self { label, min_size: max_size / 8, max_size }
+    let min_size = max_size / 8;
+    Self { label, initial_size: min_size, min_size, max_size }
+  }
+
+  /// Start the atlas with a first page of the `size` instead of the eighth of
+  /// the max size. A larger page avoids appending pages for the early
+  /// allocations, and a smaller one saves the memory. The size is clamped
+  /// between one pixel and the max size.
+  pub fn with_initial_size(mut self, size: DeviceSize) -> Self {
+    self.initial_size = size.max(DeviceSize::new(1, 1)).min(self.max_size);
+    self
   }
 }
 
 impl AtlasDist {
   pub fn tex_id(&self) -> usize {
     match self {
-      AtlasDist::Atlas(_) => 0,
-      AtlasDist::Extra(id) => *id + 1,
+      AtlasDist::Atlas { page, .. } => *page,
+      AtlasDist::Extra(id) => *id,
     }
   }
 
@@ -204,36 +293,31 @@ impl AtlasDist {
     T: Texture,
   {
     match self {
-      AtlasDist::Atlas(alloc) => alloc.rectangle.to_rect().cast_unit(),
-      AtlasDist::Extra(id) => DeviceRect::from_size(atlas.extras[*id].size()),
+      AtlasDist::Atlas { alloc, .. } => alloc.rectangle.to_rect().cast_unit(),
+      AtlasDist::Extra(id) => DeviceRect::from_size(atlas.pages[*id].texture.size()),
     }
   }
 }
 
 impl AtlasHandle {
-  pub fn tex_id(&self) -> usize {
-    match &self.dist {
-      AtlasDist::Atlas(_) => 0,
-      AtlasDist::Extra(id) => *id + 1,
-    }
-  }
+  pub fn tex_id(&self) -> usize { self.dist.tex_id() }
 
   pub(super) fn tex_rect<K, T>(&self, atlas: &Atlas<K, T>) -> DeviceRect
   where
     T: Texture,
   {
-    match &self.dist {
-      AtlasDist::Atlas(alloc) => alloc.rectangle.to_rect().cast_unit(),
-      AtlasDist::Extra(id) => DeviceRect::from_size(atlas.extras[*id].size()),
-    }
+    self.dist.tex_rect(atlas)
   }
 }
 
 impl Hash for AtlasDist {
   fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
     match self {
-      // hash id enough, because the id is unique.
-      AtlasDist::Atlas(alloc) => alloc.id.hash(state),
+      // hash id enough, because the id is unique in a page.
+      AtlasDist::Atlas { page, alloc } => {
+        page.hash(state);
+        alloc.id.hash(state);
+      }
       AtlasDist::Extra(id) => id.hash(state),
     }
   }
@@ -252,6 +336,16 @@ mod tests {
   use super::*;
   use crate::{WgpuImpl, WgpuTexture};
 
+  fn alloc_count<K, T: Texture>(atlas: &Atlas<K, T>) -> usize {
+    let mut count = 0;
+    atlas
+      .pages
+      .iter()
+      .filter_map(|(_, p)| p.allocator.as_ref())
+      .for_each(|a| a.for_each_allocated_rectangle(|_, _| count += 1));
+    count
+  }
+
   #[test]
   fn resource_hit() {
     let mut gpu = block_on(WgpuImpl::headless());
@@ -273,7 +367,7 @@ mod tests {
   }
 
   #[test]
-  fn atlas_grow_to_alloc() {
+  fn atlas_append_page_to_alloc() {
     let mut gpu_impl = block_on(WgpuImpl::headless());
     let mut atlas = Atlas::<Resource<dyn Any>, WgpuTexture>::new(
       AtlasConfig::new("", DeviceSize::new(4096, 4096)),
@@ -286,7 +380,8 @@ mod tests {
     atlas.cache(Resource::new(1).into_any(), 1., dist);
 
     gpu_impl.end_frame();
-    assert_eq!(dist.tex_id(), 0);
+    assert_eq!(dist.tex_id(), 1);
+    assert_eq!(atlas.size(), atlas.config.min_size);
   }
 
   #[test]
@@ -305,8 +400,38 @@ mod tests {
     atlas.end_frame();
     wgpu.end_frame();
 
-    assert!(atlas.extras.is_empty());
-    assert!(atlas.atlas_allocator.is_empty());
+    assert_eq!(atlas.pages.len(), 1);
+    assert_eq!(alloc_count(&atlas), 0);
+  }
+
+  #[test]
+  fn clear_atlas() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut atlas = Atlas::<Resource<dyn Any>, WgpuTexture>::new(
+      AtlasConfig::new("", DeviceSize::new(4096, 4096)),
+      ColorFormat::Rgba8,
+      &mut wgpu,
+    );
+    let min_size = atlas.config.min_size;
+    let key = Resource::new(1).into_any();
+    atlas.get_or_cache(key.clone(), 1., DeviceSize::new(32, 32), &mut wgpu, |_, _, _| {});
+    // Force the atlas to append a page and allocate an extra texture.
+    atlas.allocate(min_size, &mut wgpu);
+    atlas.allocate(DeviceSize::new(4096, 4096), &mut wgpu);
+    assert_eq!(atlas.pages.len(), 3);
+
+    atlas.clear(&mut wgpu);
+    assert!(atlas.cache.is_empty());
+    assert!(atlas.islands.is_empty());
+    assert_eq!(atlas.pages.len(), 1);
+    assert_eq!(alloc_count(&atlas), 0);
+    assert_eq!(atlas.size(), min_size);
+
+    // The atlas still works after cleared.
+    assert!(atlas.get(&key, 1.).is_none());
+    let h = atlas.get_or_cache(key.clone(), 1., DeviceSize::new(32, 32), &mut wgpu, |_, _, _| {});
+    assert_eq!(atlas.get(&key, 1.), Some(&h));
+    wgpu.end_frame();
   }
 
   #[test]
@@ -324,60 +449,139 @@ mod tests {
     // before the frame end, two allocation for key should keep.
     atlas.cache(key, 1., dist);
 
-    let mut alloc_count = 0;
-    atlas
-      .atlas_allocator
-      .for_each_allocated_rectangle(|_, _| alloc_count += 1);
-    assert_eq!(alloc_count, 2);
+    assert_eq!(alloc_count(&atlas), 2);
 
     atlas.end_frame();
 
     // after end frame, the smaller allocation of the keep should be release.
-    alloc_count = 0;
-    atlas
-      .atlas_allocator
-      .for_each_allocated_rectangle(|_, _| alloc_count += 1);
-    assert_eq!(alloc_count, 1);
+    assert_eq!(alloc_count(&atlas), 1);
   }
 
   #[test]
-  fn fix_atlas_expand_overlap() {
+  fn predict_allocation_rect() {
     let mut wgpu = block_on(WgpuImpl::headless());
     let mut atlas = Atlas::<Resource<dyn Any>, WgpuTexture>::new(
       AtlasConfig::new("", DeviceSize::new(4096, 4096)),
       ColorFormat::Alpha8,
       &mut wgpu,
     );
-    let icon = DeviceSize::new(32, 32);
-    atlas.allocate(icon, &mut wgpu);
 
+    let sizes = [DeviceSize::new(32, 32), DeviceSize::new(64, 16)];
+    for (allocated, size) in sizes.into_iter().enumerate() {
+      let (page, rect) = atlas.next_free_rect(size).unwrap();
+      // Querying doesn't take the room.
+      assert_eq!(atlas.next_free_rect(size), Some((page, rect)));
+      assert_eq!(alloc_count(&atlas), allocated);
+
+      let dist = atlas.allocate(size, &mut wgpu);
+      assert_eq!(dist.tex_id(), page);
+      assert_eq!(dist.tex_rect(&atlas), rect);
+      assert_eq!(rect.size, size);
+    }
+
+    // No room in the existing pages.
+    assert_eq!(atlas.next_free_rect(DeviceSize::new(4096, 4096)), None);
+    wgpu.end_frame();
+  }
+
+  #[test]
+  fn initial_size_avoid_new_page() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut new_atlas = |config: AtlasConfig| {
+      let mut atlas =
+        Atlas::<Resource<dyn Any>, WgpuTexture>::new(config, ColorFormat::Rgba8, &mut wgpu);
+      let dists: Vec<_> = (0..4)
+        .map(|_| atlas.allocate(DeviceSize::new(300, 300), &mut wgpu))
+        .collect();
+      (atlas, dists)
+    };
+
+    let max_size = DeviceSize::new(4096, 4096);
+    // The default first page only fits one of the images.
+    let (atlas, _) = new_atlas(AtlasConfig::new("", max_size));
+    assert!(atlas.pages.len() > 1);
+
+    let config = AtlasConfig::new("", max_size).with_initial_size(DeviceSize::new(1024, 1024));
+    let (atlas, dists) = new_atlas(config);
+    assert_eq!(atlas.size(), DeviceSize::new(1024, 1024));
+    assert_eq!(atlas.pages.len(), 1);
+    assert!(dists.iter().all(|d| d.tex_id() == 0));
+
+    // An empty first page is not allowed.
+    let config = AtlasConfig::new("", max_size).with_initial_size(DeviceSize::new(0, 1024));
+    assert_eq!(config.initial_size, DeviceSize::new(1, 1024));
+    wgpu.end_frame();
+  }
+
+  #[test]
+  fn keep_slice_after_new_page() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut atlas = Atlas::<Resource<dyn Any>, WgpuTexture>::new(
+      AtlasConfig::new("", DeviceSize::new(4096, 4096)),
+      ColorFormat::Alpha8,
+      &mut wgpu,
+    );
+    let icon = DeviceSize::new(32, 32);
+    let icon_dist = atlas.allocate(icon, &mut wgpu);
+    let icon_rect = icon_dist.tex_rect(&atlas);
     atlas
-      .texture
-      .write_data(&DeviceRect::from_size(icon), &[1; 32 * 32], &mut wgpu);
+      .get_texture_mut(icon_dist.tex_id())
+      .write_data(&icon_rect, &[1; 32 * 32], &mut wgpu);
 
     let min_size = atlas.config.min_size;
-    // force atlas to expand
+    // The first page has no room for it, so a new page is appended.
     let h = atlas.allocate(min_size, &mut wgpu);
+    assert_ne!(h.tex_id(), icon_dist.tex_id());
     let second_rect = h.tex_rect(&atlas);
     let second_area: usize = (min_size.width * min_size.height) as usize;
     atlas
-      .texture
+      .get_texture_mut(h.tex_id())
       .write_data(&second_rect, &vec![2; second_area], &mut wgpu);
-    let img = atlas
-      .texture
-      .copy_as_image(&DeviceRect::from_size(atlas.size()), &mut wgpu);
 
+    // The slice allocated before is still valid without re-querying.
+    let img = atlas
+      .get_texture(icon_dist.tex_id())
+      .copy_as_image(&icon_rect, &mut wgpu);
     wgpu.end_frame();
     let img = block_on(img).unwrap();
+    assert!(img.pixel_bytes().iter().all(|v| *v == 1));
+  }
+
+  #[test]
+  fn release_idle_page() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut atlas = Atlas::<Resource<dyn Any>, WgpuTexture>::new(
+      AtlasConfig::new("", DeviceSize::new(4096, 4096)),
+      ColorFormat::Alpha8,
+      &mut wgpu,
+    );
+    let min_size = atlas.config.min_size;
+    let mut spike = |atlas: &mut Atlas<_, _>| {
+      let icon = atlas.allocate(DeviceSize::new(32, 32), &mut wgpu);
+      atlas.cache(Resource::new(1).into_any(), 1., icon);
+      let dist = atlas.allocate(min_size, &mut wgpu);
+      atlas.cache(Resource::new(2).into_any(), 1., dist);
+      atlas.end_frame();
+      dist.tex_id()
+    };
 
-    // check sum of the texture.
-    assert_eq!(
-      img
-        .pixel_bytes()
-        .iter()
-        .map(|v| *v as usize)
-        .sum::<usize>(),
-      icon.area() as usize + second_area * 2
-    )
+    assert_eq!(spike(&mut atlas), 1);
+    assert_eq!(atlas.pages.len(), 2);
+
+    // The empty page is kept for a few frames, and reused by the next spike.
+    for _ in 0..PAGE_RELEASE_FRAMES - 1 {
+      atlas.end_frame();
+    }
+    assert_eq!(atlas.pages.len(), 2);
+    assert_eq!(spike(&mut atlas), 1);
+
+    for _ in 0..PAGE_RELEASE_FRAMES - 1 {
+      atlas.end_frame();
+      assert_eq!(atlas.pages.len(), 2);
+    }
+    atlas.end_frame();
+    assert_eq!(atlas.pages.len(), 1);
+    assert_eq!(alloc_count(&atlas), 0);
+    wgpu.end_frame();
   }
 }
@@ -1,32 +1,42 @@
 use std::{any::Any, cmp::Ordering, hash::Hash, ops::Range};
 
 use guillotiere::euclid::SideOffsets2D;
-use rayon::{prelude::ParallelIterator, slice::ParallelSlice};
+use rayon::{
+  prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
+  slice::ParallelSlice,
+};
 use ribir_algo::Resource;
-use ribir_geom::{DeviceRect, DeviceSize, Size, Transform, transform_to_device_rect};
+use ribir_geom::{DevicePoint, DeviceRect, DeviceSize, Size, Transform, transform_to_device_rect};
 use ribir_painter::{
-  PaintPath, PaintingStyle, Path, PixelImage, StrokeOptions, Vertex, VertexBuffers,
+  FillRule, PaintPath, PaintingStyle, Path, PixelImage, StrokeOptions, Vertex, VertexBuffers,
   image::ColorFormat,
 };
 
 use super::{
-  Texture,
+  AntiAliasQuality, Texture,
   atlas::{Atlas, AtlasConfig, AtlasDist},
 };
 use crate::GPUBackendImpl;
 const TOLERANCE: f32 = 0.1_f32;
 const PAR_CHUNKS_SIZE: usize = 64;
+/// The default minimum size in pixels to tessellate a path.
+const MIN_TESSELLATION_PX: f32 = 32.;
+/// The scale to rasterize the masks for the high anti-aliasing quality.
+const SUPERSAMPLE: i32 = 2;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Copy)]
 pub(super) enum TextureID {
   Alpha(usize),
   Rgba(usize),
   Bundle(usize),
+  /// The texture to rasterize the supersampled masks before they are
+  /// downsampled to the alpha atlas.
+  Scratch,
 }
 
 #[derive(PartialEq, Clone)]
 enum PathKey {
-  Fill(Resource<dyn Any>),
+  Fill { resource: Resource<dyn Any>, rule: FillRule },
   Stroke { resource: Resource<dyn Any>, options: StrokeOptions },
 }
 
@@ -42,7 +52,16 @@ pub(super) struct TexturesMgr<T: Texture> {
   target_atlas: Atlas<Resource<dyn Any>, T>,
   tess_task: Vec<TessTask>,
   tess_task_buffer: VertexBuffers<()>,
-  need_clear_areas: Vec<DeviceRect>,
+  /// The buffers to tessellate the tasks in parallel, one for each chunk. Keep
+  /// them across frames to reuse their allocations.
+  par_tess_buffers: Vec<VertexBuffers<()>>,
+  /// The released areas of the alpha atlas to clear, with the texture id they
+  /// belong to.
+  need_clear_areas: Vec<(usize, DeviceRect)>,
+  /// The tiny paths are tessellated at least this size in pixels.
+  min_tessellation_px: f32,
+  anti_alias: AntiAliasQuality,
+  alpha_scratch: Option<T>,
 }
 
 struct TessTask {
@@ -66,6 +85,7 @@ macro_rules! id_to_texture_mut {
       TextureID::Alpha(id) => $mgr.alpha_atlas.get_texture_mut(id),
       TextureID::Rgba(id) => $mgr.rgba_atlas.get_texture_mut(id),
       TextureID::Bundle(id) => $mgr.target_atlas.get_texture_mut(id),
+      TextureID::Scratch => $mgr.alpha_scratch.as_mut().unwrap(),
     }
   };
 }
@@ -76,6 +96,7 @@ macro_rules! id_to_texture {
       TextureID::Alpha(id) => $mgr.alpha_atlas.get_texture(id),
       TextureID::Rgba(id) => $mgr.rgba_atlas.get_texture(id),
       TextureID::Bundle(id) => $mgr.target_atlas.get_texture(id),
+      TextureID::Scratch => $mgr.alpha_scratch.as_ref().unwrap(),
     }
   };
 }
@@ -106,10 +127,21 @@ where
       ),
       tess_task: <_>::default(),
       tess_task_buffer: <_>::default(),
+      par_tess_buffers: vec![],
       need_clear_areas: vec![],
+      min_tessellation_px: MIN_TESSELLATION_PX,
+      anti_alias: AntiAliasQuality::default(),
+      alpha_scratch: None,
     }
   }
 
+  pub(super) fn set_min_tessellation_px(&mut self, px: f32) { self.min_tessellation_px = px; }
+
+  pub(super) fn set_anti_alias_quality(&mut self, quality: AntiAliasQuality, gpu: &mut T::Host) {
+    self.anti_alias = quality;
+    gpu.set_alpha_anti_alias(quality != AntiAliasQuality::None);
+  }
+
   /// Store an alpha path in texture and return the texture and a transform that
   /// can transform the mask to viewport
   pub(super) fn store_alpha_path(
@@ -122,7 +154,7 @@ where
         let resource = p.clone().into_any();
         let cache_scale: f32 = self.cache_scale(&path_bounds.size, matrix);
         let key = match style {
-          PaintingStyle::Fill => PathKey::Fill(resource),
+          PaintingStyle::Fill => PathKey::Fill { resource, rule: p.fill_rule() },
           PaintingStyle::Stroke(options) => PathKey::Stroke { resource, options: options.clone() },
         };
 
@@ -200,12 +232,12 @@ where
         TextureSlice { tex_id: TextureID::Rgba(h.tex_id()), rect: h.tex_rect(atlas) }
       }
       ColorFormat::Alpha8 => {
-        let key = PathKey::Fill(img.clone().into_any());
+        let key = PathKey::Fill { resource: img.clone().into_any(), rule: FillRule::default() };
         let atlas = &mut self.alpha_atlas;
         let h = atlas.get_or_cache(key, 1., img.size(), gpu, |rect, texture, gpu| {
           texture.write_data(rect, img.pixel_bytes(), gpu)
         });
-        TextureSlice { tex_id: TextureID::Rgba(h.tex_id()), rect: h.tex_rect(atlas) }
+        TextureSlice { tex_id: TextureID::Alpha(h.tex_id()), rect: h.tex_rect(atlas) }
       }
     }
   }
@@ -225,6 +257,10 @@ where
 
   pub(super) fn texture(&self, tex_id: TextureID) -> &T { id_to_texture!(self, tex_id) }
 
+  /// The count of the paths and the alpha images cached in the alpha atlas.
+  #[cfg(test)]
+  pub(super) fn alpha_allocations(&self) -> usize { self.alpha_atlas.cached_count() }
+
   fn alpha_allocate(
     &mut self, mut size: DeviceSize, gpu: &mut T::Host,
   ) -> (AtlasDist, TextureSlice) {
@@ -244,12 +280,12 @@ where
   }
 
   pub(super) fn cache_scale(&self, size: &Size, matrix: &Transform) -> f32 {
-    let Transform { m11, m12, m21, m22, .. } = matrix;
-    let scale = (m11.abs() + m12.abs()).max(m21.abs() + m22.abs());
+    let scale = prefer_scale(matrix);
     let dis = size.width.max(size.height);
-    if dis * scale < 32. {
-      // If the path is too small, set a minimum tessellation size of 32 pixels.
-      32. / dis
+    let min_px = self.min_tessellation_px;
+    if dis * scale < min_px {
+      // If the path is too small, tessellate it at the minimum size.
+      min_px / dis
     } else {
       // 2 * BLANK_EDGE is the blank edge for each side.
       let max_size = size_shrink_blank(self.alpha_atlas.max_size()).to_f32();
@@ -290,11 +326,21 @@ where
     }
 
     if !self.need_clear_areas.is_empty() {
-      let tex = self.alpha_atlas.get_texture_mut(0);
-      tex.clear_areas(&self.need_clear_areas, gpu_impl);
+      self.need_clear_areas.sort_by_key(|(id, _)| *id);
+      for areas in self.need_clear_areas.chunk_by(|a, b| a.0 == b.0) {
+        let rects: Vec<_> = areas.iter().map(|(_, rect)| *rect).collect();
+        let tex = self.alpha_atlas.get_texture_mut(areas[0].0);
+        tex.clear_areas(&rects, gpu_impl);
+      }
       self.need_clear_areas.clear();
     }
 
+    let mut downsamples = if self.anti_alias == AntiAliasQuality::High {
+      self.supersample_tasks(gpu_impl)
+    } else {
+      vec![]
+    };
+
     self.tess_task.sort_by(|a, b| {
       let a_clip = a.clip_rect.is_some();
       let b_clip = b.clip_rect.is_some();
@@ -316,27 +362,30 @@ where
         draw_indices.push((slice.tex_id, rg, clip_rect));
       }
     } else {
-      let mut tasks = Vec::with_capacity(self.tess_task.len());
-      for f in self.tess_task.iter() {
-        let TessTask { slice, path, clip_rect, transform, style } = f;
-        tasks.push((slice, style, transform, path, clip_rect));
+      let chunks = self.tess_task.len().div_ceil(PAR_CHUNKS_SIZE);
+      if self.par_tess_buffers.len() < chunks {
+        self
+          .par_tess_buffers
+          .resize_with(chunks, Default::default);
       }
 
-      let par_tess_res = tasks
+      let par_tess_res = self
+        .tess_task
         .par_chunks(PAR_CHUNKS_SIZE)
-        .map(|tasks| {
-          let mut buffer = VertexBuffers::default();
+        .zip(self.par_tess_buffers.par_iter_mut())
+        .map(|(tasks, buffer)| {
           let mut indices = Vec::with_capacity(tasks.len());
-          for (slice, style, ts, path, clip_rect) in tasks.iter() {
-            let rg = Self::tessellate(path, style, ts, &slice.rect.size, &mut buffer);
-            indices.push((slice.tex_id, rg, *clip_rect));
+          for TessTask { slice, path, clip_rect, transform, style } in tasks.iter() {
+            let rg = Self::tessellate(path, style, transform, &slice.rect.size, buffer);
+            indices.push((slice.tex_id, rg, clip_rect));
           }
-          (indices, buffer)
+          indices
         })
         .collect::<Vec<_>>();
 
       par_tess_res
         .into_iter()
+        .zip(self.par_tess_buffers.iter_mut())
         .for_each(|(indices, buffer)| {
           let offset = self.tess_task_buffer.indices.len() as u32;
           draw_indices.extend(indices.into_iter().map(|(id, mut rg, clip)| {
@@ -389,35 +438,175 @@ where
       gpu_impl.draw_alpha_triangles(&indices, texture);
     }
 
+    if let Some(scratch) = self.alpha_scratch.as_ref() {
+      downsamples.sort_by_key(|(id, ..)| *id);
+      for group in downsamples.chunk_by(|a, b| a.0 == b.0) {
+        let rects: Vec<_> = group
+          .iter()
+          .map(|(_, from, to)| (*from, *to))
+          .collect();
+        let texture = self.alpha_atlas.get_texture_mut(group[0].0);
+        gpu_impl.downsample_texture(texture, scratch, &rects);
+      }
+    }
+
     self.tess_task.clear();
     self.tess_task_buffer.vertices.clear();
     self.tess_task_buffer.indices.clear();
   }
 
+  /// Redirect the tasks to rasterize on the scratch texture at the supersampled
+  /// size, return the atlas texture, the scratch rect and the atlas rect of
+  /// every redirected task to downsample. The tasks too large to fit in the
+  /// scratch texture are kept to rasterize at the normal size.
+  fn supersample_tasks<G: GPUBackendImpl<Texture = T>>(
+    &mut self, gpu_impl: &mut G,
+  ) -> Vec<(usize, DeviceRect, DeviceRect)>
+  where
+    T: Texture<Host = G>,
+  {
+    // A blank edge between the masks, so the samples out of a mask won't
+    // affect its neighbors.
+    let gap = ALPHA_BLANK_EDGE * SUPERSAMPLE;
+    let max_size = self.alpha_atlas.max_size();
+    let mut downsamples = vec![];
+    let (mut x, mut y, mut row_height, mut width) = (0, 0, 0, 0);
+    for task in self.tess_task.iter_mut() {
+      let TextureID::Alpha(atlas_id) = task.slice.tex_id else { continue };
+      let size = task.slice.rect.size * SUPERSAMPLE;
+      if x + size.width > max_size.width {
+        (x, y, row_height) = (0, y + row_height + gap, 0);
+      }
+      if x + size.width > max_size.width || y + size.height > max_size.height {
+        continue;
+      }
+
+      let rect = DeviceRect::new(DevicePoint::new(x, y), size);
+      x += size.width + gap;
+      row_height = row_height.max(size.height);
+      width = width.max(rect.max_x());
+
+      let origin = task.slice.rect.origin.to_f32();
+      let scale = SUPERSAMPLE as f32;
+      task.transform = task
+        .transform
+        .then_translate(-origin.to_vector().cast_unit())
+        .then_scale(scale, scale)
+        .then_translate(rect.origin.to_f32().to_vector().cast_unit());
+      task.clip_rect = task.clip_rect.map(|_| rect);
+      downsamples.push((atlas_id, rect, task.slice.rect));
+      task.slice = TextureSlice { tex_id: TextureID::Scratch, rect };
+    }
+
+    if downsamples.is_empty() {
+      return downsamples;
+    }
+
+    let mut size = DeviceSize::new(width, y + row_height);
+    if let Some(scratch) = self.alpha_scratch.as_ref() {
+      let scratch_size = scratch.size();
+      size.width = size.width.max(scratch_size.width);
+      size.height = size.height.max(scratch_size.height);
+    }
+    if self.alpha_scratch.as_ref().map(|t| t.size()) != Some(size) {
+      self.alpha_scratch = Some(gpu_impl.new_texture(size, ColorFormat::Alpha8));
+    }
+    let scratch = self.alpha_scratch.as_mut().unwrap();
+    let areas: Vec<_> = downsamples
+      .iter()
+      .map(|(_, rect, _)| *rect)
+      .collect();
+    scratch.clear_areas(&areas, gpu_impl);
+
+    downsamples
+  }
+
+  /// Tessellate and draw the pending paths to the alpha atlas now, rather than
+  /// waiting for the draw phase of the frame. After it, all the stored paths
+  /// and images are committed, and can be read back deterministically.
+  pub(crate) fn flush<G: GPUBackendImpl<Texture = T>>(&mut self, gpu_impl: &mut G)
+  where
+    T: Texture<Host = G>,
+  {
+    self.draw_alpha_textures(gpu_impl);
+  }
+
+  /// Store and rasterize the `paths` and `images` ahead, so the frames that
+  /// paint them later hit the caches. The paths are filled by their fill rules
+  /// and rasterized at the scale of their transforms.
+  pub(crate) fn prewarm<G: GPUBackendImpl<Texture = T>>(
+    &mut self, paths: &[(Resource<Path>, Transform)], images: &[Resource<PixelImage>],
+    gpu_impl: &mut G,
+  ) where
+    T: Texture<Host = G>,
+  {
+    let viewport = DeviceRect::from_size(self.alpha_atlas.max_size());
+    for (path, ts) in paths {
+      let path = PaintPath::Share(path.clone());
+      self.store_alpha_path(&path, &PaintingStyle::Fill, ts, &viewport, gpu_impl);
+    }
+    for img in images {
+      self.store_image(img, gpu_impl);
+    }
+    self.draw_alpha_textures(gpu_impl);
+  }
+
+  /// Set the size of the first page of the atlases, see
+  /// `GPUBackend::set_atlas_initial_size`.
+  pub(crate) fn set_atlas_initial_size(&mut self, size: DeviceSize, gpu_impl: &mut T::Host) {
+    self.alpha_atlas.set_initial_size(size);
+    self.rgba_atlas.set_initial_size(size);
+    self.target_atlas.set_initial_size(size);
+    self.clear_caches(gpu_impl);
+  }
+
+  /// Drop all the cached paths and images to release the memory, the atlases
+  /// are shrunk back to their minimum size. It should be called between frames.
+  pub(crate) fn clear_caches(&mut self, gpu_impl: &mut T::Host) {
+    self.alpha_atlas.clear(gpu_impl);
+    self.rgba_atlas.clear(gpu_impl);
+    self.target_atlas.clear(gpu_impl);
+    self.tess_task.clear();
+    self.alpha_scratch = None;
+    // The new alpha texture is blank, nothing need to clear.
+    self.need_clear_areas.clear();
+    self.par_tess_buffers = vec![];
+  }
+
   pub(crate) fn end_frame(&mut self) {
-    self.alpha_atlas.end_frame_with(|rect| {
-      self.need_clear_areas.push(rect);
+    self.alpha_atlas.end_frame_with(|id, rect| {
+      self.need_clear_areas.push((id, rect));
     });
     self.rgba_atlas.end_frame();
     self.target_atlas.end_frame();
   }
 }
 
-fn extend_buffer<V>(dist: &mut VertexBuffers<V>, from: VertexBuffers<V>) {
+/// Move the vertices and indices of `from` to `dist`, `from` is left empty but
+/// keeps its allocation.
+fn extend_buffer<V>(dist: &mut VertexBuffers<V>, from: &mut VertexBuffers<V>) {
   if dist.vertices.is_empty() {
-    dist.vertices.extend(from.vertices);
-    dist.indices.extend(from.indices);
+    dist.vertices.append(&mut from.vertices);
+    dist.indices.append(&mut from.indices);
   } else {
     let offset = dist.vertices.len() as u32;
     dist
       .indices
-      .extend(from.indices.into_iter().map(|i| offset + i));
-    dist.vertices.extend(from.vertices);
+      .extend(from.indices.drain(..).map(|i| offset + i));
+    dist.vertices.append(&mut from.vertices);
   }
 }
 
 const ALPHA_BLANK_EDGE: i32 = 2;
 
+/// The scale of the device pixels to the path units, it's the length of the
+/// axes mapped by the full transform, the fractional scale factor of the
+/// window is included, so the mask is rasterized at the device resolution.
+fn prefer_scale(matrix: &Transform) -> f32 {
+  let Transform { m11, m12, m21, m22, .. } = matrix;
+  m11.hypot(*m12).max(m21.hypot(*m22))
+}
+
 fn size_expand_blank(mut size: DeviceSize) -> DeviceSize {
   size.width += ALPHA_BLANK_EDGE * 2;
   size.height += ALPHA_BLANK_EDGE * 2;
@@ -442,7 +631,10 @@ impl TextureSlice {
 impl Hash for PathKey {
   fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
     match self {
-      PathKey::Fill(path) => path.hash(state),
+      PathKey::Fill { resource: path, rule } => {
+        path.hash(state);
+        rule.hash(state);
+      }
       PathKey::Stroke { resource: path, options } => {
         path.hash(state);
         let StrokeOptions { width, miter_limit, line_cap, line_join } = options;
@@ -496,7 +688,7 @@ pub mod tests {
     let yellow_img = color_image(Color::YELLOW, 64, 64);
     let yellow_rect = mgr.store_image(&yellow_img, &mut wgpu);
 
-    // the color should keep after atlas rearrange
+    // the color should keep after atlas appends new page
     color_img_check(&mgr, &red_rect, &mut wgpu, Color::RED);
     color_img_check(&mgr, &yellow_rect, &mut wgpu, Color::YELLOW);
 
@@ -555,6 +747,362 @@ pub mod tests {
     assert_eq!(ts2, Transform::new(0.5, 0., 0., 0.5, 99., 99.));
   }
 
+  #[test]
+  fn rerasterize_path_at_higher_scale() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+
+    let p = Resource::new(Path::rect(&rect(0., 0., 100., 100.)));
+    let p = PaintPath::Share(p.clone());
+    let viewport = rect(0, 0, 1024, 1024);
+    let mut store = |scale: f32| {
+      let ts = Transform::scale(scale, scale);
+      mgr
+        .store_alpha_path(&p, &PaintingStyle::Fill, &ts, &viewport, &mut wgpu)
+        .0
+    };
+
+    let low = store(1.);
+    // The scale factor of the window changed, the window scales all the paths.
+    let high = store(2.);
+    assert_ne!(low, high);
+    assert!(high.rect.width() > low.rect.width() * 3 / 2);
+
+    // The higher resolution mask is reused when the scale factor is back.
+    assert_eq!(store(1.), high);
+  }
+
+  #[test]
+  fn min_tessellation_size() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+
+    let viewport = rect(0, 0, 1024, 1024);
+    let mut store = |mgr: &mut TexturesMgr<WgpuTexture>| {
+      let p = PaintPath::Share(Resource::new(Path::rect(&rect(0., 0., 4., 4.))));
+      let ts = Transform::identity();
+      mgr
+        .store_alpha_path(&p, &PaintingStyle::Fill, &ts, &viewport, &mut wgpu)
+        .0
+    };
+
+    // The masks are expanded one pixel on each side for pasting.
+    assert_eq!(store(&mut mgr).rect.size, DeviceSize::new(34, 34));
+    mgr.set_min_tessellation_px(64.);
+    assert_eq!(store(&mut mgr).rect.size, DeviceSize::new(66, 66));
+  }
+
+  #[test]
+  fn disable_min_tessellation() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+
+    let viewport = rect(0, 0, 1024, 1024);
+    let mut store = |mgr: &mut TexturesMgr<WgpuTexture>| {
+      let icon = Path::circle(Point::new(6., 6.), 6.);
+      let p = PaintPath::Share(Resource::new(icon));
+      mgr
+        .store_alpha_path(&p, &PaintingStyle::Fill, &Transform::identity(), &viewport, &mut wgpu)
+        .0
+    };
+
+    let enabled = store(&mut mgr).rect.size;
+    mgr.set_min_tessellation_px(0.);
+    let disabled = store(&mut mgr).rect.size;
+    // Tessellated at the displayed size, only the blank edges are expanded.
+    assert_eq!(disabled, DeviceSize::new(14, 14));
+    assert!(disabled.area() * 4 < enabled.area());
+  }
+
+  #[test]
+  fn fractional_scale_factor() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+
+    let size = Size::new(100., 100.);
+    let p = PaintPath::Share(Resource::new(Path::rect(&Rect::from_size(size))));
+    let ts = Transform::scale(1.25, 1.25).then_translate(Vector::new(10.5, 20.5));
+    assert_eq!(mgr.cache_scale(&size, &ts), 1.25);
+
+    let viewport = rect(0, 0, 1024, 1024);
+    let mut store = |scale: f32| {
+      let ts = Transform::scale(scale, scale);
+      mgr
+        .store_alpha_path(&p, &PaintingStyle::Fill, &ts, &viewport, &mut wgpu)
+        .0
+    };
+
+    // A mask of a little lower scale is not good enough for the 1.25 scale.
+    let low = store(1.2);
+    let mask = store(1.25);
+    assert_ne!(low, mask);
+    // The mask is 125 pixels, and expanded one pixel on each side for pasting.
+    assert_eq!(mask.rect.size, DeviceSize::new(127, 127));
+
+    // The tiny jitter of the scale reuses the cache.
+    assert_eq!(store(1.25 - 1e-5), mask);
+    assert_eq!(store(1.25), mask);
+    assert_eq!(mgr.tess_task.len(), 2);
+  }
+
+  fn star(fill_rule: FillRule) -> PaintPath {
+    let center = Point::new(50., 50.);
+    let vertex = |i: usize| {
+      let angle = Angle::degrees(-90. + 144. * i as f32);
+      center + Vector::from_angle_and_length(angle, 50.)
+    };
+    let mut builder = Path::builder();
+    builder.begin_path(vertex(0));
+    (1..5).for_each(|i| {
+      builder.line_to(vertex(i));
+    });
+    builder.end_path(true);
+    PaintPath::Share(Resource::new(builder.build().with_fill_rule(fill_rule)))
+  }
+
+  #[test]
+  fn fill_rule_of_self_overlapping_path() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+    let viewport = rect(0, 0, 1024, 1024);
+
+    let mut center_alpha = |rule| {
+      let (slice, ts) = mgr.store_alpha_path(
+        &star(rule),
+        &PaintingStyle::Fill,
+        &Transform::identity(),
+        &viewport,
+        &mut wgpu,
+      );
+      let center = ts
+        .inverse()
+        .unwrap()
+        .transform_point(Point::new(50., 50.))
+        .to_i32()
+        .cast_unit();
+
+      wgpu.begin_frame();
+      mgr.draw_alpha_textures(&mut wgpu);
+      let img = mgr
+        .texture(slice.tex_id)
+        .copy_as_image(&DeviceRect::new(center, DeviceSize::new(1, 1)), &mut wgpu);
+      wgpu.end_frame();
+      block_on(img).unwrap().pixel_bytes()[0]
+    };
+
+    let non_zero = center_alpha(FillRule::NonZero);
+    let even_odd = center_alpha(FillRule::EvenOdd);
+    // The center of the star is overlapped twice, so it's a hole for even-odd.
+    assert_eq!(non_zero, 255);
+    assert_eq!(even_odd, 0);
+  }
+
+  #[test]
+  fn anti_alias_quality_of_circle_edge() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let viewport = rect(0, 0, 1024, 1024);
+
+    // The count of the distinct partial alpha values on the edge of a circle.
+    let mut edge_levels = |quality| {
+      let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+      mgr.set_anti_alias_quality(quality, &mut wgpu);
+      let circle = Path::circle(Point::new(20., 20.), 20.);
+      let (slice, _) = mgr.store_alpha_path(
+        &PaintPath::Share(Resource::new(circle)),
+        &PaintingStyle::Fill,
+        &Transform::identity(),
+        &viewport,
+        &mut wgpu,
+      );
+
+      wgpu.begin_frame();
+      mgr.draw_alpha_textures(&mut wgpu);
+      let img = mgr
+        .texture(slice.tex_id)
+        .copy_as_image(&slice.rect, &mut wgpu);
+      wgpu.end_frame();
+      let mut levels: Vec<_> = block_on(img)
+        .unwrap()
+        .pixel_bytes()
+        .iter()
+        .filter(|a| **a != 0 && **a != 255)
+        .copied()
+        .collect();
+      levels.sort();
+      levels.dedup();
+      levels.len()
+    };
+
+    assert_eq!(edge_levels(AntiAliasQuality::None), 0);
+    let normal = edge_levels(AntiAliasQuality::Normal);
+    // 6 samples of a pixel have 5 partial coverages at most.
+    assert!(0 < normal && normal <= 5);
+    assert!(edge_levels(AntiAliasQuality::High) > normal);
+  }
+
+  #[test]
+  fn flush_before_read_back() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+    let viewport = rect(0, 0, 1024, 1024);
+
+    let path = PaintPath::Own(Path::rect(&rect(0., 0., 20., 20.)));
+    let (slice, ts) = mgr.store_alpha_path(
+      &path,
+      &PaintingStyle::Fill,
+      &Transform::identity(),
+      &viewport,
+      &mut wgpu,
+    );
+    let center = ts
+      .inverse()
+      .unwrap()
+      .transform_point(Point::new(10., 10.))
+      .to_i32()
+      .cast_unit();
+
+    wgpu.begin_frame();
+    mgr.flush(&mut wgpu);
+    let img = mgr
+      .texture(slice.tex_id)
+      .copy_as_image(&DeviceRect::new(center, DeviceSize::new(1, 1)), &mut wgpu);
+    wgpu.end_frame();
+    assert_eq!(block_on(img).unwrap().pixel_bytes()[0], 255);
+  }
+
+  #[test]
+  fn prewarm_path() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+    let viewport = rect(0, 0, 1024, 1024);
+
+    let path = Resource::new(Path::rect(&rect(0., 0., 50., 50.)));
+    let ts = Transform::scale(2., 2.);
+    wgpu.begin_frame();
+    mgr.prewarm(&[(path.clone(), ts)], &[color_image(Color::RED, 8, 8)], &mut wgpu);
+    wgpu.end_frame();
+    assert!(mgr.tess_task.is_empty());
+
+    let (slice, _) = mgr.store_alpha_path(
+      &PaintPath::Share(path),
+      &PaintingStyle::Fill,
+      &ts,
+      &viewport,
+      &mut wgpu,
+    );
+    // Hit the cache, no new task to rasterize the path.
+    assert!(mgr.tess_task.is_empty());
+    assert_eq!(slice.tex_id, TextureID::Alpha(0));
+  }
+
+  #[test]
+  fn extend_from_pooled_buffer() {
+    let mut dist = VertexBuffers::<()>::default();
+    let mut pooled = VertexBuffers::<()>::default();
+    for i in 0..2 {
+      pooled
+        .vertices
+        .extend((0..3).map(|_| Vertex::new([0., i as f32], ())));
+      pooled.indices.extend([0, 1, 2]);
+      extend_buffer(&mut dist, &mut pooled);
+      assert!(pooled.vertices.is_empty() && pooled.indices.is_empty());
+      assert!(pooled.vertices.capacity() >= 3);
+    }
+
+    assert_eq!(dist.vertices.len(), 6);
+    assert_eq!(dist.indices, [0, 1, 2, 3, 4, 5]);
+  }
+
+  #[test]
+  fn reuse_par_tess_buffers() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+    let viewport = rect(0, 0, 1024, 1024);
+
+    // Enough paths to tessellate in parallel, and draw twice to reuse the buffers.
+    for _ in 0..2 {
+      let slices = (0..PAR_CHUNKS_SIZE * 2 + 1)
+        .map(|_| {
+          let size = 10.;
+          let path = PaintPath::Share(Resource::new(Path::rect(&rect(0., 0., size, size))));
+          let center = Point::new(size / 2., size / 2.);
+          let (slice, ts) = mgr.store_alpha_path(
+            &path,
+            &PaintingStyle::Fill,
+            &Transform::identity(),
+            &viewport,
+            &mut wgpu,
+          );
+          let center = ts.inverse().unwrap().transform_point(center);
+          (slice, center.to_i32().cast_unit())
+        })
+        .collect::<Vec<_>>();
+
+      wgpu.begin_frame();
+      mgr.draw_alpha_textures(&mut wgpu);
+      wgpu.end_frame();
+
+      for (slice, center) in slices.iter() {
+        wgpu.begin_frame();
+        let img = mgr
+          .texture(slice.tex_id)
+          .copy_as_image(&DeviceRect::new(*center, DeviceSize::new(1, 1)), &mut wgpu);
+        wgpu.end_frame();
+        assert_eq!(block_on(img).unwrap().pixel_bytes()[0], 255);
+      }
+      assert!(
+        mgr
+          .par_tess_buffers
+          .iter()
+          .all(|b| b.indices.is_empty())
+      );
+      mgr.end_frame();
+    }
+  }
+
+  #[test]
+  fn clear_caches() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    let mut mgr = TexturesMgr::<WgpuTexture>::new(&mut wgpu);
+
+    let red_img = color_image(Color::RED, 32, 32);
+    let red_rect = mgr.store_image(&red_img, &mut wgpu);
+    let path = PaintPath::Share(Resource::new(Path::rect(&rect(0., 0., 100., 100.))));
+    let viewport = rect(0, 0, 1024, 1024);
+    let (slice, _) = mgr.store_alpha_path(
+      &path,
+      &PaintingStyle::Fill,
+      &Transform::identity(),
+      &viewport,
+      &mut wgpu,
+    );
+    wgpu.begin_frame();
+    mgr.draw_alpha_textures(&mut wgpu);
+    wgpu.end_frame();
+    mgr.end_frame();
+
+    mgr.clear_caches(&mut wgpu);
+    assert!(
+      mgr
+        .rgba_atlas
+        .get(&red_img.clone().into_any(), 1.)
+        .is_none()
+    );
+
+    // Store again after the caches are cleared.
+    let new_red_rect = mgr.store_image(&red_img, &mut wgpu);
+    assert_eq!(new_red_rect, red_rect);
+    color_img_check(&mgr, &new_red_rect, &mut wgpu, Color::RED);
+    let (new_slice, _) = mgr.store_alpha_path(
+      &path,
+      &PaintingStyle::Fill,
+      &Transform::identity(),
+      &viewport,
+      &mut wgpu,
+    );
+    assert_eq!(new_slice, slice);
+  }
+
   #[test]
   fn fix_resource_address_conflict() {
     // because the next resource may allocate at same address of a deallocated
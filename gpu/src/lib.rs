@@ -4,7 +4,7 @@ use std::ops::Range;
 
 pub use gpu_backend::Texture;
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize};
-use ribir_painter::{Color, GradientStop, VertexBuffers, image::ColorFormat};
+use ribir_painter::{Color, CustomDraw, GradientStop, VertexBuffers, image::ColorFormat};
 mod gpu_backend;
 use zerocopy::AsBytes;
 
@@ -59,8 +59,15 @@ pub use wgpu_impl::*;
 ///   |     |  +------------------------------------+    |
 ///   |     |  | load_linear_gradient_primitives()  |    |
 ///   |     +->| load_linear_gradient_stops()       |    |
-///   |        | load_linear_gradient_vertices()    |    |
-///   |        | draw_linear_gradient_triangles()   |    |
+///   |     |  | load_linear_gradient_vertices()    |    |
+///   |     |  | draw_linear_gradient_triangles()   |    |
+///   |     |  +------------------------------------+    |
+///   |     |                                            |
+///   |     |  +------------------------------------+    |
+///   |     |  | load_sweep_gradient_primitives()   |    |
+///   |     +->| load_sweep_gradient_stops()        |    |
+///   |        | load_sweep_gradient_vertices()     |    |
+///   |        | draw_sweep_gradient_triangles()    |    |
 ///   |        +------------------------------------+    |
 ///   +---<----------------------------------------------+
 ///
@@ -100,6 +107,16 @@ pub trait GPUBackendImpl {
   fn draw_alpha_triangles_with_scissor(
     &mut self, indices: &Range<u32>, texture: &mut Self::Texture, scissor: DeviceRect,
   );
+  /// Set whether the alpha triangles are drawn with multiple samples per pixel
+  /// to smooth the edges, or every pixel is either fully covered or not.
+  fn set_alpha_anti_alias(&mut self, anti_alias: bool);
+  /// Draw every `from` rect of the `from_tex` to its paired `to` rect of the
+  /// `dist_tex`, the content is scaled with the linear filter. Used to
+  /// downsample the supersampled alpha masks.
+  fn downsample_texture(
+    &mut self, dist_tex: &mut Self::Texture, from_tex: &Self::Texture,
+    rects: &[(DeviceRect, DeviceRect)],
+  );
 
   /// load textures that will be use in this draw phase
   fn load_textures(&mut self, textures: &[&Self::Texture]);
@@ -131,6 +148,15 @@ pub trait GPUBackendImpl {
   /// Load the vertices and indices buffer that `draw_linear_gradient_triangles`
   /// will use.
   fn load_linear_gradient_vertices(&mut self, buffers: &VertexBuffers<LinearGradientPrimIndex>);
+
+  /// Load the primitives that `draw_sweep_gradient_triangles` will use.
+  fn load_sweep_gradient_primitives(&mut self, primitives: &[SweepGradientPrimitive]);
+  /// Load the gradient color stops that `draw_sweep_gradient_triangles` will
+  /// use.
+  fn load_sweep_gradient_stops(&mut self, stops: &[GradientStopPrimitive]);
+  /// Load the vertices and indices buffer that `draw_sweep_gradient_triangles`
+  /// will use.
+  fn load_sweep_gradient_vertices(&mut self, buffers: &VertexBuffers<SweepGradientPrimIndex>);
   /// Draw pure color triangles in the texture. And use the clear color clear
   /// the texture first if it's a Some-Value
   fn draw_color_triangles(
@@ -153,10 +179,24 @@ pub trait GPUBackendImpl {
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   );
 
+  /// Draw triangles fill with color sweep gradient. And use the clear color
+  /// clear the texture first if it's a Some-Value
+  fn draw_sweep_gradient_triangles(
+    &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
+  );
+
   fn copy_texture_from_texture(
     &mut self, dist_tex: &mut Self::Texture, copy_to: DevicePoint, from_tex: &Self::Texture,
     from_rect: &DeviceRect,
   );
+
+  /// Draw the `rect` of the texture by the custom `draw` callback, the callback
+  /// should only draw in the `scissor`. The callbacks the backend doesn't know
+  /// are ignored.
+  fn draw_custom(
+    &mut self, texture: &mut Self::Texture, draw: &CustomDraw, rect: &DeviceRect,
+    scissor: &DeviceRect,
+  );
   /// A frame end, call once per frame
   fn end_frame(&mut self);
 }
@@ -176,6 +216,9 @@ pub struct DrawPhaseLimits {
   /// The maximum number of linear gradient primitives that the backend can load
   /// in a single draw
   pub max_linear_gradient_primitives: usize,
+  /// The maximum number of sweep gradient primitives that the backend can load
+  /// in a single draw
+  pub max_sweep_gradient_primitives: usize,
   /// The maximum number of gradient stops that the backend can load in a single
   /// draw phase
   pub max_gradient_stop_primitives: usize,
@@ -204,6 +247,10 @@ pub struct RadialGradientPrimIndex(u32);
 #[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
 pub struct LinearGradientPrimIndex(u32);
 
+#[repr(packed)]
+#[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
+pub struct SweepGradientPrimIndex(u32);
+
 #[repr(packed)]
 #[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
 pub struct GradientStopPrimitive {
@@ -263,6 +310,24 @@ pub struct LinearGradientPrimitive {
   pub mask_head_and_spread: i32,
 }
 
+#[repr(packed)]
+#[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
+pub struct SweepGradientPrimitive {
+  /// A 2x3 column-major matrix, transform a vertex position to the texture
+  /// position
+  pub transform: [f32; 6],
+  /// position of the center
+  pub center: [f32; 2],
+  /// the angle in radians where the gradient starts.
+  pub start_angle: f32,
+  /// The color stop's start index
+  pub stop_start: u32,
+  /// The size of the color stop
+  pub stop_cnt: u32,
+  /// The index of the head mask layer.
+  pub mask_head: i32,
+}
+
 #[repr(packed)]
 #[derive(AsBytes, PartialEq, Clone, Copy)]
 pub struct ImgPrimitive {
@@ -279,6 +344,10 @@ pub struct ImgPrimitive {
   pub mask_head_and_tex_idx: i32,
   /// extra alpha apply to current vertex
   pub opacity: f32,
+  /// The color to tint an alpha image, the red channel of the texture is used
+  /// as the alpha of the color. The image is not tinted if the alpha is
+  /// negative.
+  pub tint: [f32; 4],
 }
 
 /// The mask layer describes an alpha channel layer that is used in the fragment
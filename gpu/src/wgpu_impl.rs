@@ -6,7 +6,7 @@ use std::{
 
 use futures::channel::oneshot;
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize};
-use ribir_painter::{Color, PixelImage, VertexBuffers, image::ColorFormat};
+use ribir_painter::{Color, CustomDraw, PixelImage, VertexBuffers, image::ColorFormat};
 
 use self::{
   draw_alpha_triangles_pass::DrawAlphaTrianglesPass,
@@ -14,13 +14,14 @@ use self::{
   draw_img_triangles_pass::DrawImgTrianglesPass,
   draw_linear_gradient_pass::DrawLinearGradientTrianglesPass,
   draw_radial_gradient_pass::DrawRadialGradientTrianglesPass,
+  draw_sweep_gradient_pass::DrawSweepGradientTrianglesPass,
   texture_pass::{ClearTexturePass, CopyTexturePass},
   uniform::Uniform,
 };
 use crate::{
   ColorAttr, DrawPhaseLimits, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex, ImgPrimitive,
   LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer, RadialGradientPrimIndex,
-  RadialGradientPrimitive, gpu_backend::Texture,
+  RadialGradientPrimitive, SweepGradientPrimIndex, SweepGradientPrimitive, gpu_backend::Texture,
 };
 mod shaders;
 mod uniform;
@@ -31,11 +32,13 @@ mod draw_color_triangles_pass;
 mod draw_img_triangles_pass;
 mod draw_linear_gradient_pass;
 mod draw_radial_gradient_pass;
+mod draw_sweep_gradient_pass;
 mod texture_pass;
 
 pub const TEX_PER_DRAW: usize = 8;
 
 pub struct WgpuImpl {
+  adapter: wgpu::Adapter,
   device: wgpu::Device,
   queue: wgpu::Queue,
 
@@ -50,10 +53,28 @@ pub struct WgpuImpl {
   img_triangles_pass: Option<DrawImgTrianglesPass>,
   radial_gradient_pass: Option<DrawRadialGradientTrianglesPass>,
   linear_gradient_pass: Option<DrawLinearGradientTrianglesPass>,
+  sweep_gradient_pass: Option<DrawSweepGradientTrianglesPass>,
   texs_layout: wgpu::BindGroupLayout,
   textures_bind: Option<wgpu::BindGroup>,
   mask_layers_uniform: Uniform<MaskLayer>,
   limits: DrawPhaseLimits,
+  msaa_samples: u32,
+  /// The multisampled targets in the order of the recent usage, the first is
+  /// the last used one.
+  msaa_targets: Vec<MsaaTarget>,
+}
+
+/// The maximum count of the multisampled targets kept for reuse.
+const MAX_MSAA_TARGETS: usize = 4;
+
+/// The multisampled texture that the triangles are drawn to, and then resolved
+/// to the texture it bound to.
+pub(crate) struct MsaaTarget {
+  view: wgpu::TextureView,
+  size: DeviceSize,
+  format: wgpu::TextureFormat,
+  samples: u32,
+  resolve_to: Option<wgpu::Id<wgpu::Texture>>,
 }
 
 macro_rules! command_encoder {
@@ -125,6 +146,21 @@ macro_rules! linear_gradient_pass {
   };
 }
 
+macro_rules! sweep_gradient_pass {
+  ($backend:ident) => {
+    $backend
+      .sweep_gradient_pass
+      .get_or_insert_with(|| {
+        DrawSweepGradientTrianglesPass::new(
+          &$backend.device,
+          $backend.mask_layers_uniform.layout(),
+          &$backend.texs_layout,
+          &$backend.limits,
+        )
+      })
+  };
+}
+
 pub(crate) use command_encoder;
 
 pub struct Surface<'a> {
@@ -216,6 +252,18 @@ impl GPUBackendImpl for WgpuImpl {
     linear_gradient_pass!(self).load_triangles_vertices(buffers, &self.device, &self.queue);
   }
 
+  fn load_sweep_gradient_primitives(&mut self, primitives: &[SweepGradientPrimitive]) {
+    sweep_gradient_pass!(self).load_sweep_gradient_primitives(&self.queue, primitives);
+  }
+
+  fn load_sweep_gradient_stops(&mut self, stops: &[GradientStopPrimitive]) {
+    sweep_gradient_pass!(self).load_gradient_stops(&self.queue, stops);
+  }
+
+  fn load_sweep_gradient_vertices(&mut self, buffers: &VertexBuffers<SweepGradientPrimIndex>) {
+    sweep_gradient_pass!(self).load_triangles_vertices(buffers, &self.device, &self.queue);
+  }
+
   fn load_mask_layers(&mut self, layers: &[crate::MaskLayer]) {
     self
       .mask_layers_uniform
@@ -223,19 +271,19 @@ impl GPUBackendImpl for WgpuImpl {
   }
 
   fn draw_alpha_triangles(&mut self, indices: &Range<u32>, texture: &mut Self::Texture) {
-    let encoder = command_encoder!(self);
-    self
-      .alpha_triangles_pass
-      .draw_alpha_triangles(indices, texture, None, &self.queue, encoder);
+    self.draw_alpha(indices, texture, None);
   }
 
   fn draw_radial_gradient_triangles(
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
+    let use_msaa = self.prepare_msaa(texture, clear);
+    let msaa = self.msaa_targets.first().filter(|_| use_msaa);
     let encoder = command_encoder!(self);
 
     radial_gradient_pass!(self).draw_triangles(
       texture,
+      msaa,
       indices,
       clear,
       &self.device,
@@ -250,10 +298,13 @@ impl GPUBackendImpl for WgpuImpl {
   fn draw_linear_gradient_triangles(
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
+    let use_msaa = self.prepare_msaa(texture, clear);
+    let msaa = self.msaa_targets.first().filter(|_| use_msaa);
     let encoder = command_encoder!(self);
 
     linear_gradient_pass!(self).draw_triangles(
       texture,
+      msaa,
       indices,
       clear,
       &self.device,
@@ -265,25 +316,56 @@ impl GPUBackendImpl for WgpuImpl {
     self.submit()
   }
 
-  fn draw_alpha_triangles_with_scissor(
-    &mut self, indices: &Range<u32>, texture: &mut Self::Texture, scissor: DeviceRect,
+  fn draw_sweep_gradient_triangles(
+    &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
+    let use_msaa = self.prepare_msaa(texture, clear);
+    let msaa = self.msaa_targets.first().filter(|_| use_msaa);
     let encoder = command_encoder!(self);
-    self.alpha_triangles_pass.draw_alpha_triangles(
-      indices,
+
+    sweep_gradient_pass!(self).draw_triangles(
       texture,
-      Some(scissor),
-      &self.queue,
+      msaa,
+      indices,
+      clear,
+      &self.device,
       encoder,
+      self.textures_bind.as_ref().unwrap(),
+      &self.mask_layers_uniform,
     );
+
+    self.submit()
+  }
+
+  fn draw_alpha_triangles_with_scissor(
+    &mut self, indices: &Range<u32>, texture: &mut Self::Texture, scissor: DeviceRect,
+  ) {
+    self.draw_alpha(indices, texture, Some(scissor));
+  }
+
+  fn set_alpha_anti_alias(&mut self, anti_alias: bool) {
+    self
+      .alpha_triangles_pass
+      .set_anti_alias(anti_alias);
+  }
+
+  fn downsample_texture(
+    &mut self, dist_tex: &mut Self::Texture, from_tex: &Self::Texture,
+    rects: &[(DeviceRect, DeviceRect)],
+  ) {
+    self.unbind_msaa(dist_tex);
+    self.draw_texture_rects_to_texture(dist_tex, from_tex, rects);
   }
 
   fn draw_color_triangles(
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
+    let use_msaa = self.prepare_msaa(texture, clear);
+    let msaa = self.msaa_targets.first().filter(|_| use_msaa);
     let encoder = command_encoder!(self);
     color_pass!(self).draw_triangles(
       texture,
+      msaa,
       indices,
       clear,
       &self.device,
@@ -297,9 +379,12 @@ impl GPUBackendImpl for WgpuImpl {
   fn draw_img_triangles(
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
+    let use_msaa = self.prepare_msaa(texture, clear);
+    let msaa = self.msaa_targets.first().filter(|_| use_msaa);
     let encoder = command_encoder!(self);
     img_pass!(self).draw_triangles(
       texture,
+      msaa,
       indices,
       clear,
       &self.device,
@@ -314,6 +399,7 @@ impl GPUBackendImpl for WgpuImpl {
     &mut self, dist_tex: &mut Self::Texture, dist_pos: DevicePoint, from_tex: &Self::Texture,
     from_rect: &DeviceRect,
   ) {
+    self.unbind_msaa(dist_tex);
     if dist_tex.format() == from_tex.format() {
       self.copy_same_format_texture(
         dist_tex.inner_tex.texture(),
@@ -326,7 +412,36 @@ impl GPUBackendImpl for WgpuImpl {
     }
   }
 
+  fn draw_custom(
+    &mut self, texture: &mut Self::Texture, draw: &CustomDraw, rect: &DeviceRect,
+    scissor: &DeviceRect,
+  ) {
+    let Some(WgpuCustomDraw(draw)) = draw.downcast_ref::<WgpuCustomDraw>() else { return };
+    // The custom content is drawn to the texture directly, and the commands
+    // recorded before are submitted first, so the queue operations of the
+    // callback are ordered after them.
+    self.unbind_msaa(texture);
+    self.submit();
+
+    let encoder = command_encoder!(self);
+    let mut ctx = WgpuCustomCtx {
+      device: &self.device,
+      queue: &self.queue,
+      encoder,
+      texture: texture.inner_tex.texture(),
+      view: &texture.view,
+      rect: *rect,
+      scissor: *scissor,
+    };
+    draw(&mut ctx);
+    self.submit()
+  }
+
   fn end_frame(&mut self) {
+    self
+      .msaa_targets
+      .iter_mut()
+      .for_each(|t| t.resolve_to = None);
     self.submit();
     self.device.poll(wgpu::Maintain::Wait);
     #[cfg(debug_assertions)]
@@ -366,6 +481,53 @@ impl<'a> Surface<'a> {
     }
   }
 }
+/// A callback to draw the custom content by the raw wgpu commands, wrap it by
+/// `CustomDraw::new` to draw it by `Painter::draw_custom`.
+pub struct WgpuCustomDraw(Box<dyn Fn(&mut WgpuCustomCtx) + Send + Sync>);
+
+/// The context of the [`WgpuCustomDraw`] callback.
+pub struct WgpuCustomCtx<'a> {
+  pub device: &'a wgpu::Device,
+  pub queue: &'a wgpu::Queue,
+  pub encoder: &'a mut wgpu::CommandEncoder,
+  /// The texture to draw to.
+  pub texture: &'a wgpu::Texture,
+  pub view: &'a wgpu::TextureView,
+  /// The rect to draw in the texture.
+  pub rect: DeviceRect,
+  /// The visible part of the `rect`, the callback should only draw in it.
+  pub scissor: DeviceRect,
+}
+
+impl WgpuCustomDraw {
+  pub fn new(draw: impl Fn(&mut WgpuCustomCtx) + Send + Sync + 'static) -> Self {
+    Self(Box::new(draw))
+  }
+}
+
+impl WgpuCustomCtx<'_> {
+  /// Begin a render pass to the texture that keeps its content and is
+  /// scissored to the `scissor`.
+  pub fn begin_render_pass(&mut self) -> wgpu::RenderPass<'_> {
+    let mut rpass = self
+      .encoder
+      .begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Custom draw pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+          view: self.view,
+          resolve_target: None,
+          ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+      });
+    let s = self.scissor;
+    rpass.set_scissor_rect(s.min_x() as u32, s.min_y() as u32, s.width() as u32, s.height() as u32);
+    rpass
+  }
+}
+
 pub struct WgpuTexture {
   inner_tex: InnerTexture,
   view: wgpu::TextureView,
@@ -388,7 +550,11 @@ impl InnerTexture {
 impl WgpuTexture {
   fn from_tex(tex: wgpu::Texture) -> Self { Self::new(InnerTexture::Texture(tex)) }
 
-  pub(crate) fn color_attachments(&self, clear: Option<Color>) -> wgpu::RenderPassColorAttachment {
+  /// Return the color attachment to draw to the texture, if the `msaa` target
+  /// is given, draw to it and resolve to the texture.
+  pub(crate) fn color_attachments<'a>(
+    &'a self, clear: Option<Color>, msaa: Option<&'a MsaaTarget>,
+  ) -> wgpu::RenderPassColorAttachment<'a> {
     let load = match clear {
       Some(c) => {
         let [r, g, b, a] = c.into_f32_components();
@@ -397,9 +563,13 @@ impl WgpuTexture {
       None => wgpu::LoadOp::Load,
     };
 
-    let view = self.view();
     let ops = wgpu::Operations { load, store: wgpu::StoreOp::Store };
-    wgpu::RenderPassColorAttachment { view, resolve_target: None, ops }
+    match msaa {
+      Some(msaa) => {
+        wgpu::RenderPassColorAttachment { view: &msaa.view, resolve_target: Some(self.view()), ops }
+      }
+      None => wgpu::RenderPassColorAttachment { view: self.view(), resolve_target: None, ops },
+    }
   }
 
   fn new(inner_tex: InnerTexture) -> Self {
@@ -528,6 +698,7 @@ impl Texture for WgpuTexture {
   fn size(&self) -> DeviceSize { self.size() }
 
   fn clear_areas(&mut self, areas: &[DeviceRect], backend: &mut Self::Host) {
+    backend.unbind_msaa(self);
     backend.clear_tex_areas(areas, self);
   }
 }
@@ -617,6 +788,7 @@ impl WgpuImpl {
       max_image_primitives: uniform_bytes / size_of::<ImgPrimitive>(),
       max_radial_gradient_primitives: uniform_bytes / size_of::<RadialGradientPrimitive>(),
       max_linear_gradient_primitives: uniform_bytes / size_of::<LinearGradientPrimitive>(),
+      max_sweep_gradient_primitives: uniform_bytes / size_of::<SweepGradientPrimitive>(),
       max_gradient_stop_primitives: uniform_bytes / size_of::<GradientStopPrimitive>(),
       max_mask_layers: uniform_bytes / size_of::<MaskLayer>(),
     };
@@ -626,6 +798,7 @@ impl WgpuImpl {
     let clear_tex_pass = ClearTexturePass::new(&device);
     let texs_layout = textures_layout(&device);
     let gpu_impl = WgpuImpl {
+      adapter,
       device,
       queue,
       command_encoder: None,
@@ -638,16 +811,19 @@ impl WgpuImpl {
       img_triangles_pass: None,
       radial_gradient_pass: None,
       linear_gradient_pass: None,
+      sweep_gradient_pass: None,
       texs_layout,
       textures_bind: None,
       mask_layers_uniform,
       limits,
+      msaa_samples: 1,
+      msaa_targets: vec![],
     };
 
     let surface = surface.map(|surface| {
       use wgpu::TextureFormat::*;
       let format = surface
-        .get_capabilities(&adapter)
+        .get_capabilities(&gpu_impl.adapter)
         .formats
         .into_iter()
         .find(|&f| f == Rgba8Unorm || f == Bgra8Unorm)
@@ -676,6 +852,108 @@ impl WgpuImpl {
 
   pub fn device(&self) -> &wgpu::Device { &self.device }
 
+  /// Set the sample count of the multi-sampling anti-aliasing for the edges of
+  /// the geometry, `1` means no multi-sampling.
+  ///
+  /// If the adapter does not support the sample count for the format of the
+  /// drawing texture, it falls back to `1`.
+  pub fn set_msaa_samples(&mut self, samples: u32) { self.msaa_samples = samples.max(1); }
+
+  /// The sample count of the multi-sampling anti-aliasing that is requested.
+  pub fn msaa_samples(&self) -> u32 { self.msaa_samples }
+
+  /// The sample count applied to draw to a texture of the `format`.
+  pub fn sample_count(&self, format: wgpu::TextureFormat) -> u32 {
+    use wgpu::TextureFormatFeatureFlags as Flags;
+
+    let flags = self
+      .adapter
+      .get_texture_format_features(format)
+      .flags;
+    if flags.sample_count_supported(self.msaa_samples) && flags.contains(Flags::MULTISAMPLE_RESOLVE)
+    {
+      self.msaa_samples
+    } else {
+      1
+    }
+  }
+
+  /// Prepare the multisampled target to draw to the `texture`, return if the
+  /// drawing should use it, the target is moved to the first.
+  ///
+  /// Only the content drawn to the texture bound to the target is kept in the
+  /// target, so a texture is bound when it's cleared by the drawing, and the
+  /// texture that not bound is drawn without multi-sampling.
+  ///
+  /// The targets are kept for reuse by their size, format and sample count, so
+  /// switching between the textures or the sample counts not recreate them.
+  fn prepare_msaa(&mut self, texture: &WgpuTexture, clear: Option<Color>) -> bool {
+    let format = texture.format();
+    let samples = self.sample_count(format);
+    if samples == 1 {
+      return false;
+    }
+
+    let id = texture.inner_tex.texture().global_id();
+    let size = texture.size();
+    let targets = &mut self.msaa_targets;
+    let bound = targets
+      .iter()
+      .position(|t| t.resolve_to == Some(id) && t.samples == samples);
+    let idx = if clear.is_none() {
+      bound
+    } else {
+      let reusable = |t: &MsaaTarget| {
+        t.size == size && t.format == format && t.samples == samples && t.resolve_to.is_none()
+      };
+      bound.or_else(|| targets.iter().position(reusable))
+    };
+
+    let target = match idx {
+      Some(idx) => targets.remove(idx),
+      None if clear.is_none() => return false,
+      None => {
+        let tex = self
+          .device
+          .create_texture(&wgpu::TextureDescriptor {
+            label: Some("Multisampled texture"),
+            size: wgpu::Extent3d {
+              width: size.width as u32,
+              height: size.height as u32,
+              depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+          });
+        let view = tex.create_view(&<_>::default());
+        MsaaTarget { view, size, format, samples, resolve_to: None }
+      }
+    };
+    // A texture is bound to one target at most.
+    targets
+      .iter_mut()
+      .filter(|t| t.resolve_to == Some(id))
+      .for_each(|t| t.resolve_to = None);
+    targets.insert(0, MsaaTarget { resolve_to: Some(id), ..target });
+    targets.truncate(MAX_MSAA_TARGETS);
+    true
+  }
+
+  /// The `texture` is modified without the multisampled target, so the content
+  /// of the target is outdated.
+  fn unbind_msaa(&mut self, texture: &WgpuTexture) {
+    let id = texture.inner_tex.texture().global_id();
+    self
+      .msaa_targets
+      .iter_mut()
+      .filter(|t| t.resolve_to == Some(id))
+      .for_each(|t| t.resolve_to = None);
+  }
+
   fn submit(&mut self) {
     self.finish_command();
     if !self.command_buffers.is_empty() {
@@ -715,6 +993,23 @@ impl WgpuImpl {
     );
   }
 
+  fn draw_alpha(
+    &mut self, indices: &Range<u32>, texture: &WgpuTexture, scissor: Option<DeviceRect>,
+  ) {
+    // The uniform is written by the queue before the submitted commands run, so
+    // the drawn commands are submitted before it changes.
+    if self
+      .alpha_triangles_pass
+      .is_uniform_changed(texture)
+    {
+      self.submit();
+    }
+    let encoder = command_encoder!(self);
+    self
+      .alpha_triangles_pass
+      .draw_alpha_triangles(indices, texture, scissor, &self.queue, encoder);
+  }
+
   pub(crate) fn finish_command(&mut self) {
     if let Some(encoder) = self.command_encoder.take() {
       self.command_buffers.push(encoder.finish());
@@ -794,3 +1089,73 @@ fn textures_bind(
     label: Some("textures bind group"),
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+  use ribir_geom::{Rect, Transform, rect};
+  use ribir_painter::{Painter, PainterBackend};
+
+  use super::*;
+  use crate::GPUBackend;
+
+  #[test]
+  fn msaa_samples() {
+    let mut wgpu = block_on(WgpuImpl::headless());
+    wgpu.set_msaa_samples(4);
+    let samples = wgpu.sample_count(wgpu::TextureFormat::Rgba8Unorm);
+    // Fall back to no multi-sampling if the adapter does not support it.
+    assert!(samples == 4 || samples == 1);
+
+    let mut backend = GPUBackend::new(wgpu);
+    let draw = |backend: &mut GPUBackend<WgpuImpl>, size: DeviceSize| {
+      let mut texture = backend
+        .get_impl_mut()
+        .new_texture(size, ColorFormat::Rgba8);
+      let mut painter = Painter::new(Rect::from_size(size.to_f32().cast_unit()));
+      painter
+        .set_fill_brush(Color::RED)
+        .rect(&rect(8., 8., 32., 32.))
+        .fill();
+      backend.begin_frame(Color::WHITE);
+      backend.draw_commands(
+        DeviceRect::from_size(size),
+        &painter.finish(),
+        &Transform::identity(),
+        &mut texture,
+      );
+      backend.end_frame();
+    };
+    let (large, small) = (DeviceSize::new(64, 64), DeviceSize::new(48, 48));
+    draw(&mut backend, large);
+
+    let wgpu = backend.get_impl();
+    let color_pass = wgpu.color_triangles_pass.as_ref().unwrap();
+    let key = (wgpu::TextureFormat::Rgba8Unorm, samples);
+    assert!(color_pass.pipelines.contains_key(&key));
+    let target = wgpu.msaa_targets.first();
+    assert_eq!(target.map(|t| t.samples), (samples > 1).then_some(samples));
+
+    // The targets and the pipelines are reused when switching between the sizes
+    // and the sample counts.
+    draw(&mut backend, small);
+    draw(&mut backend, large);
+    backend.get_impl_mut().set_msaa_samples(1);
+    draw(&mut backend, large);
+    backend.get_impl_mut().set_msaa_samples(4);
+    draw(&mut backend, small);
+
+    let wgpu = backend.get_impl();
+    let color_pass = wgpu.color_triangles_pass.as_ref().unwrap();
+    assert_eq!(color_pass.pipelines.len(), if samples > 1 { 2 } else { 1 });
+    let targets_of = |size| {
+      wgpu
+        .msaa_targets
+        .iter()
+        .filter(|t| t.size == size)
+        .count()
+    };
+    let cnt = (samples > 1) as usize;
+    assert_eq!((targets_of(small), targets_of(large)), (cnt, cnt));
+  }
+}
@@ -13,16 +13,19 @@ pub struct DrawAlphaTrianglesPass {
   vertices_buffer: VerticesBuffer<()>,
   pipeline: wgpu::RenderPipeline,
   size_uniform: Uniform<u32>,
+  samples: u32,
+  /// The last value written to the `size_uniform`.
+  uniform: Option<[u32; 4]>,
 }
 
 impl DrawAlphaTrianglesPass {
   pub fn new(device: &wgpu::Device) -> Self {
     let vertices_buffer = VerticesBuffer::new(2048, 4096, device);
     let shader = device.create_shader_module(include_wgsl!("./shaders/alpha_triangles.wgsl"));
-    // Although we only need 2 x u32, we use 4 x f32 to align with the 16-byte
-    // uniform buffer. This is because WebGL requires the buffer to be 16-byte
-    // aligned.
-    let size_uniform = Uniform::new(device, wgpu::ShaderStages::VERTEX, 4);
+    // The size of the view and the sample count, padded to 4 x u32 to align with
+    // the 16-byte uniform buffer. This is because WebGL requires the buffer to be
+    // 16-byte aligned.
+    let size_uniform = Uniform::new(device, wgpu::ShaderStages::VERTEX_FRAGMENT, 4);
     let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
       label: Some("Alpha triangles pipeline layout"),
       bind_group_layouts: &[size_uniform.layout()],
@@ -77,7 +80,24 @@ impl DrawAlphaTrianglesPass {
       multiview: None,
     });
 
-    Self { vertices_buffer, pipeline, size_uniform }
+    Self { vertices_buffer, pipeline, size_uniform, samples: SAMPLE_COUNT, uniform: None }
+  }
+
+  pub fn set_anti_alias(&mut self, anti_alias: bool) {
+    self.samples = if anti_alias { SAMPLE_COUNT } else { 1 };
+  }
+
+  /// Return if drawing to the `texture` needs to write a different uniform
+  /// than the last one.
+  pub fn is_uniform_changed(&self, texture: &WgpuTexture) -> bool {
+    self
+      .uniform
+      .is_some_and(|u| u != self.uniform_of(texture))
+  }
+
+  fn uniform_of(&self, texture: &WgpuTexture) -> [u32; 4] {
+    let size = texture.size().to_u32();
+    [size.width, size.height, self.samples, 0]
   }
 
   pub fn load_alpha_vertices(
@@ -92,10 +112,10 @@ impl DrawAlphaTrianglesPass {
     &mut self, indices: &Range<u32>, texture: &WgpuTexture, scissor: Option<DeviceRect>,
     queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder,
   ) {
-    let color_attachments = texture.color_attachments(None);
-    self
-      .size_uniform
-      .write_buffer(queue, &texture.size().to_u32().to_array());
+    let color_attachments = texture.color_attachments(None, None);
+    let uniform = self.uniform_of(texture);
+    self.size_uniform.write_buffer(queue, &uniform);
+    self.uniform = Some(uniform);
 
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Alpha triangles render pass"),
@@ -117,6 +137,6 @@ impl DrawAlphaTrianglesPass {
       );
     }
     rpass.set_pipeline(&self.pipeline);
-    rpass.draw_indexed(indices.clone(), 0, 0..SAMPLE_COUNT)
+    rpass.draw_indexed(indices.clone(), 0, 0..self.samples)
   }
 }
@@ -2,15 +2,17 @@ use std::{mem::size_of, ops::Range};
 
 use ribir_painter::{Color, Vertex, VertexBuffers};
 
-use super::{shaders::color_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  MsaaTarget, shaders::color_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{ColorAttr, MaskLayer, WgpuTexture};
 
 pub struct DrawColorTrianglesPass {
   vertices_buffer: VerticesBuffer<ColorAttr>,
   layout: wgpu::PipelineLayout,
-  pipeline: Option<wgpu::RenderPipeline>,
+  /// The pipelines for the texture formats and the sample counts.
+  pub(super) pipelines: ahash::HashMap<(wgpu::TextureFormat, u32), wgpu::RenderPipeline>,
   shader: wgpu::ShaderModule,
-  format: Option<wgpu::TextureFormat>,
 }
 
 impl DrawColorTrianglesPass {
@@ -30,7 +32,7 @@ impl DrawColorTrianglesPass {
       push_constant_ranges: &[],
     });
 
-    Self { layout, vertices_buffer, pipeline: None, shader, format: None }
+    Self { layout, vertices_buffer, pipelines: <_>::default(), shader }
   }
 
   pub fn load_triangles_vertices(
@@ -43,14 +45,16 @@ impl DrawColorTrianglesPass {
 
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
-    &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_storage: &Uniform<MaskLayer>,
+    &mut self, texture: &WgpuTexture, msaa: Option<&MsaaTarget>, indices: Range<u32>,
+    clear: Option<Color>, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_storage: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
+    let samples = msaa.map_or(1, |m| m.samples);
+    let format = texture.format();
+    self.update(format, samples, device);
+    let pipeline = &self.pipelines[&(format, samples)];
 
-    let color_attachments = texture.color_attachments(clear);
+    let color_attachments = texture.color_attachments(clear, msaa);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Color triangles pass"),
       color_attachments: &[Some(color_attachments)],
@@ -68,13 +72,8 @@ impl DrawColorTrianglesPass {
     rpass.draw_indexed(indices, 0, 0..1);
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
-    if self.format != Some(format) {
-      self.pipeline.take();
-      self.format = Some(format);
-    }
-
-    if self.pipeline.is_none() {
+  fn update(&mut self, format: wgpu::TextureFormat, samples: u32, device: &wgpu::Device) {
+    if !self.pipelines.contains_key(&(format, samples)) {
       let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: Some("Color triangles pipeline"),
         layout: Some(&self.layout),
@@ -130,13 +129,13 @@ impl DrawColorTrianglesPass {
         },
         depth_stencil: None,
         multisample: wgpu::MultisampleState {
-          count: 1,
+          count: samples,
           mask: !0,
           alpha_to_coverage_enabled: false,
         },
         multiview: None,
       });
-      self.pipeline = Some(pipeline);
+      self.pipelines.insert((format, samples), pipeline);
     }
   }
 }
@@ -2,16 +2,18 @@ use std::{mem::size_of, ops::Range};
 
 use ribir_painter::{Color, Vertex, VertexBuffers};
 
-use super::{shaders::img_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  MsaaTarget, shaders::img_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{DrawPhaseLimits, ImagePrimIndex, ImgPrimitive, MaskLayer, WgpuTexture};
 
 pub struct DrawImgTrianglesPass {
   vertices_buffer: VerticesBuffer<ImagePrimIndex>,
   layout: wgpu::PipelineLayout,
-  pipeline: Option<wgpu::RenderPipeline>,
+  /// The pipelines for the texture formats and the sample counts.
+  pub(super) pipelines: ahash::HashMap<(wgpu::TextureFormat, u32), wgpu::RenderPipeline>,
   shader: wgpu::ShaderModule,
   prims_uniform: Uniform<ImgPrimitive>,
-  format: Option<wgpu::TextureFormat>,
 }
 
 impl DrawImgTrianglesPass {
@@ -36,10 +38,9 @@ impl DrawImgTrianglesPass {
     Self {
       vertices_buffer,
       layout,
-      pipeline: None,
+      pipelines: <_>::default(),
       shader,
       prims_uniform: prims_storage,
-      format: None,
     }
   }
 
@@ -57,13 +58,15 @@ impl DrawImgTrianglesPass {
 
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
-    &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_storage: &Uniform<MaskLayer>,
+    &mut self, texture: &WgpuTexture, msaa: Option<&MsaaTarget>, indices: Range<u32>,
+    clear: Option<Color>, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_storage: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
-    let color_attachments = texture.color_attachments(clear);
+    let samples = msaa.map_or(1, |m| m.samples);
+    let format = texture.format();
+    self.update(format, samples, device);
+    let pipeline = &self.pipelines[&(format, samples)];
+    let color_attachments = texture.color_attachments(clear, msaa);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Image triangles render pass"),
       color_attachments: &[Some(color_attachments)],
@@ -82,13 +85,8 @@ impl DrawImgTrianglesPass {
     rpass.draw_indexed(indices, 0, 0..1);
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
-    if self.format != Some(format) {
-      self.pipeline.take();
-      self.format = Some(format);
-    }
-
-    if self.pipeline.is_none() {
+  fn update(&mut self, format: wgpu::TextureFormat, samples: u32, device: &wgpu::Device) {
+    if !self.pipelines.contains_key(&(format, samples)) {
       let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: Some("Image triangles pipeline"),
         layout: Some(&self.layout),
@@ -138,13 +136,13 @@ impl DrawImgTrianglesPass {
         },
         depth_stencil: None,
         multisample: wgpu::MultisampleState {
-          count: 1,
+          count: samples,
           mask: !0,
           alpha_to_coverage_enabled: false,
         },
         multiview: None,
       });
-      self.pipeline = Some(pipeline);
+      self.pipelines.insert((format, samples), pipeline);
     }
   }
 }
@@ -2,7 +2,9 @@ use std::{mem::size_of, ops::Range};
 
 use ribir_painter::{Color, Vertex, VertexBuffers};
 
-use super::{shaders::linear_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  MsaaTarget, shaders::linear_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{
   DrawPhaseLimits, GradientStopPrimitive, LinearGradientPrimIndex, LinearGradientPrimitive,
   MaskLayer, WgpuTexture,
@@ -10,9 +12,9 @@ use crate::{
 
 pub struct DrawLinearGradientTrianglesPass {
   vertices_buffer: VerticesBuffer<LinearGradientPrimIndex>,
-  pipeline: Option<wgpu::RenderPipeline>,
+  /// The pipelines for the texture formats and the sample counts.
+  pub(super) pipelines: ahash::HashMap<(wgpu::TextureFormat, u32), wgpu::RenderPipeline>,
   shader: wgpu::ShaderModule,
-  format: Option<wgpu::TextureFormat>,
   prims_uniform: Uniform<LinearGradientPrimitive>,
   stops_uniform: Uniform<GradientStopPrimitive>,
   layout: wgpu::PipelineLayout,
@@ -45,9 +47,8 @@ impl DrawLinearGradientTrianglesPass {
     });
     Self {
       vertices_buffer,
-      pipeline: None,
+      pipelines: <_>::default(),
       shader,
-      format: None,
       prims_uniform,
       stops_uniform: stops_unifrom,
       layout,
@@ -75,14 +76,16 @@ impl DrawLinearGradientTrianglesPass {
 
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
-    &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_uniform: &Uniform<MaskLayer>,
+    &mut self, texture: &WgpuTexture, msaa: Option<&MsaaTarget>, indices: Range<u32>,
+    clear: Option<Color>, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_uniform: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
+    let samples = msaa.map_or(1, |m| m.samples);
+    let format = texture.format();
+    self.update(format, samples, device);
+    let pipeline = &self.pipelines[&(format, samples)];
 
-    let color_attachments = texture.color_attachments(clear);
+    let color_attachments = texture.color_attachments(clear, msaa);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Linear triangles render pass"),
       color_attachments: &[Some(color_attachments)],
@@ -102,13 +105,8 @@ impl DrawLinearGradientTrianglesPass {
     rpass.draw_indexed(indices, 0, 0..1);
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
-    if self.format != Some(format) {
-      self.pipeline.take();
-      self.format = Some(format);
-    }
-
-    if self.pipeline.is_none() {
+  fn update(&mut self, format: wgpu::TextureFormat, samples: u32, device: &wgpu::Device) {
+    if !self.pipelines.contains_key(&(format, samples)) {
       let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: Some("Linear triangles pipeline"),
         layout: Some(&self.layout),
@@ -158,13 +156,13 @@ impl DrawLinearGradientTrianglesPass {
         },
         depth_stencil: None,
         multisample: wgpu::MultisampleState {
-          count: 1,
+          count: samples,
           mask: !0,
           alpha_to_coverage_enabled: false,
         },
         multiview: None,
       });
-      self.pipeline = Some(pipeline);
+      self.pipelines.insert((format, samples), pipeline);
     }
   }
 }
@@ -2,7 +2,9 @@ use std::{mem::size_of, ops::Range};
 
 use ribir_painter::{Color, Vertex, VertexBuffers};
 
-use super::{shaders::radial_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  MsaaTarget, shaders::radial_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{
   DrawPhaseLimits, GradientStopPrimitive, MaskLayer, RadialGradientPrimIndex,
   RadialGradientPrimitive, WgpuTexture,
@@ -10,9 +12,9 @@ use crate::{
 
 pub struct DrawRadialGradientTrianglesPass {
   vertices_buffer: VerticesBuffer<RadialGradientPrimIndex>,
-  pipeline: Option<wgpu::RenderPipeline>,
+  /// The pipelines for the texture formats and the sample counts.
+  pub(super) pipelines: ahash::HashMap<(wgpu::TextureFormat, u32), wgpu::RenderPipeline>,
   shader: wgpu::ShaderModule,
-  format: Option<wgpu::TextureFormat>,
   prims_uniform: Uniform<RadialGradientPrimitive>,
   stops_uniform: Uniform<GradientStopPrimitive>,
   layout: wgpu::PipelineLayout,
@@ -45,9 +47,8 @@ impl DrawRadialGradientTrianglesPass {
 
     Self {
       vertices_buffer,
-      pipeline: None,
+      pipelines: <_>::default(),
       shader,
-      format: None,
       prims_uniform: prims_storage,
       stops_uniform: stops_storage,
       layout,
@@ -74,14 +75,16 @@ impl DrawRadialGradientTrianglesPass {
 
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
-    &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_storage: &Uniform<MaskLayer>,
+    &mut self, texture: &WgpuTexture, msaa: Option<&MsaaTarget>, indices: Range<u32>,
+    clear: Option<Color>, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_storage: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
+    let samples = msaa.map_or(1, |m| m.samples);
+    let format = texture.format();
+    self.update(format, samples, device);
+    let pipeline = &self.pipelines[&(format, samples)];
 
-    let color_attachments = texture.color_attachments(clear);
+    let color_attachments = texture.color_attachments(clear, msaa);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Radial triangles render pass"),
       color_attachments: &[Some(color_attachments)],
@@ -101,13 +104,8 @@ impl DrawRadialGradientTrianglesPass {
     rpass.draw_indexed(indices, 0, 0..1);
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
-    if self.format != Some(format) {
-      self.pipeline.take();
-      self.format = Some(format);
-    }
-
-    if self.pipeline.is_none() {
+  fn update(&mut self, format: wgpu::TextureFormat, samples: u32, device: &wgpu::Device) {
+    if !self.pipelines.contains_key(&(format, samples)) {
       let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: Some("Radial triangles pipeline"),
         layout: Some(&self.layout),
@@ -157,13 +155,13 @@ impl DrawRadialGradientTrianglesPass {
         },
         depth_stencil: None,
         multisample: wgpu::MultisampleState {
-          count: 1,
+          count: samples,
           mask: !0,
           alpha_to_coverage_enabled: false,
         },
         multiview: None,
       });
-      self.pipeline = Some(pipeline);
+      self.pipelines.insert((format, samples), pipeline);
     }
   }
 }
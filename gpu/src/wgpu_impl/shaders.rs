@@ -325,6 +325,124 @@ fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
 "#
 }
 
+pub fn sweep_gradient_shader(limits: &DrawPhaseLimits) -> String {
+  basic_template(limits.max_mask_layers)
+    + &format!(
+      r#"
+@group(2) @binding(0)
+var<uniform> prims: array<Primitive, {}>;
+
+@group(3) @binding(0)
+var<uniform> stops: array<StopPair, {}>;"#,
+      limits.max_sweep_gradient_primitives,
+      limits.max_gradient_stop_primitives / 2,
+    )
+    + r#"
+struct Vertex {
+  @location(0) pos: vec2<f32>,
+  @location(1) @interpolate(flat) prim_idx: u32,
+};
+
+struct FragInput {
+  @builtin(position) pos: vec4<f32>,
+  @location(0) @interpolate(flat) prim_idx: u32,
+}
+
+@vertex
+fn vs_main(v: Vertex) -> FragInput {
+    var input: FragInput;
+    // convert from gpu-backend coords(0..1) to wgpu corrds(-1..1)
+    let pos = v.pos * vec2(2., -2.) + vec2(-1., 1.);
+    input.pos = vec4<f32>(pos, 0.0, 1.0);
+    input.prim_idx = v.prim_idx;
+    return input;
+}
+
+// A pair of stops. This arrangement aligns the stops with 16 bytes, minimizing excessive padding.
+struct StopPair {
+    color1: u32,
+    offset1: f32,
+    color2: u32,
+    offset2: f32,
+}
+
+struct Stop {
+    color: vec4<f32>,
+    offset: f32,
+}
+
+// Since a the different alignment between WebGPU and WebGL, we not use 
+// mat3x2<f32> in the struct, but use vec2<f32> instead. Then, we compose it.
+struct Primitive {
+  t0: vec2<f32>,
+  t1: vec2<f32>,
+  t2: vec2<f32>,
+  center: vec2<f32>,
+  start_angle: f32,
+  stop_start: u32,
+  stop_cnt: u32,
+  mask_head: i32,
+}
+
+const TAU: f32 = 6.283185307179586;
+
+fn unpackUnorm4x8(packed: u32) -> vec4<f32> {
+    return vec4<f32>(
+        f32((packed & 0xff000000) >> 24) / 255.0,
+        f32((packed & 0x00ff0000) >> 16) / 255.0,
+        f32((packed & 0x0000ff00) >> 8) / 255.0,
+        f32((packed & 0x000000ff) >> 0) / 255.0
+    );
+}
+
+fn get_stop(idx: u32) -> Stop {
+    let pair = stops[idx / 2];
+    if idx % 2 == 0 {
+        return Stop(unpackUnorm4x8(pair.color1), pair.offset1);
+    } else {
+        return Stop(unpackUnorm4x8(pair.color2), pair.offset2);
+    }
+}
+
+@fragment
+fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
+    let prim = prims[input.prim_idx];
+    let pos = mat3x2(prim.t0, prim.t1, prim.t2) * vec3(input.pos.xy, 1.);
+
+    var alpha = 1.;
+    var mask_idx = prim.mask_head;
+    loop {
+        if mask_idx < 0 { break; }
+
+        let mask = mask_layers[u32(mask_idx)];
+        alpha *= mask_sample(mask, input.pos.xy);
+        mask_idx = mask.prev_mask_idx;
+    }
+
+    // The y axis points down, so the angle increases clockwise. The angle is
+    // wrapped to a full turn from the start angle.
+    let dir = pos - prim.center;
+    var offset = fract((atan2(dir.y, dir.x) - prim.start_angle) / TAU);
+
+    var prev = get_stop(prim.stop_start);
+    // A single stop paints its color, there is no next stop to mix with.
+    if prim.stop_cnt < 2u {
+        return prev.color * vec4<f32>(1., 1., 1., alpha);
+    }
+    var next = get_stop(prim.stop_start + 1);
+    for (var i = 2u; i < prim.stop_cnt && next.offset < offset; i++) {
+        prev = next;
+        next = get_stop(prim.stop_start + i);
+    }
+
+    offset = max(prev.offset, min(next.offset, offset));
+    let weight1 = (next.offset - offset) / (next.offset - prev.offset);
+    let weight2 = 1. - weight1;
+    return (prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha);
+}
+"#
+}
+
 pub fn color_triangles_shader(max_mask_layers: usize) -> String {
   basic_template(max_mask_layers)
     + r#"
@@ -402,6 +520,8 @@ pub fn img_triangles_shader(limits: &DrawPhaseLimits) -> String {
     mask_head_and_tex_idx: i32,
     /// extra alpha apply to current vertex
     opacity: f32,
+    /// The color to tint an alpha image, not tint if its alpha is negative.
+    tint: vec4<f32>,
   }
   
   struct VertexOutput {
@@ -427,6 +547,10 @@ pub fn img_triangles_shader(limits: &DrawPhaseLimits) -> String {
       let pos = mat3x2(prim.t0, prim.t1, prim.t2) * f.pos.xyz;
       var img_pos = pos.xy % prim.img_size + prim.img_start;
       var color = img_sample(prim, img_pos);
+      if prim.tint.a >= 0. {
+        // The alpha image is stored in the red channel.
+        color = vec4<f32>(prim.tint.rgb, color.r * prim.tint.a);
+      }
   
       var mask_idx = prim.mask_head_and_tex_idx >> 16 ;
       var alpha = 1.0;
@@ -49,13 +49,17 @@ fn vs_main(@location(0) pos: vec2<f32>, @builtin(instance_index) instance: u32)
   );
 
     let size = vec2(f32(view_size.x), f32(view_size.y));
-    var sample_pos = pos + sample_pattern[instance % 6];
+    var sample_pos = pos;
+    // Without anti-aliasing, only sample the center of the pixel.
+    if view_size.z > 1u {
+      sample_pos += sample_pattern[instance % 6];
+    }
     sample_pos = sample_pos * vec2(2., -2.)  / size + vec2(-1., 1.);
     return vec4<f32>(sample_pos, 0.0, 1.0);
 }
 
 @fragment
 fn fs_main() -> @location(0) vec4<f32> {
-  let value: f32 = 1.0 / 6.0;
+  let value: f32 = 1.0 / f32(view_size.z);
   return vec4(value, value, value, value);
 }
@@ -2,7 +2,7 @@ use std::mem::size_of;
 
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize, rect_corners};
 use ribir_painter::Vertex;
-use wgpu::{StoreOp, include_wgsl};
+use wgpu::{StoreOp, include_wgsl, util::DeviceExt};
 use zerocopy::AsBytes;
 
 use super::vertex_buffer::new_vertices;
@@ -206,6 +206,80 @@ impl WgpuImpl {
     rpass.draw(0..4, 0..1)
   }
 
+  /// Draw every `from` rect of the `from_tex` to its paired `to` rect of the
+  /// `dist_tex` in one render pass.
+  pub(crate) fn draw_texture_rects_to_texture(
+    &mut self, dist_tex: &WgpuTexture, from_tex: &WgpuTexture, rects: &[(DeviceRect, DeviceRect)],
+  ) {
+    if rects.is_empty() {
+      return;
+    }
+    let pass = self
+      .copy_tex_pass
+      .get_or_insert_with(|| CopyTexturePass::new(&self.device));
+    pass.update(dist_tex.format(), &self.device);
+
+    let mut vertices = Vec::with_capacity(rects.len() * 4);
+    for (from, to) in rects {
+      let [d_lt, d_rt, d_rb, d_lb] = vertices_corners(to, Texture::size(dist_tex));
+      let [s_lt, s_rt, s_rb, s_lb] = vertices_corners(from, Texture::size(from_tex));
+      vertices.extend([
+        Vertex::new(d_lt, s_lt),
+        Vertex::new(d_lb, s_lb),
+        Vertex::new(d_rt, s_rt),
+        Vertex::new(d_rb, s_rb),
+      ]);
+    }
+    // A new buffer for every call, so the vertices of the calls before the
+    // submission are not overwritten.
+    let vertices_buffer = self
+      .device
+      .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Copy texture rects vertices"),
+        contents: vertices.as_bytes(),
+        usage: wgpu::BufferUsages::VERTEX,
+      });
+
+    let bind_group = self
+      .device
+      .create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &pass.bind_layout,
+        entries: &[
+          wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(from_tex.view()),
+          },
+          wgpu::BindGroupEntry {
+            binding: 1,
+            resource: wgpu::BindingResource::Sampler(&self.sampler),
+          },
+        ],
+        label: Some("Copy texture rects bind group"),
+      });
+
+    let color_attachments = wgpu::RenderPassColorAttachment {
+      view: dist_tex.view(),
+      resolve_target: None,
+      ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: StoreOp::Store },
+    };
+
+    let encoder = command_encoder!(self);
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("Copy texture rects"),
+      color_attachments: &[Some(color_attachments)],
+      depth_stencil_attachment: None,
+      timestamp_writes: None,
+      occlusion_query_set: None,
+    });
+
+    rpass.set_vertex_buffer(0, vertices_buffer.slice(..));
+    rpass.set_bind_group(0, &bind_group, &[]);
+    rpass.set_pipeline(pass.pipeline.as_ref().unwrap());
+    for i in 0..rects.len() as u32 {
+      rpass.draw(i * 4..i * 4 + 4, 0..1);
+    }
+  }
+
   pub(crate) fn clear_tex_areas(&mut self, clear_areas: &[DeviceRect], tex: &WgpuTexture) {
     self.finish_command();
 
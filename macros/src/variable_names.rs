@@ -89,6 +89,12 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "on_triple_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_x_times_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_x_times_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_start" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_start_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_update" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_update_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_end" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_end_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_ime_pre_edit" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_ime_pre_edit_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_wheel" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
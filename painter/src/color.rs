@@ -41,6 +41,16 @@ pub struct LinearGradient {
   pub spread_method: SpreadMethod,
 }
 
+/// A gradient sweeps clockwise around the `center`, the offset of a point is
+/// the angle from the `start_angle` to it divided by a full turn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SweepGradient {
+  pub center: Point,
+  /// The angle in radians where the offset starts, `0.` points to the right.
+  pub start_angle: f32,
+  pub stops: Vec<GradientStop>,
+}
+
 /// Describe the light tone of a color, should between [0, 1.0], 0.0 gives
 /// absolute black and 1.0 give the brightest white.
 #[derive(Clone, Debug, Copy)]
@@ -20,3 +20,9 @@ pub use style::*;
 pub use crate::image::PixelImage;
 mod svg;
 pub use svg::Svg;
+mod path_boolean;
+mod rasterizer;
+mod svg_path;
+pub use svg_path::SvgPathError;
+mod shadow;
+pub use shadow::{box_shadow_margin, box_shadow_mask};
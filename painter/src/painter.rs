@@ -1,4 +1,9 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+  any::Any,
+  fmt::Debug,
+  ops::{Deref, DerefMut},
+  sync::Arc,
+};
 
 use ribir_algo::Resource;
 use ribir_geom::{Angle, DeviceRect, Point, Rect, Size, Transform, Vector};
@@ -6,8 +11,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
   Brush, Color, Glyph, PixelImage, Svg, VisualGlyphs,
-  color::{LinearGradient, RadialGradient},
+  color::{LinearGradient, RadialGradient, SweepGradient},
   font_db::FontDB,
+  image::ColorFormat,
   path::*,
   path_builder::PathBuilder,
 };
@@ -22,6 +28,18 @@ pub struct Painter {
   state_stack: Vec<PainterState>,
   commands: Vec<PaintCommand>,
   path_builder: PathBuilder,
+  layers: Vec<Layer>,
+}
+
+/// A group of commands that will be composited as a whole, see
+/// [`Painter::begin_layer`].
+struct Layer {
+  /// The depth of the state stack when the layer begins, the layer ends when
+  /// this state is restored.
+  depth: usize,
+  /// The index of the first command of the layer.
+  cmd_start: usize,
+  opacity: f32,
 }
 
 pub struct PainterResult<'a>(&'a mut Vec<PaintCommand>);
@@ -84,9 +102,15 @@ pub enum PaintPathAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CommandBrush {
   Color(Color),
-  Image { img: Resource<PixelImage>, opacity: f32 },
+  Image {
+    img: Resource<PixelImage>,
+    opacity: f32,
+    /// The color to tint the alpha image, `None` to draw the image as it is.
+    tint: Option<Color>,
+  },
   Radial(RadialGradient),
   Linear(LinearGradient),
+  Sweep(SweepGradient),
 }
 
 #[repr(u32)]
@@ -129,6 +153,9 @@ pub enum PathStyle {
   #[default]
   Fill,
   Stroke,
+  /// Fill the path and then stroke it, so the stroke is painted over the fill
+  /// as the default paint order of SVG.
+  FillAndStroke,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,6 +172,34 @@ pub enum PaintCommand {
     bounds: Rect,
     cmds: Resource<Box<[PaintCommand]>>,
   },
+  /// A rect drawn by a callback of the backend, see [`Painter::draw_custom`].
+  #[serde(skip)]
+  Custom(CustomCommand),
+}
+
+/// A command to draw a rect by a callback of the backend.
+#[derive(Debug, Clone)]
+pub struct CustomCommand {
+  /// The rect to draw, after the transform applied.
+  pub rect: Rect,
+  /// The visible part of the `rect`, the callback should only draw in it.
+  pub paint_bounds: Rect,
+  pub draw: CustomDraw,
+}
+
+/// A backend specific callback to draw the custom content. The backend
+/// downcasts it to the callback type it knows, and ignores the others.
+#[derive(Clone)]
+pub struct CustomDraw(Arc<dyn Any + Send + Sync>);
+
+impl CustomDraw {
+  pub fn new(draw: impl Any + Send + Sync) -> Self { Self(Arc::new(draw)) }
+
+  pub fn downcast_ref<T: Any>(&self) -> Option<&T> { self.0.downcast_ref() }
+}
+
+impl Debug for CustomDraw {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("CustomDraw") }
 }
 
 #[derive(Clone)]
@@ -186,6 +241,7 @@ impl Painter {
       init_state,
       commands: vec![],
       path_builder: Path::builder(),
+      layers: vec![],
     }
   }
 
@@ -220,6 +276,9 @@ impl Painter {
   #[inline]
   pub fn finish(&mut self) -> PainterResult {
     self.fill_all_pop_clips();
+    while !self.layers.is_empty() {
+      self.end_layer();
+    }
     PainterResult(&mut self.commands)
   }
 
@@ -247,10 +306,18 @@ impl Painter {
     let clip_cnt = self.current_state().clip_cnt;
     self.state_stack.pop();
     self.push_n_pop_cmd(clip_cnt - self.current_state().clip_cnt);
+    while self
+      .layers
+      .last()
+      .is_some_and(|l| l.depth > self.state_stack.len())
+    {
+      self.end_layer();
+    }
   }
 
   pub fn reset(&mut self) {
     self.fill_all_pop_clips();
+    self.layers.clear();
     self.commands.clear();
     self.state_stack.clear();
     self.state_stack.push(self.init_state.clone());
@@ -299,6 +366,40 @@ impl Painter {
     self
   }
 
+  /// Begin a layer in the current state, the commands drawn until the current
+  /// state is restored are composited as a whole with the `opacity`, so the
+  /// overlapping parts of them will not blend with each other.
+  ///
+  /// The commands in the layer are drawn with full alpha, and the layer is
+  /// drawn as a bundle command. If the layer is fully opaque or invisible, no
+  /// layer is created and the `opacity` is applied to the alpha directly.
+  pub fn begin_layer(&mut self, opacity: f32) -> &mut Self {
+    let alpha = self.alpha() * opacity;
+    if opacity < 1. && alpha > 0. { self.push_layer(alpha) } else { self.set_alpha(alpha) }
+  }
+
+  /// Begin a layer in the current state like [`Painter::begin_layer`], but the
+  /// layer is drawn as a bundle command even if it's fully opaque, so the
+  /// backend composites the commands in it as a whole.
+  pub fn begin_composite_layer(&mut self) -> &mut Self {
+    let alpha = self.alpha();
+    if alpha > 0. { self.push_layer(alpha) } else { self }
+  }
+
+  fn push_layer(&mut self, opacity: f32) -> &mut Self {
+    let depth = self.state_stack.len();
+    let cmd_start = self.commands.len();
+    match self.layers.last_mut() {
+      // The layer begins in the same state without any command is merged.
+      Some(l) if l.depth == depth && l.cmd_start == cmd_start => l.opacity *= opacity,
+      _ => {
+        let layer = Layer { depth, cmd_start, opacity };
+        self.layers.push(layer);
+      }
+    }
+    self.set_alpha(1.)
+  }
+
   #[inline]
   pub fn set_strokes(&mut self, strokes: StrokeOptions) -> &mut Self {
     self.current_state_mut().stroke_options = strokes;
@@ -397,6 +498,12 @@ impl Painter {
     self.inner_draw_path(path, PathStyle::Stroke)
   }
 
+  /// Fill a path with the fill brush, and then outline it with the stroke brush
+  /// and `StrokeOptions`.
+  pub fn fill_and_stroke_path(&mut self, path: PaintPath) -> &mut Self {
+    self.inner_draw_path(path, PathStyle::FillAndStroke)
+  }
+
   /// Strokes (outlines) the current path with the current brush and line width.
   pub fn stroke(&mut self) -> &mut Self {
     let builder = std::mem::take(&mut self.path_builder);
@@ -409,6 +516,13 @@ impl Painter {
     self.fill_path(builder.build().into())
   }
 
+  /// Fill the current path with the fill brush, and then stroke it with the
+  /// stroke brush.
+  pub fn fill_and_stroke(&mut self) -> &mut Self {
+    let builder = std::mem::take(&mut self.path_builder);
+    self.fill_and_stroke_path(builder.build().into())
+  }
+
   /// Draw the current path with the current paint style and brush.
   pub fn draw(&mut self) -> &mut Self {
     let builder = std::mem::take(&mut self.path_builder);
@@ -548,6 +662,24 @@ impl Painter {
     self
   }
 
+  /// Draw the `rect` by a callback of the backend, such as a 3D scene drawn by
+  /// the raw GPU commands. The content is composited in the paint order, and
+  /// scissored to the visible part of the `rect`.
+  ///
+  /// The callback draws to the device directly, so the opacity is not applied,
+  /// and the clip paths only limit it by their bounds.
+  pub fn draw_custom(&mut self, rect: &Rect, draw: CustomDraw) -> &mut Self {
+    invisible_return!(self);
+    if let Some(visible) = self.intersection_paint_bounds(rect) {
+      let transform = self.transform();
+      let rect = transform.outer_transformed_rect(rect);
+      let paint_bounds = transform.outer_transformed_rect(&visible);
+      let cmd = CustomCommand { rect, paint_bounds, draw };
+      self.commands.push(PaintCommand::Custom(cmd));
+    }
+    self
+  }
+
   pub fn draw_svg(&mut self, svg: &Svg) -> &mut Self {
     invisible_return!(self);
     let commands = svg.commands(self.fill_brush(), self.stroke_brush());
@@ -578,6 +710,11 @@ impl Painter {
             bounds,
             cmds,
           },
+          PaintCommand::Custom(mut custom) => {
+            custom.rect = transform.outer_transformed_rect(&custom.rect);
+            custom.paint_bounds = transform.outer_transformed_rect(&custom.paint_bounds);
+            PaintCommand::Custom(custom)
+          }
         };
         self.commands.push(cmd);
       }
@@ -596,6 +733,27 @@ impl Painter {
   /// dst_rect.
   pub fn draw_img(
     &mut self, img: Resource<PixelImage>, dst_rect: &Rect, src_rect: &Option<Rect>,
+  ) -> &mut Self {
+    self.inner_draw_img(img, dst_rect, src_rect, None)
+  }
+
+  /// Draw the alpha image colored by the `tint`, the image is the same as
+  /// [`Painter::draw_img`] but only its alpha channel is used, so an icon mask
+  /// can be drawn in any color without a copy of the image for every color.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the image is not an [`ColorFormat::Alpha8`] image.
+  pub fn draw_tinted_img(
+    &mut self, img: Resource<PixelImage>, dst_rect: &Rect, src_rect: &Option<Rect>, tint: Color,
+  ) -> &mut Self {
+    assert_eq!(img.color_format(), ColorFormat::Alpha8, "Only an alpha image can be tinted.");
+    self.inner_draw_img(img, dst_rect, src_rect, Some(tint))
+  }
+
+  fn inner_draw_img(
+    &mut self, img: Resource<PixelImage>, dst_rect: &Rect, src_rect: &Option<Rect>,
+    tint: Option<Color>,
   ) -> &mut Self {
     {
       let mut painter = self.save_guard();
@@ -615,22 +773,53 @@ impl Painter {
       painter
         .scale(dst_rect.width() / paint_rect.width(), dst_rect.height() / paint_rect.height())
         .translate(-paint_rect.min_x(), -paint_rect.min_y())
-        .rect(&Rect::from_size(Size::new(m_width, m_height)))
-        .set_fill_brush(img)
-        .fill();
+        .rect(&Rect::from_size(Size::new(m_width, m_height)));
+      let brush = match tint {
+        Some(tint) => Brush::TintedImage { img, tint },
+        None => Brush::Image(img),
+      };
+      painter.set_fill_brush(brush).fill();
     }
 
     self
   }
 
-  pub fn draw_glyph(&mut self, g: &Glyph, font_size: f32, font_db: &FontDB) -> &mut Self {
+  /// Draw a glyph, the COLR glyph is colored by the CPAL palette of
+  /// `palette_index`, and other glyphs are drawn by the current brush.
+  pub fn draw_glyph(
+    &mut self, g: &Glyph, font_size: f32, palette_index: u16, font_db: &FontDB,
+  ) -> &mut Self {
     let Some(face) = font_db.try_get_face_data(g.face_id) else { return self };
 
     let unit = face.units_per_em() as f32;
     let matrix = *self.transform();
 
     let bounds = g.bounds();
-    if let Some(path) = face.outline_glyph(g.glyph_id) {
+    let brush = match self.style() {
+      PathStyle::Stroke => self.stroke_brush(),
+      PathStyle::Fill | PathStyle::FillAndStroke => self.fill_brush(),
+    };
+    let foreground = match brush {
+      Brush::Color(c) => *c,
+      _ => Color::BLACK,
+    };
+    if let Some(layers) = face.color_glyph_layers(g.glyph_id, palette_index, foreground) {
+      let scale = font_size / unit;
+      let fill = self.fill_brush().clone();
+      let stroke = self.stroke_brush().clone();
+      self
+        .translate(bounds.min_x(), bounds.min_y())
+        .scale(scale, -scale)
+        .translate(0., -unit);
+      // Every layer is drawn in the current style with its palette color.
+      for (path, color) in layers {
+        self
+          .set_fill_brush(color)
+          .set_stroke_brush(color)
+          .draw_path(path.into());
+      }
+      self.set_fill_brush(fill).set_stroke_brush(stroke);
+    } else if let Some(path) = face.outline_glyph(g.glyph_id) {
       let scale = font_size / unit;
       self
         .translate(bounds.min_x(), bounds.min_y())
@@ -684,7 +873,7 @@ impl Painter {
     self.translate(visual_rect.origin.x, visual_rect.origin.y);
 
     for g in glyphs {
-      self.draw_glyph(&g, visual_glyphs.font_size(), font_db);
+      self.draw_glyph(&g, visual_glyphs.font_size(), visual_glyphs.palette_index(), font_db);
     }
 
     self
@@ -692,7 +881,27 @@ impl Painter {
 
   fn inner_draw_path(&mut self, path: PaintPath, path_style: PathStyle) -> &mut Self {
     invisible_return!(self);
-    let line_width = matches!(path_style, PathStyle::Stroke).then(|| self.line_width());
+    match path_style {
+      PathStyle::Fill => self.paint_path(path, false),
+      PathStyle::Stroke => self.paint_path(path, true),
+      PathStyle::FillAndStroke => {
+        // Share the path between the two commands instead of cloning it. The
+        // backend still caches the fill and the stroke by two keys, as they are
+        // rasterized differently, but the keys share the same path.
+        let path = match path {
+          PaintPath::Own(path) => PaintPath::Share(Resource::new(path)),
+          share => share,
+        };
+        self.paint_path(path.clone(), false);
+        self.paint_path(path, true)
+      }
+    }
+  }
+
+  /// Paint the path by the fill brush, or stroke it by the stroke brush if
+  /// `stroke` is true.
+  fn paint_path(&mut self, path: PaintPath, stroke: bool) -> &mut Self {
+    let line_width = stroke.then(|| self.line_width());
     let p_bounds = path.bounds(line_width);
     if p_bounds.is_empty()
       || !locatable_bounds(&p_bounds)
@@ -701,16 +910,14 @@ impl Painter {
       return self;
     }
 
-    let brush = match path_style {
-      PathStyle::Fill => self.fill_brush().clone(),
-      PathStyle::Stroke => self.stroke_brush().clone(),
-    };
+    let brush = if stroke { self.stroke_brush().clone() } else { self.fill_brush().clone() };
 
     if brush.is_visible() {
       let mut brush = CommandBrush::from(brush);
-      let painting_style = match path_style {
-        PathStyle::Fill => PaintingStyle::Fill,
-        PathStyle::Stroke => PaintingStyle::Stroke(self.stroke_options().clone()),
+      let painting_style = if stroke {
+        PaintingStyle::Stroke(self.stroke_options().clone())
+      } else {
+        PaintingStyle::Fill
       };
       brush.apply_alpha(self.alpha());
       let ts = *self.transform();
@@ -762,6 +969,51 @@ impl Painter {
     }
   }
 
+  fn end_layer(&mut self) {
+    let Some(Layer { cmd_start, opacity, .. }) = self.layers.pop() else { return };
+    let mut cmds: Vec<_> = self.commands.drain(cmd_start..).collect();
+    let bounds = cmds
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          paint_bounds,
+          action: PaintPathAction::Paint { .. },
+          ..
+        }) => Some(*paint_bounds),
+        PaintCommand::Bundle { transform, bounds, .. } => {
+          Some(transform.outer_transformed_rect(bounds))
+        }
+        PaintCommand::Custom(CustomCommand { paint_bounds, .. }) => Some(*paint_bounds),
+        _ => None,
+      })
+      .reduce(|a, b| a.union(&b))
+      .and_then(|bounds| bounds.intersection(self.viewport()));
+    let Some(bounds) = bounds else { return };
+
+    // Move the commands to start from the zero point of the bundle.
+    let offset = -bounds.origin.to_vector();
+    for cmd in cmds.iter_mut() {
+      match cmd {
+        PaintCommand::Path(path) => {
+          path.transform = path.transform.then_translate(offset);
+          path.paint_bounds = path.paint_bounds.translate(offset);
+        }
+        PaintCommand::Bundle { transform, .. } => *transform = transform.then_translate(offset),
+        PaintCommand::Custom(custom) => {
+          custom.rect = custom.rect.translate(offset);
+          custom.paint_bounds = custom.paint_bounds.translate(offset);
+        }
+        PaintCommand::PopClip => {}
+      }
+    }
+    self.commands.push(PaintCommand::Bundle {
+      transform: Transform::translation(bounds.origin.x, bounds.origin.y),
+      opacity,
+      bounds: Rect::from_size(bounds.size),
+      cmds: Resource::new(cmds.into_boxed_slice()),
+    });
+  }
+
   fn fill_all_pop_clips(&mut self) {
     let clip_cnt = self.current_state().clip_cnt;
     self
@@ -788,9 +1040,13 @@ impl From<Brush> for CommandBrush {
   fn from(brush: Brush) -> Self {
     match brush {
       Brush::Color(color) => CommandBrush::Color(color),
-      Brush::Image(img) => CommandBrush::Image { img, opacity: 1. },
+      Brush::Image(img) => CommandBrush::Image { img, opacity: 1., tint: None },
+      Brush::TintedImage { img, tint } => {
+        CommandBrush::Image { img, opacity: 1., tint: Some(tint) }
+      }
       Brush::RadialGradient(radial_gradient) => CommandBrush::Radial(radial_gradient),
       Brush::LinearGradient(linear_gradient) => CommandBrush::Linear(linear_gradient),
+      Brush::SweepGradient(sweep_gradient) => CommandBrush::Sweep(sweep_gradient),
     }
   }
 }
@@ -882,7 +1138,8 @@ impl CommandBrush {
       CommandBrush::Color(color) => *color = color.apply_alpha(alpha),
       CommandBrush::Image { opacity, .. } => *opacity *= alpha,
       CommandBrush::Radial(RadialGradient { stops, .. })
-      | CommandBrush::Linear(LinearGradient { stops, .. }) => stops
+      | CommandBrush::Linear(LinearGradient { stops, .. })
+      | CommandBrush::Sweep(SweepGradient { stops, .. }) => stops
         .iter_mut()
         .for_each(|s| s.color = s.color.apply_alpha(alpha)),
     }
@@ -919,6 +1176,7 @@ mod test {
   use ribir_geom::rect;
 
   use super::*;
+  use crate::GlyphUnit;
 
   fn painter() -> Painter { Painter::new(Rect::from_size(Size::new(512., 512.))) }
 
@@ -941,6 +1199,36 @@ mod test {
     assert_eq!(&Transform::new(1., 0., 0., 1., 0., 0.), painter.transform());
   }
 
+  #[test]
+  fn save_restore_transform_and_clip() {
+    let mut painter = painter();
+    painter.translate(10., 10.);
+    let base = *painter.transform();
+    let base_bounds = painter.current_state().bounds;
+
+    painter
+      .save()
+      .scale(2., 2.)
+      .clip(Path::rect(&rect(0., 0., 50., 50.)).into())
+      .rect(&rect(0., 0., 10., 10.))
+      .fill();
+    let scaled = *painter.transform();
+    assert_eq!(painter.current_state().clip_cnt, 1);
+
+    painter.save().translate(5., 5.);
+    assert_ne!(painter.transform(), &scaled);
+    painter.restore();
+    assert_eq!(painter.transform(), &scaled);
+
+    painter.restore();
+    assert_eq!(painter.transform(), &base);
+    assert_eq!(painter.current_state().bounds, base_bounds);
+    assert_eq!(painter.current_state().clip_cnt, 0);
+    // The clip is popped after the painting in it.
+    let commands = painter.finish();
+    assert!(matches!(commands.last(), Some(PaintCommand::PopClip)));
+  }
+
   #[test]
   fn fix_clip_pop_without_restore() {
     let mut painter = painter();
@@ -963,6 +1251,103 @@ mod test {
     assert_eq!(painter.current_state().clip_cnt, 0);
   }
 
+  #[test]
+  fn layer_composite_as_group() {
+    let mut painter = painter();
+    painter
+      .save()
+      .begin_layer(0.5)
+      .set_fill_brush(Color::RED)
+      .rect(&rect(10., 10., 100., 100.))
+      .fill()
+      .rect(&rect(60., 10., 100., 100.))
+      .fill()
+      .restore();
+
+    let commands = painter.finish();
+    let [PaintCommand::Bundle { transform, opacity, bounds, cmds }] = &commands[..] else {
+      panic!("The layer should be drawn as a bundle.");
+    };
+    assert_eq!(*opacity, 0.5);
+    assert_eq!(*transform, Transform::translation(10., 10.));
+    assert_eq!(*bounds, rect(0., 0., 150., 100.));
+    assert_eq!(cmds.len(), 2);
+    for cmd in cmds.iter() {
+      let PaintCommand::Path(PathCommand { action: PaintPathAction::Paint { brush, .. }, .. }) =
+        cmd
+      else {
+        panic!("Expect a path command.");
+      };
+      assert!(matches!(brush, CommandBrush::Color(c) if *c == Color::RED));
+    }
+  }
+
+  #[test]
+  fn composite_layer() {
+    let mut painter = painter();
+    painter
+      .save()
+      .begin_composite_layer()
+      .rect(&rect(0., 0., 10., 10.))
+      .fill()
+      .restore();
+    assert!(matches!(
+      &painter.finish()[..],
+      [PaintCommand::Bundle { opacity, .. }] if *opacity == 1.
+    ));
+
+    // The layer begins in the same state is merged into the composite layer.
+    painter
+      .save()
+      .begin_composite_layer()
+      .begin_layer(0.5)
+      .rect(&rect(0., 0., 10., 10.))
+      .fill()
+      .restore();
+    let commands = painter.finish();
+    let [PaintCommand::Bundle { opacity, cmds, .. }] = &commands[..] else {
+      panic!("Expect a single layer.");
+    };
+    assert_eq!(*opacity, 0.5);
+    assert!(matches!(&cmds[..], [PaintCommand::Path(_)]));
+  }
+
+  #[test]
+  fn opaque_layer_draw_directly() {
+    let mut painter = painter();
+    painter
+      .save()
+      .begin_layer(1.)
+      .rect(&rect(0., 0., 10., 10.))
+      .fill()
+      .restore();
+
+    assert!(matches!(&painter.finish()[..], [PaintCommand::Path(_)]));
+  }
+
+  #[test]
+  fn fill_and_stroke_share_path() {
+    let mut painter = painter();
+    painter
+      .rect(&rect(0., 0., 10., 10.))
+      .fill_and_stroke();
+
+    let commands = painter.finish();
+    let [PaintCommand::Path(fill), PaintCommand::Path(stroke)] = &commands[..] else {
+      panic!("Expect a fill and a stroke command.");
+    };
+    let style = |cmd: &PathCommand| match &cmd.action {
+      PaintPathAction::Paint { painting_style, .. } => painting_style.line_width(),
+      _ => unreachable!(),
+    };
+    assert_eq!(style(fill), None);
+    assert!(style(stroke).is_some());
+    let (PaintPath::Share(fill), PaintPath::Share(stroke)) = (&fill.path, &stroke.path) else {
+      panic!("Expect the path shared by the two commands.");
+    };
+    assert_eq!(Resource::as_ptr(fill), Resource::as_ptr(stroke));
+  }
+
   #[test]
   fn filter_invalid_clip() {
     let mut painter = painter();
@@ -1013,6 +1398,47 @@ mod test {
     assert_eq!(painter.commands.len(), 2);
   }
 
+  #[test]
+  fn stroke_color_glyph() {
+    let mut db = FontDB::default();
+    let bytes = include_bytes!("../../fonts/colr-search.ttf");
+    let face_id = db.load_font_data(bytes.to_vec()).unwrap()[0];
+    let face = db.face_data_or_insert(face_id).unwrap();
+    let glyph_id = face.glyph_index('a').unwrap();
+    let size = GlyphUnit::from_pixel(16.);
+    let glyph = Glyph {
+      face_id,
+      x_advance: size,
+      y_advance: size,
+      x_offset: GlyphUnit::ZERO,
+      y_offset: GlyphUnit::ZERO,
+      glyph_id,
+      cluster: 0,
+    };
+
+    let mut painter = painter();
+    painter
+      .set_style(PathStyle::Stroke)
+      .draw_glyph(&glyph, 16., 0, &db);
+
+    let commands = painter.finish();
+    assert_eq!(commands.len(), 2);
+    for (cmd, color) in commands.iter().zip([Color::RED, Color::GREEN]) {
+      let PaintCommand::Path(PathCommand {
+        action: PaintPathAction::Paint { brush, painting_style },
+        ..
+      }) = cmd
+      else {
+        panic!("Expect a path command.");
+      };
+      assert!(matches!(painting_style, PaintingStyle::Stroke(_)));
+      assert!(matches!(brush, CommandBrush::Color(c) if *c == color));
+    }
+    drop(commands);
+    // The brushes of the painter are restored.
+    assert_eq!(painter.stroke_brush(), &Brush::Color(Color::BLACK));
+  }
+
   #[test]
   fn fix_scale_zero_crash() {
     let mut painter = painter();
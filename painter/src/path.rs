@@ -16,6 +16,21 @@ pub struct Path {
   pub(crate) lyon_path: LyonPath,
   // the bounds of the path.
   bounds: Rect,
+  #[serde(default)]
+  fill_rule: FillRule,
+}
+
+/// The rule to determine which parts of the path are inside it when filling,
+/// it matters for self-intersecting paths, such as stars and donuts.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default, Hash)]
+pub enum FillRule {
+  /// A point is inside the path if the sum of the windings of the path around
+  /// it is not zero.
+  #[default]
+  NonZero,
+  /// A point is inside the path if a ray from it crosses the path an odd number
+  /// of times.
+  EvenOdd,
 }
 
 /// Stroke properties.
@@ -108,7 +123,9 @@ pub struct PathSampler {
 }
 
 impl Path {
-  pub(crate) fn new(lyon_path: LyonPath, bounds: Rect) -> Self { Self { lyon_path, bounds } }
+  pub(crate) fn new(lyon_path: LyonPath, bounds: Rect) -> Self {
+    Self { lyon_path, bounds, fill_rule: FillRule::default() }
+  }
 
   #[inline]
   pub fn builder() -> PathBuilder { PathBuilder::default() }
@@ -123,6 +140,17 @@ impl Path {
     }
   }
 
+  /// The fill rule used to fill the path.
+  #[inline]
+  pub fn fill_rule(&self) -> FillRule { self.fill_rule }
+
+  /// Return the path with the fill rule used to fill it.
+  #[inline]
+  pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+    self.fill_rule = fill_rule;
+    self
+  }
+
   /// create a rect path.
   pub fn rect(rect: &Rect) -> Self {
     let mut builder = Path::builder();
@@ -130,8 +158,11 @@ impl Path {
     builder.build()
   }
 
-  /// Creates a path for a rectangle by `rect` with `radius`.
-  /// #[inline]
+  /// Creates a path for a rectangle by `rect` with `radius`, each corner is
+  /// rounded by its own radius.
+  ///
+  /// A radius that exceeds half of the shorter side is clamped to it, so a
+  /// rectangle with large radii becomes a capsule or a circle.
   pub fn rect_round(rect: &Rect, radius: &Radius) -> Self {
     let mut builder = Path::builder();
     builder.rect_round(rect, radius);
@@ -150,7 +181,8 @@ impl Path {
   /// Some points may become NaN/inf therefore this method can fail.
   pub fn transform(self, ts: &Transform) -> Self {
     let ts: &lyon_algorithms::geom::Transform<f32> = unsafe { std::mem::transmute(ts) };
-    self.lyon_path.transformed(ts).into()
+    let path: Path = self.lyon_path.transformed(ts).into();
+    path.with_fill_rule(self.fill_rule)
   }
 
   /// Create an sampler that can queries point at this path or usb-path of this
@@ -183,11 +215,15 @@ impl Path {
   ) {
     use lyon_tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex};
 
+    let options = match self.fill_rule {
+      FillRule::NonZero => FillOptions::non_zero(),
+      FillRule::EvenOdd => FillOptions::even_odd(),
+    };
     let mut fill_tess = FillTessellator::default();
     fill_tess
       .tessellate_path(
         &self.lyon_path,
-        &FillOptions::non_zero().with_tolerance(tolerance),
+        &options.with_tolerance(tolerance),
         &mut BuffersBuilder::new(buffer, move |v: FillVertex| {
           vertex_ctor(v.position().cast_unit())
         }),
@@ -332,7 +368,7 @@ impl From<LyonPath> for Path {
     let bounds = lyon_algorithms::aabb::bounding_box(&lyon_path)
       .to_rect()
       .cast_unit();
-    Path { lyon_path, bounds }
+    Path::new(lyon_path, bounds)
   }
 }
 
@@ -352,3 +388,53 @@ impl<Attr> Vertex<Attr> {
   #[inline]
   pub fn new(pos: [f32; 2], attr: Attr) -> Self { Self { attr, pos } }
 }
+
+#[cfg(test)]
+mod tests {
+  use ribir_geom::rect;
+
+  use super::*;
+
+  fn curves(path: &Path) -> usize {
+    path
+      .segments()
+      .filter(|s| matches!(s, PathSegment::CubicTo { .. } | PathSegment::QuadTo { .. }))
+      .count()
+  }
+
+  #[test]
+  fn rect_round_corners() {
+    let rc = rect(0., 0., 40., 20.);
+    assert_eq!(curves(&Path::rect(&rc)), 0);
+    assert_eq!(curves(&Path::rect_round(&rc, &Radius::top_left(5.))), 1);
+    assert_eq!(curves(&Path::rect_round(&rc, &Radius::all(5.))), 4);
+    assert_eq!(Path::rect_round(&rc, &Radius::all(5.)).bounds(None), rc);
+  }
+
+  #[test]
+  fn clamp_over_large_radius() {
+    let segments = |rc: &Rect, radius: f32| {
+      Path::rect_round(rc, &Radius::all(radius))
+        .segments()
+        .collect::<Vec<_>>()
+    };
+
+    // A capsule.
+    let rc = rect(0., 0., 40., 20.);
+    assert_eq!(segments(&rc, 100.), segments(&rc, 10.));
+
+    // A circle.
+    let rc = rect(0., 0., 20., 20.);
+    assert_eq!(segments(&rc, 100.), segments(&rc, 10.));
+    let center = Point::new(10., 10.);
+    let on_circle = Path::rect_round(&rc, &Radius::all(100.))
+      .segments()
+      .all(|s| match s {
+        PathSegment::CubicTo { to, .. } | PathSegment::QuadTo { to, .. } => {
+          ((to - center).length() - 10.).abs() < 1e-3
+        }
+        _ => true,
+      });
+    assert!(on_circle);
+  }
+}
@@ -0,0 +1,393 @@
+use std::collections::{HashMap, HashSet};
+
+use lyon_algorithms::path::{Event, iterator::PathIterator};
+use ribir_geom::{Point, Rect, Vector};
+
+use crate::{FillRule, Path};
+
+/// The tolerance to flatten the curves before the boolean operations.
+const TOLERANCE: f32 = 0.01;
+/// The distance to probe the both sides of an edge to know if it's a boundary
+/// of the result.
+const PROBE: f32 = 1e-3;
+/// The points closer than it are treated as the same point.
+const SNAP: f32 = 1e-4;
+
+#[derive(Clone, Copy)]
+enum BooleanOp {
+  Union,
+  Intersection,
+  Difference,
+}
+
+type Edge = (Point, Point);
+
+impl Path {
+  /// Return a path that covers the area covered by either `self` or `other`.
+  ///
+  /// The curves are flattened to line segments, and the fill rule of both the
+  /// paths are respected. The result is a new path filled by the non-zero rule.
+  ///
+  /// Only the edges whose bounds overlap are tested for the intersections, and
+  /// the winding of a point only counts the edges in the same horizontal band,
+  /// so the cost grows with the edges and their intersections, instead of the
+  /// square of the edges, unless most of the edges overlap each other.
+  pub fn union(&self, other: &Path) -> Path { boolean(self, other, BooleanOp::Union) }
+
+  /// Return a path that covers the area covered by both `self` and `other`.
+  ///
+  /// See [`Path::union`] for the details of the result.
+  pub fn intersection(&self, other: &Path) -> Path { boolean(self, other, BooleanOp::Intersection) }
+
+  /// Return a path that covers the area covered by `self` but not by `other`.
+  ///
+  /// See [`Path::union`] for the details of the result.
+  pub fn difference(&self, other: &Path) -> Path { boolean(self, other, BooleanOp::Difference) }
+}
+
+impl BooleanOp {
+  fn apply(self, a: bool, b: bool) -> bool {
+    match self {
+      BooleanOp::Union => a || b,
+      BooleanOp::Intersection => a && b,
+      BooleanOp::Difference => a && !b,
+    }
+  }
+}
+
+/// Split all the edges of the two paths at their intersections, then keep the
+/// edges that separate the inside and the outside of the result, and chain them
+/// into contours. The inside of the result is always on the same side of the
+/// kept edges, so the holes wind in the reverse direction.
+fn boolean(a: &Path, b: &Path, op: BooleanOp) -> Path {
+  let a_edges = flatten(a);
+  let b_edges = flatten(b);
+  let a_bands = EdgeBands::new(&a_edges);
+  let b_bands = EdgeBands::new(&b_edges);
+  let inside = |pt: Point| {
+    let in_a = is_inside(a_bands.crossing(pt.y), a.fill_rule(), pt);
+    let in_b = is_inside(b_bands.crossing(pt.y), b.fill_rule(), pt);
+    op.apply(in_a, in_b)
+  };
+
+  let all_edges: Vec<Edge> = a_edges
+    .iter()
+    .chain(b_edges.iter())
+    .copied()
+    .collect();
+  let mut boundaries = vec![];
+  let mut visited = HashSet::new();
+  for (from, to) in split_edges(&all_edges) {
+    let mid = from.lerp(to, 0.5);
+    let dir = (to - from).normalize();
+    let normal = Vector::new(-dir.y, dir.x) * PROBE;
+    let left = inside(mid + normal);
+    if left != inside(mid - normal) {
+      // The coincident edges of the two paths are only kept once.
+      let edge = if left { (from, to) } else { (to, from) };
+      if visited.insert((point_key(edge.0), point_key(edge.1))) {
+        boundaries.push(edge);
+      }
+    }
+  }
+
+  chain_contours(&boundaries)
+}
+
+fn flatten(path: &Path) -> Vec<Edge> {
+  let mut edges = vec![];
+  for e in path.lyon_path.iter().flattened(TOLERANCE) {
+    match e {
+      Event::Line { from, to } => edges.push((from.cast_unit(), to.cast_unit())),
+      // A filled contour is always closed.
+      Event::End { last, first, .. } if last != first => {
+        edges.push((last.cast_unit(), first.cast_unit()))
+      }
+      _ => {}
+    }
+  }
+  edges.retain(|(from, to)| from != to);
+  edges
+}
+
+/// The edges bucketed by the horizontal bands they cross, to find the edges
+/// crossing a horizontal line without going through all the edges.
+struct EdgeBands<'a> {
+  edges: &'a [Edge],
+  min_y: f32,
+  max_y: f32,
+  band_height: f32,
+  bands: Vec<Vec<usize>>,
+}
+
+impl<'a> EdgeBands<'a> {
+  fn new(edges: &'a [Edge]) -> Self {
+    let (min_y, max_y) = edges
+      .iter()
+      .fold((f32::MAX, f32::MIN), |(min, max), (a, b)| {
+        (min.min(a.y).min(b.y), max.max(a.y).max(b.y))
+      });
+    let count = (edges.len() as f32).sqrt().ceil() as usize;
+    let band_height = ((max_y - min_y) / count as f32).max(f32::EPSILON);
+    let mut this = Self { edges, min_y, max_y, band_height, bands: vec![vec![]; count] };
+    for (idx, (a, b)) in edges.iter().enumerate() {
+      let start = this.band_of(a.y.min(b.y));
+      let end = this.band_of(a.y.max(b.y));
+      for band in &mut this.bands[start..=end] {
+        band.push(idx);
+      }
+    }
+    this
+  }
+
+  fn band_of(&self, y: f32) -> usize {
+    let band = ((y - self.min_y) / self.band_height) as usize;
+    band.min(self.bands.len() - 1)
+  }
+
+  /// The edges may cross the horizontal line at `y`.
+  fn crossing(&self, y: f32) -> impl Iterator<Item = &Edge> + '_ {
+    let band = (self.min_y..=self.max_y)
+      .contains(&y)
+      .then(|| &self.bands[self.band_of(y)]);
+    band
+      .into_iter()
+      .flatten()
+      .map(|idx| &self.edges[*idx])
+  }
+}
+
+fn is_inside<'a>(edges: impl Iterator<Item = &'a Edge>, fill_rule: FillRule, pt: Point) -> bool {
+  let winding: i32 = edges
+    .map(|&(from, to)| {
+      // Count the edges crossing the horizontal ray from `pt` to the right.
+      let cross = (to - from).cross(pt - from);
+      if from.y <= pt.y && pt.y < to.y && cross > 0. {
+        1
+      } else if to.y <= pt.y && pt.y < from.y && cross < 0. {
+        -1
+      } else {
+        0
+      }
+    })
+    .sum();
+  match fill_rule {
+    FillRule::NonZero => winding != 0,
+    FillRule::EvenOdd => winding % 2 != 0,
+  }
+}
+
+/// Split the edges at the points where they intersect or touch each other, the
+/// split point is shared by the edges, so they can be chained exactly.
+fn split_edges(edges: &[Edge]) -> Vec<Edge> {
+  let mut splits: Vec<Vec<(f32, Point)>> = vec![vec![]; edges.len()];
+  // Sweep the edges from left to right, only the edges whose bounds overlap
+  // may intersect.
+  let bounds: Vec<Rect> = edges
+    .iter()
+    .map(|(a, b)| Rect::from_points([*a, *b]).inflate(SNAP, SNAP))
+    .collect();
+  let mut order: Vec<usize> = (0..edges.len()).collect();
+  order.sort_by(|a, b| bounds[*a].min_x().total_cmp(&bounds[*b].min_x()));
+  for (k, &i) in order.iter().enumerate() {
+    for &j in &order[k + 1..] {
+      if bounds[j].min_x() > bounds[i].max_x() {
+        break;
+      }
+      if bounds[j].min_y() > bounds[i].max_y() || bounds[j].max_y() < bounds[i].min_y() {
+        continue;
+      }
+      let (a0, a1) = edges[i];
+      let (b0, b1) = edges[j];
+      let r = a1 - a0;
+      let s = b1 - b0;
+      let denom = r.cross(s);
+      let ab = b0 - a0;
+      if denom.abs() > f32::EPSILON * r.length() * s.length() {
+        let t = ab.cross(s) / denom;
+        let u = ab.cross(r) / denom;
+        if (0. ..=1.).contains(&t) && (0. ..=1.).contains(&u) {
+          let p = a0 + r * t;
+          let p = [a0, a1, b0, b1]
+            .into_iter()
+            .find(|end| (p - *end).length() < SNAP)
+            .unwrap_or(p);
+          splits[i].push((t, p));
+          splits[j].push((u, p));
+        }
+      } else if ab.cross(r).abs() < SNAP * r.length() {
+        // The collinear edges are split at the ends of each other.
+        let project =
+          |p: Point, from: Point, dir: Vector| (p - from).dot(dir) / dir.square_length();
+        for p in [b0, b1] {
+          let t = project(p, a0, r);
+          if 0. < t && t < 1. {
+            splits[i].push((t, p));
+          }
+        }
+        for p in [a0, a1] {
+          let u = project(p, b0, s);
+          if 0. < u && u < 1. {
+            splits[j].push((u, p));
+          }
+        }
+      }
+    }
+  }
+
+  let mut pieces = vec![];
+  for (&(from, to), mut points) in edges.iter().zip(splits) {
+    points.push((0., from));
+    points.push((1., to));
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    points.dedup_by(|a, b| a.1 == b.1);
+    pieces.extend(points.windows(2).map(|w| (w[0].1, w[1].1)));
+  }
+  pieces
+}
+
+fn chain_contours(edges: &[Edge]) -> Path {
+  let mut outgoing: HashMap<_, Vec<usize>> = HashMap::new();
+  for (idx, (from, _)) in edges.iter().enumerate() {
+    outgoing
+      .entry(point_key(*from))
+      .or_default()
+      .push(idx);
+  }
+
+  let mut used = vec![false; edges.len()];
+  let mut builder = Path::builder();
+  for start in 0..edges.len() {
+    if used[start] {
+      continue;
+    }
+    used[start] = true;
+    let (first, mut to) = edges[start];
+    builder.begin_path(first);
+    while point_key(to) != point_key(first) {
+      builder.line_to(to);
+      let next = outgoing.get_mut(&point_key(to)).and_then(|out| {
+        let pos = out.iter().position(|e| !used[*e])?;
+        Some(out.swap_remove(pos))
+      });
+      let Some(next) = next else { break };
+      used[next] = true;
+      to = edges[next].1;
+    }
+    builder.end_path(true);
+  }
+  builder.build()
+}
+
+fn point_key(p: Point) -> (u32, u32) { (p.x.to_bits(), p.y.to_bits()) }
+
+#[cfg(test)]
+mod tests {
+  use ribir_geom::{DeviceSize, Transform, rect};
+
+  use super::*;
+  use crate::{PathSegment, rasterizer::AlphaRasterizer};
+
+  /// Return the count of the fully covered pixels of the path.
+  fn covered_pixels(path: &Path, size: DeviceSize) -> usize {
+    let mut raster = AlphaRasterizer::new(size);
+    raster.add_path(path, &Transform::identity());
+    let img = raster.rasterize(path.fill_rule());
+    img
+      .pixel_bytes()
+      .iter()
+      .filter(|a| **a == 255)
+      .count()
+  }
+
+  fn contours(path: &Path) -> usize {
+    path
+      .segments()
+      .filter(|s| matches!(s, PathSegment::MoveTo(_)))
+      .count()
+  }
+
+  #[test]
+  fn union_overlapping_rects() {
+    let a = Path::rect(&rect(0., 0., 100., 100.));
+    let b = Path::rect(&rect(50., 50., 100., 100.));
+    let union = a.union(&b);
+
+    assert_eq!(contours(&union), 1);
+    assert_eq!(union.bounds(None), rect(0., 0., 150., 150.));
+    let size = DeviceSize::new(150, 150);
+    assert_eq!(covered_pixels(&union, size), 100 * 100 * 2 - 50 * 50);
+  }
+
+  #[test]
+  fn intersection_overlapping_rects() {
+    let a = Path::rect(&rect(0., 0., 100., 100.));
+    let b = Path::rect(&rect(50., 50., 100., 100.));
+    let intersection = a.intersection(&b);
+
+    assert_eq!(contours(&intersection), 1);
+    assert_eq!(intersection.bounds(None), rect(50., 50., 50., 50.));
+  }
+
+  #[test]
+  fn difference_with_hole() {
+    let a = Path::rect(&rect(0., 0., 100., 100.));
+    let b = Path::rect(&rect(25., 25., 50., 50.));
+    let diff = a.difference(&b);
+
+    // The outline and the hole.
+    assert_eq!(contours(&diff), 2);
+    assert_eq!(diff.bounds(None), rect(0., 0., 100., 100.));
+    let size = DeviceSize::new(100, 100);
+    assert_eq!(covered_pixels(&diff, size), 100 * 100 - 50 * 50);
+    // Nothing left if the whole path is subtracted.
+    assert_eq!(contours(&b.difference(&a)), 0);
+  }
+
+  #[test]
+  fn union_many_edges() {
+    let mut builder = Path::builder();
+    for i in 0..20 {
+      builder.circle(Point::new(20. + i as f32 * 10., 50.), 15.);
+    }
+    let a = builder.build();
+    let b = Path::rect(&rect(0., 40., 240., 20.));
+    let union = a.union(&b);
+
+    assert_eq!(contours(&union), 1);
+    let size = DeviceSize::new(240, 100);
+    let mut raster = AlphaRasterizer::new(size);
+    raster.add_path(&a, &Transform::identity());
+    raster.add_path(&b, &Transform::identity());
+    let expected = raster
+      .rasterize(FillRule::NonZero)
+      .pixel_bytes()
+      .iter()
+      .filter(|a| **a == 255)
+      .count();
+    let covered = covered_pixels(&union, size);
+    assert!(covered.abs_diff(expected) < 100);
+  }
+
+  #[cfg(feature = "tessellation")]
+  #[test]
+  fn tessellate_difference() {
+    use crate::{Vertex, VertexBuffers};
+
+    let a = Path::rect(&rect(0., 0., 100., 100.));
+    let b = Path::rect(&rect(25., 25., 50., 50.));
+    let mut buffer = VertexBuffers::<()>::new();
+    a.difference(&b)
+      .fill_tessellate(0.01, &mut buffer, |p| Vertex::new([p.x, p.y], ()));
+
+    let area: f32 = buffer
+      .indices
+      .chunks(3)
+      .map(|tri| {
+        let [a, b, c] = [0, 1, 2].map(|i| buffer.vertices[tri[i] as usize].pos);
+        ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.
+      })
+      .sum();
+    assert!((area - (100. * 100. - 50. * 50.)).abs() < 0.1);
+  }
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+use lyon_algorithms::path::{Event, iterator::PathIterator};
+use ribir_geom::{DeviceSize, Point, Transform};
+
+use crate::{FillRule, Path, PixelImage, image::ColorFormat};
+
+/// The number of samples per pixel in each axis.
+const SUB_SAMPLES: usize = 4;
+const TOLERANCE: f32 = 0.1;
+
+/// A simple scanline rasterizer that fills paths into an alpha image on CPU.
+///
+/// It's not designed for performance, but for generating small images without
+/// a GPU, such as icons and thumbnails.
+pub(crate) struct AlphaRasterizer {
+  size: DeviceSize,
+  edges: Vec<(Point, Point)>,
+}
+
+impl AlphaRasterizer {
+  pub(crate) fn new(size: DeviceSize) -> Self { Self { size, edges: vec![] } }
+
+  /// Add the outline of the `path` transformed by `ts` to the rasterizer.
+  pub(crate) fn add_path(&mut self, path: &Path, ts: &Transform) {
+    let Transform { m11, m12, m21, m22, .. } = ts;
+    let scale = (m11.abs() + m12.abs())
+      .max(m21.abs() + m22.abs())
+      .max(f32::EPSILON);
+    let to_device = |p: lyon_algorithms::math::Point| ts.transform_point(p.cast_unit());
+
+    for e in path.lyon_path.iter().flattened(TOLERANCE / scale) {
+      match e {
+        Event::Line { from, to } => self.edges.push((to_device(from), to_device(to))),
+        // A filled contour is always closed.
+        Event::End { last, first, .. } if last != first => self
+          .edges
+          .push((to_device(last), to_device(first))),
+        _ => {}
+      }
+    }
+  }
+
+  pub(crate) fn rasterize(self, fill_rule: FillRule) -> PixelImage {
+    let width = self.size.width.max(0) as usize;
+    let height = self.size.height.max(0) as usize;
+    let mut coverage = vec![0u16; width * height];
+    let mut crossings: Vec<(f32, i32)> = vec![];
+    let sub_width = (width * SUB_SAMPLES) as i64;
+
+    for sub_y in 0..height * SUB_SAMPLES {
+      let y = (sub_y as f32 + 0.5) / SUB_SAMPLES as f32;
+      crossings.clear();
+      for (from, to) in self.edges.iter() {
+        let (top, bottom, dir) = if from.y <= to.y { (from, to, 1) } else { (to, from, -1) };
+        if y < top.y || bottom.y <= y {
+          continue;
+        }
+        let x = top.x + (y - top.y) * (bottom.x - top.x) / (bottom.y - top.y);
+        crossings.push((x, dir));
+      }
+      crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+      let row = &mut coverage[sub_y / SUB_SAMPLES * width..][..width];
+      let mut winding = 0;
+      for (i, (x, dir)) in crossings.iter().enumerate() {
+        winding += dir;
+        let inside = match fill_rule {
+          FillRule::NonZero => winding != 0,
+          FillRule::EvenOdd => winding % 2 != 0,
+        };
+        let Some((next, _)) = crossings.get(i + 1) else { break };
+        if inside {
+          // Fill the sub samples whose center is in the span.
+          let start = ((x * SUB_SAMPLES as f32 - 0.5).ceil() as i64).clamp(0, sub_width);
+          let end = ((next * SUB_SAMPLES as f32 - 0.5).ceil() as i64).clamp(0, sub_width);
+          for sub_x in start..end {
+            row[sub_x as usize / SUB_SAMPLES] += 1;
+          }
+        }
+      }
+    }
+
+    let full = (SUB_SAMPLES * SUB_SAMPLES) as u32;
+    let data = coverage
+      .into_iter()
+      .map(|c| (c as u32 * 255 / full) as u8)
+      .collect::<Vec<_>>();
+    PixelImage::new(Cow::Owned(data), width as u32, height as u32, ColorFormat::Alpha8)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_geom::rect;
+
+  use super::*;
+
+  #[test]
+  fn fill_rect() {
+    let mut raster = AlphaRasterizer::new(DeviceSize::new(4, 4));
+    raster.add_path(&Path::rect(&rect(1., 1., 2., 2.)), &Transform::identity());
+    let img = raster.rasterize(FillRule::NonZero);
+
+    #[rustfmt::skip]
+    assert_eq!(img.pixel_bytes(), &[
+      0, 0, 0, 0,
+      0, 255, 255, 0,
+      0, 255, 255, 0,
+      0, 0, 0, 0,
+    ]);
+  }
+
+  #[test]
+  fn half_covered_pixel() {
+    let mut raster = AlphaRasterizer::new(DeviceSize::new(1, 1));
+    raster.add_path(&Path::rect(&rect(0., 0., 0.5, 1.)), &Transform::identity());
+    let img = raster.rasterize(FillRule::NonZero);
+    assert_eq!(img.pixel_bytes(), &[127]);
+  }
+}
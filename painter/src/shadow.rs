@@ -0,0 +1,174 @@
+use std::{borrow::Cow, cell::RefCell, collections::HashMap};
+
+use ribir_algo::Resource;
+use ribir_geom::{Rect, Size, Transform};
+
+use crate::{FillRule, Path, PixelImage, Radius, image::ColorFormat, rasterizer::AlphaRasterizer};
+
+/// The blank space around the box that a shadow with `spread` and
+/// `blur_radius` may cover.
+pub fn box_shadow_margin(spread: f32, blur_radius: f32) -> f32 {
+  (spread.max(0.) + blur_radius.max(0.)).ceil()
+}
+
+/// The bit patterns of the size, the radius, the spread and the blur radius of
+/// a shadow mask.
+#[derive(PartialEq, Eq, Hash)]
+struct MaskKey([u32; 8]);
+
+thread_local! {
+  static SHADOW_MASKS: RefCell<HashMap<MaskKey, Resource<PixelImage>, ahash::RandomState>> =
+    RefCell::new(<_>::default());
+}
+
+/// Return the `Alpha8` mask of the shadow of a box with `size` and `radius`,
+/// tint it by [`Painter::draw_tinted_img`](crate::Painter::draw_tinted_img) to
+/// paint the shadow in any color.
+///
+/// The box is expanded by `spread` and then blurred by a Gaussian blur with a
+/// standard deviation of half the `blur_radius`, like the `box-shadow` in CSS.
+/// The mask contains a margin of [`box_shadow_margin`] on each side, so the
+/// box is placed at `(margin, margin)` of the mask.
+///
+/// The masks are shared by the same parameters, so the backend uploads a mask
+/// only once for all the shadows of the same shape. A mask is released once
+/// it's held by nobody else, such as the texture cache of the backend.
+pub fn box_shadow_mask(
+  size: Size, radius: &Radius, spread: f32, blur_radius: f32,
+) -> Resource<PixelImage> {
+  let key = MaskKey([
+    size.width.to_bits(),
+    size.height.to_bits(),
+    radius.top_left.to_bits(),
+    radius.top_right.to_bits(),
+    radius.bottom_left.to_bits(),
+    radius.bottom_right.to_bits(),
+    spread.to_bits(),
+    blur_radius.to_bits(),
+  ]);
+  SHADOW_MASKS.with_borrow_mut(|masks| {
+    if let Some(mask) = masks.get(&key) {
+      return mask.clone();
+    }
+    masks.retain(|_, mask| !Resource::is_unique(mask));
+    let mask = Resource::new(rasterize_shadow_mask(size, radius, spread, blur_radius));
+    masks.insert(key, mask.clone());
+    mask
+  })
+}
+
+fn rasterize_shadow_mask(size: Size, radius: &Radius, spread: f32, blur_radius: f32) -> PixelImage {
+  let margin = box_shadow_margin(spread, blur_radius);
+  let img_size = (size + Size::splat(margin * 2.)).ceil();
+  let (width, height) = (img_size.width as usize, img_size.height as usize);
+
+  let rect = Rect::from_size(size).inflate(spread, spread);
+  let grow = |r: f32| if r > 0. { (r + spread).max(0.) } else { 0. };
+  let radius = Radius::new(
+    grow(radius.top_left),
+    grow(radius.top_right),
+    grow(radius.bottom_left),
+    grow(radius.bottom_right),
+  );
+  let mut raster = AlphaRasterizer::new(img_size.to_i32().cast_unit());
+  raster.add_path(&Path::rect_round(&rect, &radius), &Transform::translation(margin, margin));
+  let mask = raster.rasterize(FillRule::NonZero);
+
+  let mut alpha: Vec<f32> = mask
+    .pixel_bytes()
+    .iter()
+    .map(|a| *a as f32 / 255.)
+    .collect();
+  let kernel = gaussian_kernel(blur_radius / 2.);
+  if kernel.len() > 1 {
+    let mut tmp = vec![0.; alpha.len()];
+    blur_pass(&alpha, &mut tmp, &kernel, width, height, 1, width);
+    blur_pass(&tmp, &mut alpha, &kernel, height, width, width, 1);
+  }
+
+  let data = alpha
+    .iter()
+    .map(|m| (m.clamp(0., 1.) * 255.).round() as u8)
+    .collect::<Vec<_>>();
+  PixelImage::new(Cow::Owned(data), width as u32, height as u32, ColorFormat::Alpha8)
+}
+
+/// A normalized Gaussian kernel that covers three standard deviations.
+fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+  if sigma <= 0. {
+    return vec![1.];
+  }
+  let half = (sigma * 3.).ceil() as i32;
+  let kernel: Vec<f32> = (-half..=half)
+    .map(|i| (-((i * i) as f32) / (2. * sigma * sigma)).exp())
+    .collect();
+  let sum: f32 = kernel.iter().sum();
+  kernel.into_iter().map(|k| k / sum).collect()
+}
+
+/// Blur `src` along one axis into `dst`. The `len` samples of a line are
+/// `step` apart, and the `lines` lines are `stride` apart.
+fn blur_pass(
+  src: &[f32], dst: &mut [f32], kernel: &[f32], len: usize, lines: usize, step: usize,
+  stride: usize,
+) {
+  let half = (kernel.len() / 2) as isize;
+  for line in 0..lines {
+    let start = line * stride;
+    for i in 0..len as isize {
+      let value = kernel
+        .iter()
+        .enumerate()
+        .filter_map(|(k, w)| {
+          let at = i + k as isize - half;
+          (0..len as isize)
+            .contains(&at)
+            .then(|| src[start + at as usize * step] * w)
+        })
+        .sum();
+      dst[start + i as usize * step] = value;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn shadow_fades_out_of_box() {
+    let size = Size::new(20., 20.);
+    let img = box_shadow_mask(size, &Radius::all(4.), 0., 8.);
+    let margin = box_shadow_margin(0., 8.) as usize;
+    assert_eq!(margin, 8);
+    assert_eq!((img.width(), img.height()), (36, 36));
+    assert_eq!(img.color_format(), ColorFormat::Alpha8);
+
+    let width = img.width() as usize;
+    let alpha = |x: usize, y: usize| img.pixel_bytes()[y * width + x];
+    // The row across the center of the box, from the center to the left edge.
+    let y = margin + 10;
+    let row = (0..margin + 10)
+      .rev()
+      .map(|x| alpha(x, y))
+      .collect::<Vec<_>>();
+
+    assert!(row[0] > 200);
+    // The alpha decreases outside the box, and the shadow extends beyond the
+    // box by about the blur radius.
+    assert!(row.windows(2).all(|w| w[0] >= w[1]));
+    assert!(alpha(margin - 2, y) > 0);
+    assert!(alpha(0, y) < alpha(margin - 4, y));
+    assert!(alpha(0, y) < 10);
+  }
+  #[test]
+  fn share_mask_of_same_shape() {
+    let size = Size::new(10., 10.);
+    let mask = box_shadow_mask(size, &Radius::all(2.), 1., 4.);
+    let same = box_shadow_mask(size, &Radius::all(2.), 1., 4.);
+    assert!(mask == same);
+
+    let other = box_shadow_mask(size, &Radius::all(2.), 1., 6.);
+    assert!(mask != other);
+  }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
   Color, PixelImage,
-  color::{LinearGradient, RadialGradient},
+  color::{LinearGradient, RadialGradient, SweepGradient},
 };
 
 /// The brush is used to fill or stroke shapes with color, image, or gradient.
@@ -12,8 +12,16 @@ pub enum Brush {
   Color(Color),
   /// Image brush always use a repeat mode to brush the path.
   Image(Resource<PixelImage>),
+  /// An alpha image brush, the alpha channel of the image is colored by the
+  /// `tint`, such as an icon mask. It's also a repeat mode brush, and the image
+  /// must be an `Alpha8` image.
+  TintedImage {
+    img: Resource<PixelImage>,
+    tint: Color,
+  },
   RadialGradient(RadialGradient),
   LinearGradient(LinearGradient),
+  SweepGradient(SweepGradient),
 }
 
 impl Brush {
@@ -29,8 +37,10 @@ impl Brush {
     match self {
       Brush::Color(c) => c.alpha > 0,
       Brush::Image(_) => true,
+      Brush::TintedImage { tint, .. } => tint.alpha > 0,
       Brush::RadialGradient(RadialGradient { ref stops, .. })
-      | Brush::LinearGradient(LinearGradient { ref stops, .. }) => {
+      | Brush::LinearGradient(LinearGradient { ref stops, .. })
+      | Brush::SweepGradient(SweepGradient { ref stops, .. }) => {
         stops.iter().any(|s| s.color.alpha > 0)
       }
     }
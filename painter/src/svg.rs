@@ -6,8 +6,8 @@ use serde::{Deserialize, Serialize};
 use usvg::{Options, Stop, Tree};
 
 use crate::{
-  Brush, Color, CommandBrush, GradientStop, LineCap, LineJoin, PaintCommand, PaintPathAction, Path,
-  StrokeOptions,
+  Brush, Color, CommandBrush, FillRule, GradientStop, LineCap, LineJoin, PaintCommand,
+  PaintPathAction, Path, StrokeOptions,
   color::{LinearGradient, RadialGradient},
 };
 
@@ -156,7 +156,15 @@ fn paint_group(g: &usvg::Group, painter: &mut crate::Painter) {
           let (brush, transform) = brush_from_usvg_paint(fill.paint(), fill.opacity());
 
           let inverse_ts = transform.inverse().unwrap();
-          let path = Resource::new(path.clone().transform(&inverse_ts));
+          let fill_rule = match fill.rule() {
+            usvg::FillRule::NonZero => FillRule::NonZero,
+            usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+          };
+          let path = path
+            .clone()
+            .with_fill_rule(fill_rule)
+            .transform(&inverse_ts);
+          let path = Resource::new(path);
           painter
             .set_fill_brush(brush.clone())
             .apply_transform(&transform)
@@ -274,7 +282,7 @@ fn fallback_color_check(cmds: &[PaintCommand]) -> (bool, bool) {
           }
         }
       }
-      PaintCommand::PopClip => {}
+      PaintCommand::PopClip | PaintCommand::Custom(_) => {}
       PaintCommand::Bundle { cmds, .. } => {
         let (f, s) = fallback_color_check(cmds);
         fill_fallback = f;
@@ -302,6 +310,7 @@ fn brush_replace(cmds: &[PaintCommand], fill: &Brush, stroke: &Brush) -> Box<[Pa
         PaintCommand::Path(p)
       }
       PaintCommand::PopClip => PaintCommand::PopClip,
+      PaintCommand::Custom(c) => PaintCommand::Custom(c.clone()),
       PaintCommand::Bundle { transform, opacity, bounds, cmds } => {
         let cmds = brush_replace(cmds, fill, stroke);
         let cmds = Resource::new(cmds);
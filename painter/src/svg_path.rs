@@ -0,0 +1,407 @@
+use lyon_algorithms::geom::{ArcFlags, SvgArc};
+use ribir_geom::{Angle, Point, Vector};
+
+use crate::{Path, path_builder::PathBuilder};
+
+/// The error occurs when parsing the SVG path data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgPathError {
+  /// Meet an unknown command character at the byte offset.
+  UnknownCommand { offset: usize, cmd: char },
+  /// Expect a number at the byte offset but not found.
+  ExpectedNumber { offset: usize },
+  /// Expect an arc flag (`0` or `1`) at the byte offset but not found.
+  ExpectedFlag { offset: usize },
+  /// The path data does not start with a move to command.
+  MissingMoveTo,
+}
+
+impl std::fmt::Display for SvgPathError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SvgPathError::UnknownCommand { offset, cmd } => {
+        write!(f, "unknown path command `{cmd}` at {offset}")
+      }
+      SvgPathError::ExpectedNumber { offset } => write!(f, "expected a number at {offset}"),
+      SvgPathError::ExpectedFlag { offset } => write!(f, "expected an arc flag at {offset}"),
+      SvgPathError::MissingMoveTo => write!(f, "path data must start with a move to command"),
+    }
+  }
+}
+
+impl std::error::Error for SvgPathError {}
+
+impl Path {
+  /// Parse the path data of the SVG `d` attribute into a path.
+  ///
+  /// All the commands of the path data are supported, both absolute and
+  /// relative. The elliptical arcs are converted to cubic Bézier curves.
+  ///
+  /// ```
+  /// use ribir_painter::Path;
+  ///
+  /// let triangle = Path::from_svg("M 0 0 L 10 0 l -5 10 z").unwrap();
+  /// assert_eq!(triangle.segments().count(), 4);
+  /// ```
+  pub fn from_svg(d: &str) -> Result<Path, SvgPathError> {
+    let mut parser = SvgPathParser::new(d);
+    parser.parse()?;
+    Ok(parser.builder.build())
+  }
+}
+
+struct SvgPathParser<'a> {
+  src: &'a str,
+  pos: usize,
+  builder: PathBuilder,
+  in_path: bool,
+  start: Point,
+  current: Point,
+  /// The last control point and whether it's a cubic one, used by the smooth
+  /// curve commands.
+  last_ctrl: Option<(Point, bool)>,
+}
+
+impl<'a> SvgPathParser<'a> {
+  fn new(src: &'a str) -> Self {
+    Self {
+      src,
+      pos: 0,
+      builder: PathBuilder::default(),
+      in_path: false,
+      start: Point::zero(),
+      current: Point::zero(),
+      last_ctrl: None,
+    }
+  }
+
+  fn parse(&mut self) -> Result<(), SvgPathError> {
+    let mut cmd: Option<u8> = None;
+    loop {
+      self.skip_separators();
+      let Some(&b) = self.src.as_bytes().get(self.pos) else { break };
+      let c = if b.is_ascii_alphabetic() {
+        self.pos += 1;
+        b
+      } else {
+        // Repeat the previous command, a repeated move to is a line to.
+        match cmd {
+          Some(b'M') => b'L',
+          Some(b'm') => b'l',
+          Some(c) if !c.eq_ignore_ascii_case(&b'z') => c,
+          _ => return Err(SvgPathError::ExpectedNumber { offset: self.pos }),
+        }
+      };
+      if cmd.is_none() && !c.eq_ignore_ascii_case(&b'm') {
+        return Err(SvgPathError::MissingMoveTo);
+      }
+      self.command(c)?;
+      cmd = Some(c);
+    }
+    if self.in_path {
+      self.builder.end_path(false);
+    }
+    Ok(())
+  }
+
+  fn command(&mut self, c: u8) -> Result<(), SvgPathError> {
+    let rel = c.is_ascii_lowercase();
+    let base = if rel { self.current.to_vector() } else { Vector::zero() };
+    let mut last_ctrl = None;
+    match c.to_ascii_uppercase() {
+      b'M' => {
+        let to = self.point()? + base;
+        if self.in_path {
+          self.builder.end_path(false);
+        }
+        self.builder.begin_path(to);
+        self.in_path = true;
+        self.start = to;
+        self.current = to;
+      }
+      b'L' => {
+        let to = self.point()? + base;
+        self.line_to(to);
+      }
+      b'H' => {
+        let x = self.number()? + base.x;
+        self.line_to(Point::new(x, self.current.y));
+      }
+      b'V' => {
+        let y = self.number()? + base.y;
+        self.line_to(Point::new(self.current.x, y));
+      }
+      b'C' => {
+        let ctrl1 = self.point()? + base;
+        let ctrl2 = self.point()? + base;
+        let to = self.point()? + base;
+        self.cubic_to(ctrl1, ctrl2, to);
+        last_ctrl = Some((ctrl2, true));
+      }
+      b'S' => {
+        let ctrl1 = self.reflected_ctrl(true);
+        let ctrl2 = self.point()? + base;
+        let to = self.point()? + base;
+        self.cubic_to(ctrl1, ctrl2, to);
+        last_ctrl = Some((ctrl2, true));
+      }
+      b'Q' => {
+        let ctrl = self.point()? + base;
+        let to = self.point()? + base;
+        self.quad_to(ctrl, to);
+        last_ctrl = Some((ctrl, false));
+      }
+      b'T' => {
+        let ctrl = self.reflected_ctrl(false);
+        let to = self.point()? + base;
+        self.quad_to(ctrl, to);
+        last_ctrl = Some((ctrl, false));
+      }
+      b'A' => {
+        let radii = Vector::new(self.number()?, self.number()?);
+        let x_rotation = Angle::degrees(self.number()?);
+        let large_arc = self.flag()?;
+        let sweep = self.flag()?;
+        let to = self.point()? + base;
+        self.arc_to(radii, x_rotation, ArcFlags { large_arc, sweep }, to);
+      }
+      b'Z' => {
+        if self.in_path {
+          self.builder.end_path(true);
+          self.in_path = false;
+        }
+        self.current = self.start;
+      }
+      _ => {
+        let offset = self.pos - 1;
+        return Err(SvgPathError::UnknownCommand { offset, cmd: c as char });
+      }
+    }
+    self.last_ctrl = last_ctrl;
+    Ok(())
+  }
+
+  fn ensure_in_path(&mut self) {
+    if !self.in_path {
+      self.builder.begin_path(self.current);
+      self.start = self.current;
+      self.in_path = true;
+    }
+  }
+
+  fn line_to(&mut self, to: Point) {
+    self.ensure_in_path();
+    self.builder.line_to(to);
+    self.current = to;
+  }
+
+  fn cubic_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+    self.ensure_in_path();
+    self.builder.bezier_curve_to(ctrl1, ctrl2, to);
+    self.current = to;
+  }
+
+  fn quad_to(&mut self, ctrl: Point, to: Point) {
+    self.ensure_in_path();
+    self.builder.quadratic_curve_to(ctrl, to);
+    self.current = to;
+  }
+
+  fn arc_to(&mut self, radii: Vector, x_rotation: Angle, flags: ArcFlags, to: Point) {
+    let arc = SvgArc {
+      from: self.current.to_untyped(),
+      to: to.to_untyped(),
+      radii: radii.to_untyped(),
+      x_rotation,
+      flags,
+    };
+    if arc.is_straight_line() {
+      self.line_to(to);
+    } else {
+      self.ensure_in_path();
+      arc.to_arc().for_each_cubic_bezier(&mut |c| {
+        self
+          .builder
+          .bezier_curve_to(c.ctrl1.cast_unit(), c.ctrl2.cast_unit(), c.to.cast_unit());
+      });
+      self.current = to;
+    }
+  }
+
+  /// The control point of a smooth curve is the reflection of the previous
+  /// control point if the previous command is the same kind of curve.
+  fn reflected_ctrl(&self, cubic: bool) -> Point {
+    match self.last_ctrl {
+      Some((ctrl, is_cubic)) if is_cubic == cubic => self.current + (self.current - ctrl),
+      _ => self.current,
+    }
+  }
+
+  fn point(&mut self) -> Result<Point, SvgPathError> {
+    let x = self.number()?;
+    let y = self.number()?;
+    Ok(Point::new(x, y))
+  }
+
+  fn flag(&mut self) -> Result<bool, SvgPathError> {
+    self.skip_separators();
+    let flag = match self.src.as_bytes().get(self.pos) {
+      Some(b'0') => false,
+      Some(b'1') => true,
+      _ => return Err(SvgPathError::ExpectedFlag { offset: self.pos }),
+    };
+    self.pos += 1;
+    Ok(flag)
+  }
+
+  fn number(&mut self) -> Result<f32, SvgPathError> {
+    self.skip_separators();
+    let bytes = self.src.as_bytes();
+    let start = self.pos;
+    let mut end = start;
+    if matches!(bytes.get(end), Some(b'+' | b'-')) {
+      end += 1;
+    }
+    let mut has_dot = false;
+    while let Some(&b) = bytes.get(end) {
+      if b.is_ascii_digit() {
+        end += 1;
+      } else if b == b'.' && !has_dot {
+        has_dot = true;
+        end += 1;
+      } else {
+        break;
+      }
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+      let mut exp = end + 1;
+      if matches!(bytes.get(exp), Some(b'+' | b'-')) {
+        exp += 1;
+      }
+      if bytes.get(exp).is_some_and(u8::is_ascii_digit) {
+        end = exp;
+        while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+          end += 1;
+        }
+      }
+    }
+
+    let value = self.src[start..end]
+      .parse()
+      .map_err(|_| SvgPathError::ExpectedNumber { offset: start })?;
+    self.pos = end;
+    Ok(value)
+  }
+
+  fn skip_separators(&mut self) {
+    let bytes = self.src.as_bytes();
+    while bytes
+      .get(self.pos)
+      .is_some_and(|b| b.is_ascii_whitespace() || *b == b',')
+    {
+      self.pos += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::PathSegment;
+
+  fn segments(d: &str) -> Vec<PathSegment> { Path::from_svg(d).unwrap().segments().collect() }
+
+  #[test]
+  fn absolute_and_relative() {
+    let abs = segments("M10 10 L20 10 L20 20 Z");
+    let rel = segments("m10,10 l10,0 l0,10 z");
+    let expected = [
+      PathSegment::MoveTo(Point::new(10., 10.)),
+      PathSegment::LineTo(Point::new(20., 10.)),
+      PathSegment::LineTo(Point::new(20., 20.)),
+      PathSegment::Close(true),
+    ];
+    assert_eq!(abs, expected);
+    assert_eq!(rel, expected);
+  }
+
+  #[test]
+  fn implicit_commands() {
+    assert_eq!(
+      segments("M0 0 10 0 10 10H0V5"),
+      [
+        PathSegment::MoveTo(Point::new(0., 0.)),
+        PathSegment::LineTo(Point::new(10., 0.)),
+        PathSegment::LineTo(Point::new(10., 10.)),
+        PathSegment::LineTo(Point::new(0., 10.)),
+        PathSegment::LineTo(Point::new(0., 5.)),
+        PathSegment::Close(false),
+      ]
+    );
+  }
+
+  #[test]
+  fn curves() {
+    assert_eq!(
+      segments("M0 0C0 10 10 10 10 0S20-10 20 0q5 5 10 0t10 0"),
+      [
+        PathSegment::MoveTo(Point::new(0., 0.)),
+        PathSegment::CubicTo {
+          ctrl1: Point::new(0., 10.),
+          ctrl2: Point::new(10., 10.),
+          to: Point::new(10., 0.),
+        },
+        PathSegment::CubicTo {
+          ctrl1: Point::new(10., -10.),
+          ctrl2: Point::new(20., -10.),
+          to: Point::new(20., 0.),
+        },
+        PathSegment::QuadTo { ctrl: Point::new(25., 5.), to: Point::new(30., 0.) },
+        PathSegment::QuadTo { ctrl: Point::new(35., -5.), to: Point::new(40., 0.) },
+        PathSegment::Close(false),
+      ]
+    );
+  }
+
+  #[test]
+  fn arc_to_cubic() {
+    // A half circle with radius 10 from (0, 0) to (20, 0).
+    let segs = segments("M0 0A10 10 0 0 1 20 0");
+    assert_eq!(segs[0], PathSegment::MoveTo(Point::zero()));
+    let cubics: Vec<_> = segs[1..segs.len() - 1].to_vec();
+    assert!(cubics.len() >= 2);
+    assert!(
+      cubics
+        .iter()
+        .all(|s| matches!(s, PathSegment::CubicTo { .. }))
+    );
+
+    let PathSegment::CubicTo { to, .. } = cubics.last().unwrap() else { unreachable!() };
+    assert!((to.x - 20.).abs() < 1e-3 && to.y.abs() < 1e-3);
+    // Every end point is on the circle.
+    for s in &cubics {
+      let PathSegment::CubicTo { to, .. } = s else { unreachable!() };
+      let r = (*to - Point::new(10., 0.)).length();
+      assert!((r - 10.).abs() < 1e-3);
+    }
+  }
+
+  #[test]
+  fn compact_arc_flags() {
+    assert_eq!(segments("M0 0a10 10 0 0120 0"), segments("M0 0 a 10 10 0 0 1 20 0"));
+  }
+
+  #[test]
+  fn errors() {
+    assert_eq!(Path::from_svg("L10 10").unwrap_err(), SvgPathError::MissingMoveTo);
+    assert_eq!(
+      Path::from_svg("M0 0 X1").unwrap_err(),
+      SvgPathError::UnknownCommand { offset: 5, cmd: 'X' }
+    );
+    assert_eq!(Path::from_svg("M0").unwrap_err(), SvgPathError::ExpectedNumber { offset: 2 });
+    assert_eq!(
+      Path::from_svg("M0 0 A1 1 0 2 0 1 1").unwrap_err(),
+      SvgPathError::ExpectedFlag { offset: 12 }
+    );
+  }
+}
@@ -11,12 +11,14 @@ use font_db::Face;
 pub use fontdb::{ID, Stretch as FontStretch, Style as FontStyle, Weight as FontWeight};
 pub use ribir_algo::Substr;
 use ribir_geom::{Rect, rect};
+pub use rustybuzz::ttf_parser::Tag;
 use rustybuzz::{GlyphPosition, ttf_parser::GlyphId};
+use unicode_segmentation::UnicodeSegmentation;
 pub mod text_reorder;
 pub mod typography;
 pub use text_reorder::TextReorder;
 mod typography_store;
-pub use typography_store::{TypographyStore, VisualGlyphs};
+pub use typography_store::{TypographyStore, VisualGlyphs, word_range_at};
 mod svg_glyph_cache;
 
 // Enum value descriptions are from the CSS spec.
@@ -79,10 +81,46 @@ pub struct TextStyle {
   pub font_face: FontFace,
   /// The space between characters in logical pixel units.
   pub letter_space: f32,
-  /// The line height of the text in logical pixels.
-  pub line_height: f32,
+  /// The line height of the text.
+  pub line_height: LineHeight,
   /// How to handle the visual overflow.
   pub overflow: TextOverflow,
+  /// The OpenType features to enable or disable when shaping the text, such as
+  /// `liga`, `smcp` and `tnum`. The value `0` disables the feature, and `1`
+  /// enables it, others select an alternate glyph of the feature.
+  pub font_features: Vec<(Tag, u32)>,
+  /// The index of the CPAL palette to color the COLR glyphs, the first palette
+  /// is used if the font has no such palette.
+  pub palette_index: u16,
+  /// How to change the case of the text before it's shaped.
+  pub text_transform: TextTransform,
+  /// The language rules to change the case of the text by the
+  /// `text_transform`.
+  pub case_locale: CaseLocale,
+}
+
+/// The height of a line of text.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineHeight {
+  /// The line height in logical pixels.
+  Absolute(f32),
+  /// The line height as a multiple of the font size, like `1.5` in CSS.
+  Factor(f32),
+}
+
+impl LineHeight {
+  /// Return the line height in logical pixels for the `font_size`.
+  pub fn to_pixel(self, font_size: f32) -> f32 {
+    match self {
+      LineHeight::Absolute(h) => h,
+      LineHeight::Factor(f) => f * font_size,
+    }
+  }
+}
+
+impl From<f32> for LineHeight {
+  #[inline]
+  fn from(value: f32) -> Self { LineHeight::Absolute(value) }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
@@ -135,9 +173,9 @@ impl Default for FontFace {
   }
 }
 
-/// Text-align relative to the horizontal or vertical, not caring about whether
-/// the text is left-to-right or right-to-left, In the horizontal the left is
-/// the start, and in vertical the top is the start.
+/// Text-align relative to the direction of the text. In the horizontal, the
+/// start is the left of a left-to-right paragraph and the right of a
+/// right-to-left paragraph, and in vertical the top is the start.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TextAlign {
   Start,
@@ -145,6 +183,32 @@ pub enum TextAlign {
   End,
 }
 
+/// How to change the case of the text before it's shaped, like the
+/// `text-transform` of CSS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextTransform {
+  #[default]
+  None,
+  /// Convert all the characters to uppercase.
+  Upper,
+  /// Convert all the characters to lowercase.
+  Lower,
+  /// Convert the first character of every word to uppercase.
+  Capitalize,
+}
+
+/// The language rules to change the case of the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CaseLocale {
+  /// The default case mapping of Unicode.
+  #[default]
+  Default,
+  /// The rules of Turkish and Azerbaijani, the dotted and the dotless `i` are
+  /// different letters, so `i` is uppercased to `İ` and `I` is lowercased to
+  /// `ı`.
+  Turkic,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextDirection {
   /// Text is set horizontally from left to right.
@@ -157,6 +221,42 @@ pub enum TextDirection {
   BottomToTop,
 }
 
+impl TextTransform {
+  /// Return the transformed text, and the byte index in the `text` of every
+  /// byte of the transformed text. Return `None` if the transform is `None`.
+  pub fn apply(self, text: &str, locale: CaseLocale) -> Option<(String, Vec<u32>)> {
+    if self == TextTransform::None {
+      return None;
+    }
+    let mut transformed = String::with_capacity(text.len());
+    let mut map = Vec::with_capacity(text.len());
+    for (start, word) in text.split_word_bound_indices() {
+      for (offset, c) in word.char_indices() {
+        match self {
+          TextTransform::Upper => locale.push_case(c, true, &mut transformed),
+          TextTransform::Lower => locale.push_case(c, false, &mut transformed),
+          TextTransform::Capitalize if offset == 0 => locale.push_case(c, true, &mut transformed),
+          _ => transformed.push(c),
+        }
+        map.resize(transformed.len(), (start + offset) as u32);
+      }
+    }
+    Some((transformed, map))
+  }
+}
+
+impl CaseLocale {
+  fn push_case(self, c: char, upper: bool, s: &mut String) {
+    match (self, upper, c) {
+      (CaseLocale::Turkic, true, 'i') => s.push('İ'),
+      (CaseLocale::Turkic, false, 'I') => s.push('ı'),
+      (CaseLocale::Turkic, false, 'İ') => s.push('i'),
+      (_, true, c) => s.extend(c.to_uppercase()),
+      (_, false, c) => s.extend(c.to_lowercase()),
+    }
+  }
+}
+
 impl TextDirection {
   #[inline]
   pub fn is_vertical(&self) -> bool {
@@ -251,8 +351,12 @@ impl Default for TextStyle {
       font_size: 14.,
       font_face: Default::default(),
       letter_space: 0.,
-      line_height: 16.,
+      line_height: LineHeight::Absolute(16.),
       overflow: <_>::default(),
+      font_features: vec![],
+      palette_index: 0,
+      text_transform: TextTransform::None,
+      case_locale: CaseLocale::Default,
     }
   }
 }
@@ -5,10 +5,10 @@ use fontdb::{Database, Query};
 pub use fontdb::{FaceInfo, Family, ID};
 use ribir_algo::{Resource, Sc};
 use ribir_geom::{Point, Rect, rect};
-use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder};
+use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder, RgbaColor, colr};
 
 use crate::{
-  Path, PixelImage, Svg,
+  Color, Path, PixelImage, Svg,
   path_builder::PathBuilder,
   text::{FontFace, FontFamily, svg_glyph_cache::SvgGlyphCache},
 };
@@ -65,6 +65,16 @@ impl FontDB {
     get_or_insert_face(&mut self.cache, &self.data_base, face_id).as_ref()
   }
 
+  /// Return the ink bounding box of the glyph in the em unit, see
+  /// [`Face::glyph_bounds`].
+  ///
+  /// Return `None` if the face is not loaded yet or the glyph has no outline.
+  pub fn glyph_bounds(&self, face_id: ID, glyph_id: GlyphId) -> Option<Rect> {
+    self
+      .try_get_face_data(face_id)?
+      .glyph_bounds(glyph_id)
+  }
+
   /// Selects a `FaceInfo` by `id`.
   ///
   /// Returns `None` if a face with such ID was already removed,
@@ -89,6 +99,22 @@ impl FontDB {
   #[inline]
   pub fn load_from_bytes(&mut self, data: Vec<u8>) { self.data_base.load_font_data(data); }
 
+  /// Loads the font faces from the font data in memory, return the ids of the
+  /// loaded faces.
+  ///
+  /// Will load all font faces in case of a font collection, and return an
+  /// `InvalidData` error if no face can be parsed from the data.
+  pub fn load_font_data(&mut self, data: Vec<u8>) -> Result<Vec<ID>, std::io::Error> {
+    let ids = self
+      .data_base
+      .load_font_source(fontdb::Source::Binary(Arc::new(data)));
+    if ids.is_empty() {
+      Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no font face in the data"))
+    } else {
+      Ok(ids.to_vec())
+    }
+  }
+
   /// Loads a font file into the `Database`.
   ///
   /// Will load all font faces in case of a font collection.
@@ -322,6 +348,8 @@ impl Face {
 
   pub fn descender(&self) -> i16 { self.descender }
 
+  pub fn line_gap(&self) -> i16 { self.rb_face.line_gap() }
+
   pub fn x_height(&self) -> u16 { self.x_height }
 
   pub fn baseline_offset(&self, baseline: GlyphBaseline) -> i16 {
@@ -355,6 +383,18 @@ impl Face {
       .cloned()
   }
 
+  /// Return the ink bounding box of the glyph in the em unit, that's the box
+  /// of its outline in the font coordinate(the y-axis points up) divided by
+  /// the units per em. It's different from the advance box of the glyph, the
+  /// ink may extend beyond the advance or leave side bearings in it.
+  ///
+  /// Return `None` if the glyph has no outline, such as a space.
+  pub fn glyph_bounds(&self, glyph_id: GlyphId) -> Option<Rect> {
+    let b = self.rb_face.glyph_bounding_box(glyph_id)?;
+    let unit = self.units_per_em() as f32;
+    Some(rect(b.x_min, b.y_min, b.width(), b.height()).to_f32() / unit)
+  }
+
   pub fn glyph_raster_image(
     &self, glyph_id: GlyphId, pixels_per_em: u16,
   ) -> Option<Resource<PixelImage>> {
@@ -385,10 +425,68 @@ impl Face {
       .clone()
   }
 
+  /// Return the layers of a COLR glyph from the bottom to the top, every layer
+  /// is an outline filled by a color of the CPAL palette of `palette_index`,
+  /// or the `foreground` color if the layer uses the text color. The first
+  /// palette is used if the face has no palette of `palette_index`.
+  ///
+  /// Return `None` if the glyph is not a color glyph. Only the solid layers are
+  /// supported, the gradient layers are skipped.
+  pub fn color_glyph_layers(
+    &self, glyph_id: GlyphId, palette_index: u16, foreground: Color,
+  ) -> Option<Vec<(Resource<Path>, Color)>> {
+    if !self.is_color_glyph(glyph_id) {
+      return None;
+    }
+
+    let palettes = self.color_palettes().map_or(0, |n| n.get());
+    let palette = if palette_index < palettes { palette_index } else { 0 };
+    let Color { red, green, blue, alpha } = foreground;
+    let foreground = RgbaColor::new(red, green, blue, alpha);
+    let mut painter = ColorLayersPainter { face: self, outline: None, layers: vec![] };
+    self.paint_color_glyph(glyph_id, palette, foreground, &mut painter)?;
+    Some(painter.layers)
+  }
+
   #[inline]
   pub fn units_per_em(&self) -> u16 { self.rb_face.deref().units_per_em() }
 }
 
+/// Collect the solid layers of a COLR glyph.
+struct ColorLayersPainter<'a> {
+  face: &'a Face,
+  outline: Option<Resource<Path>>,
+  layers: Vec<(Resource<Path>, Color)>,
+}
+
+impl<'a> colr::Painter<'a> for ColorLayersPainter<'_> {
+  fn outline_glyph(&mut self, glyph_id: GlyphId) {
+    self.outline = self.face.outline_glyph(glyph_id);
+  }
+
+  fn paint(&mut self, paint: colr::Paint<'a>) {
+    if let (Some(path), colr::Paint::Solid(c)) = (self.outline.take(), paint) {
+      self
+        .layers
+        .push((path, Color::new(c.red, c.green, c.blue, c.alpha)));
+    }
+  }
+
+  fn push_clip(&mut self) {}
+
+  fn push_clip_box(&mut self, _: colr::ClipBox) {}
+
+  fn pop_clip(&mut self) {}
+
+  fn push_layer(&mut self, _: colr::CompositeMode) {}
+
+  fn pop_layer(&mut self) {}
+
+  fn push_transform(&mut self, _: rustybuzz::ttf_parser::Transform) {}
+
+  fn pop_transform(&mut self) {}
+}
+
 fn to_db_family(f: &FontFamily) -> Family {
   match f {
     FontFamily::Name(name) => Family::Name(name),
@@ -542,6 +640,77 @@ mod tests {
     assert!(face_id.is_some());
   }
 
+  #[test]
+  fn load_font_data() {
+    let mut db = FontDB::default();
+    let bytes = include_bytes!("../../../fonts/DejaVuSans.ttf");
+    let ids = db.load_font_data(bytes.to_vec()).unwrap();
+    assert_eq!(ids.len(), 1);
+
+    let face_id = db.select_best_match(&FontFace {
+      families: vec![FontFamily::Name("DejaVu Sans".into())].into_boxed_slice(),
+      ..<_>::default()
+    });
+    assert_eq!(face_id, Some(ids[0]));
+
+    assert!(db.load_font_data(vec![0; 16]).is_err());
+  }
+
+  #[test]
+  fn color_glyph_palette() {
+    let mut db = FontDB::default();
+    let bytes = include_bytes!("../../../fonts/colr-search.ttf");
+    let ids = db.load_font_data(bytes.to_vec()).unwrap();
+    let face = db.face_data_or_insert(ids[0]).unwrap();
+
+    // The glyph of `a` has two layers, colored by the first two palette entries.
+    let glyph = face.glyph_index('a').unwrap();
+    let colors = |palette| -> Vec<Color> {
+      let layers = face.color_glyph_layers(glyph, palette, Color::BLACK);
+      layers
+        .unwrap()
+        .into_iter()
+        .map(|(_, c)| c)
+        .collect()
+    };
+    assert_eq!(colors(0), [Color::RED, Color::GREEN]);
+    assert_eq!(colors(1), [Color::BLUE, Color::WHITE]);
+    // No such palette, fall back to the first one.
+    assert_eq!(colors(2), [Color::RED, Color::GREEN]);
+
+    let plain = face.glyph_index('c').unwrap();
+    assert!(
+      face
+        .color_glyph_layers(plain, 0, Color::BLACK)
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn glyph_ink_bounds() {
+    let mut db = FontDB::default();
+    let bytes = include_bytes!("../../../fonts/DejaVuSans.ttf");
+    let ids = db.load_font_data(bytes.to_vec()).unwrap();
+    let face = db.face_data_or_insert(ids[0]).unwrap();
+    let unit = face.units_per_em() as f32;
+    let advance = |g| face.glyph_hor_advance(g).unwrap() as f32 / unit;
+
+    // The ink of `o` is inside its advance, with side bearings on both sides.
+    let o = face.glyph_index('o').unwrap();
+    let o_advance = advance(o);
+    let space = face.glyph_index(' ').unwrap();
+    let space_advance = advance(space);
+
+    let bounds = db.glyph_bounds(ids[0], o).unwrap();
+    assert!(bounds.min_x() > 0.);
+    assert!(bounds.max_x() < o_advance);
+    assert!(bounds.min_y() < 0. && bounds.max_y() > 0.);
+
+    // A space has an advance but no ink.
+    assert!(space_advance > 0.);
+    assert!(db.glyph_bounds(ids[0], space).is_none());
+  }
+
   #[test]
   fn load_sys_fonts() {
     let mut db = FontDB::default();
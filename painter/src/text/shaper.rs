@@ -5,11 +5,11 @@ use std::{
 
 use ribir_algo::{FrameCache, Sc, Substr};
 pub use rustybuzz::ttf_parser::GlyphId;
-use rustybuzz::{GlyphInfo, UnicodeBuffer};
+use rustybuzz::{Feature, GlyphInfo, UnicodeBuffer};
 
 use super::{GlyphUnit, font_db::GlyphBaseline};
 use crate::{
-  Glyph, TextDirection,
+  CaseLocale, Glyph, Tag, TextDirection, TextTransform,
   font_db::{Face, FontDB, ID},
 };
 
@@ -21,6 +21,21 @@ pub const NEWLINE_GLYPH_ID: GlyphId = GlyphId(u16::MAX);
 pub struct TextShaper {
   font_db: Sc<RefCell<FontDB>>,
   shape_cache: FrameCache<ShapeKey, Sc<ShapeResult>>,
+  /// The last shaped text, its glyphs are reused when the next text only
+  /// differs in a range of it, such as typing in a line.
+  last_shaped: Option<(ShapeKey, Sc<ShapeResult>)>,
+  stats: ShapeStats,
+}
+
+/// The counters of the shaping work, to know how much the caches help.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShapeStats {
+  /// The times the whole text is found in the cache.
+  pub cache_hits: usize,
+  /// The glyphs reused from the last shaped text rather than shaped again.
+  pub reused_glyphs: usize,
+  /// The bytes of the text that are actually shaped.
+  pub shaped_bytes: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +47,7 @@ pub struct ShapeResult {
 #[derive(PartialEq, Eq, Hash, Clone)]
 struct ShapeKey {
   face_ids: Box<[ID]>,
+  features: Box<[(Tag, u32)]>,
   text: Substr,
   direction: TextDirection,
   baseline: GlyphBaseline,
@@ -44,7 +60,11 @@ struct GlyphsWithoutFallback {
 
 impl TextShaper {
   #[inline]
-  pub fn new(font_db: Sc<RefCell<FontDB>>) -> Self { Self { font_db, shape_cache: <_>::default() } }
+  pub fn new(font_db: Sc<RefCell<FontDB>>) -> Self {
+    Self { font_db, shape_cache: <_>::default(), last_shaped: None, stats: <_>::default() }
+  }
+
+  pub fn stats(&self) -> ShapeStats { self.stats }
 
   pub fn end_frame(&mut self) { self.shape_cache.end_frame("Text shape"); }
 
@@ -53,11 +73,32 @@ impl TextShaper {
   pub fn shape_text(
     &mut self, text: &Substr, face_ids: &[ID], direction: TextDirection, baseline: GlyphBaseline,
   ) -> Sc<ShapeResult> {
-    if let Some(res) = self.get_cache(text, face_ids, direction, baseline) {
+    self.shape_text_with_features(text, face_ids, &[], direction, baseline)
+  }
+
+  /// Shape text with the OpenType `features` enabled or disabled, the default
+  /// features of the font are used if a feature is not specified.
+  pub fn shape_text_with_features(
+    &mut self, text: &Substr, face_ids: &[ID], features: &[(Tag, u32)], direction: TextDirection,
+    baseline: GlyphBaseline,
+  ) -> Sc<ShapeResult> {
+    if let Some(res) = self.get_cache(text, face_ids, features, direction, baseline) {
+      self.stats.cache_hits += 1;
       res.clone()
     } else {
+      let key = ShapeKey {
+        face_ids: face_ids.into(),
+        features: features.into(),
+        text: text.clone(),
+        direction,
+        baseline,
+      };
       let mut glyphs = self
-        .shape_text_with_fallback(text, direction, face_ids, baseline)
+        .reshape_from_last(&key)
+        .or_else(|| {
+          self.stats.shaped_bytes += text.len();
+          self.shape_text_with_fallback(text, direction, face_ids, features, baseline)
+        })
         .unwrap_or_default();
 
       if let Some(last_char) = text.bytes().last() {
@@ -69,18 +110,118 @@ impl TextShaper {
       }
 
       let glyphs = Sc::new(ShapeResult { text: text.clone(), glyphs });
-      self.shape_cache.put(
-        ShapeKey { face_ids: face_ids.into(), text: text.clone(), direction, baseline },
-        glyphs.clone(),
-      );
+      self.shape_cache.put(key.clone(), glyphs.clone());
+      self.last_shaped = Some((key, glyphs.clone()));
       glyphs
     }
   }
 
+  /// Shape the text after changing its case by the `transform`. The glyphs are
+  /// shaped from the transformed text, but their clusters are the byte indices
+  /// of the original `text`, so they still map back to the original text.
+  #[allow(clippy::too_many_arguments)]
+  pub fn shape_text_with_transform(
+    &mut self, text: &Substr, transform: TextTransform, locale: CaseLocale, face_ids: &[ID],
+    features: &[(Tag, u32)], direction: TextDirection, baseline: GlyphBaseline,
+  ) -> Sc<ShapeResult> {
+    let Some((transformed, map)) = transform.apply(text, locale) else {
+      return self.shape_text_with_features(text, face_ids, features, direction, baseline);
+    };
+    let transformed = Substr::from(transformed);
+    let res = self.shape_text_with_features(&transformed, face_ids, features, direction, baseline);
+    let glyphs = res
+      .glyphs
+      .iter()
+      .map(|g| {
+        let mut g = g.clone();
+        g.cluster = map
+          .get(g.cluster as usize)
+          .copied()
+          .unwrap_or(text.len() as u32);
+        g
+      })
+      .collect();
+    Sc::new(ShapeResult { text: text.clone(), glyphs })
+  }
+
+  /// Shape the text of `key` by reusing the glyphs of the last shaped text for
+  /// the unchanged prefix and suffix, only the edited range between them is
+  /// shaped. The edited range is extended to the whitespaces around it, so
+  /// the reused glyphs have the same neighbors as they were shaped with, and
+  /// the shaping context across the boundaries, such as the kerning and the
+  /// ligatures, is not lost. Only the left-to-right text is supported, return
+  /// `None` if nothing can be reused.
+  fn reshape_from_last(&mut self, key: &ShapeKey) -> Option<Vec<Glyph>> {
+    if key.direction != TextDirection::LeftToRight {
+      return None;
+    }
+    let last = self
+      .last_shaped
+      .as_ref()
+      .filter(|(last, _)| {
+        last.face_ids == key.face_ids
+          && last.features == key.features
+          && last.direction == key.direction
+          && last.baseline == key.baseline
+      })
+      .map(|(_, res)| res.clone())?;
+
+    let old: &str = &last.text;
+    let new: &str = &key.text;
+    // The glyph of a line break is replaced, so it can't be reused in the prefix.
+    let old_content = old.trim_end_matches(['\r', '\n']);
+    let prefix = common_prefix(old_content, new);
+    let suffix = common_suffix(&old[prefix..], &new[prefix..]);
+    if prefix == 0 && suffix == 0 {
+      return None;
+    }
+
+    let mut glyphs: Vec<Glyph> = last
+      .glyphs
+      .iter()
+      .take_while(|g| (g.cluster as usize) < prefix)
+      .cloned()
+      .collect();
+    let mut reused = glyphs.len();
+
+    let (old_suffix, new_suffix) = (old.len() - suffix, new.len() - suffix);
+    let middle = &new[prefix..new_suffix];
+    if !middle.is_empty() {
+      let shaped = self.shape_text_with_fallback(
+        middle,
+        key.direction,
+        &key.face_ids,
+        &key.features,
+        key.baseline,
+      )?;
+      self.stats.shaped_bytes += middle.len();
+      glyphs.extend(shaped.into_iter().map(|mut g| {
+        g.cluster += prefix as u32;
+        g
+      }));
+    }
+
+    for g in last.glyphs.iter() {
+      if old_suffix <= g.cluster as usize {
+        let mut g = g.clone();
+        g.cluster = (g.cluster as usize - old_suffix + new_suffix) as u32;
+        glyphs.push(g);
+        reused += 1;
+      }
+    }
+    self.stats.reused_glyphs += reused;
+    Some(glyphs)
+  }
+
   /// Directly shape text without bidi reordering.
   pub fn shape_text_with_fallback(
-    &self, text: &str, dir: TextDirection, face_ids: &[ID], baseline: GlyphBaseline,
+    &self, text: &str, dir: TextDirection, face_ids: &[ID], features: &[(Tag, u32)],
+    baseline: GlyphBaseline,
   ) -> Option<Vec<Glyph>> {
+    let features: Vec<_> = features
+      .iter()
+      .map(|&(tag, value)| Feature::new(tag, value, ..))
+      .collect();
     let mut font_fallback = FallBackFaceHelper::new(face_ids, &self.font_db);
     let face = font_fallback.next_fallback_face(text)?;
     let mut buffer = UnicodeBuffer::new();
@@ -88,23 +229,24 @@ impl TextShaper {
     buffer.set_direction(dir.into());
 
     let GlyphsWithoutFallback { mut glyphs, mut buffer } =
-      Self::directly_shape(buffer, baseline, dir.is_horizontal(), &face);
+      Self::directly_shape(buffer, &features, baseline, dir.is_horizontal(), &face);
     let mut new_part = vec![(0, glyphs.len(), font_fallback.clone())];
     loop {
       if new_part.is_empty() {
         break;
       }
       let miss_part = collect_miss_part(&glyphs, &new_part);
-      (buffer, new_part) = regen_miss_part(text, dir, baseline, &mut glyphs, miss_part, buffer);
+      (buffer, new_part) =
+        regen_miss_part(text, dir, &features, baseline, &mut glyphs, miss_part, buffer);
     }
 
     Some(glyphs)
   }
 
   fn directly_shape(
-    text: UnicodeBuffer, baseline: GlyphBaseline, hor_text: bool, face: &Face,
+    text: UnicodeBuffer, features: &[Feature], baseline: GlyphBaseline, hor_text: bool, face: &Face,
   ) -> GlyphsWithoutFallback {
-    let output = rustybuzz::shape(face.as_rb_face(), &[], text);
+    let output = rustybuzz::shape(face.as_rb_face(), features, text);
     let mut glyphs = Vec::with_capacity(output.len());
 
     let infos = output.glyph_infos();
@@ -121,6 +263,10 @@ impl TextShaper {
       if hor_text {
         g.y_offset -= shift;
       } else {
+        // The vertical positions of rustybuzz grow upward, but the lines grow
+        // downward.
+        g.y_advance = -g.y_advance;
+        g.y_offset = -g.y_offset;
         g.x_offset -= shift;
       }
       glyphs.push(g)
@@ -130,17 +276,48 @@ impl TextShaper {
   }
 
   pub fn get_cache(
-    &mut self, text: &str, face_ids: &[ID], direction: TextDirection, baseline: GlyphBaseline,
+    &mut self, text: &str, face_ids: &[ID], features: &[(Tag, u32)], direction: TextDirection,
+    baseline: GlyphBaseline,
   ) -> Option<Sc<ShapeResult>> {
     self
       .shape_cache
-      .get(&(face_ids, text, direction, baseline) as &(dyn ShapeKeySlice))
+      .get(&(face_ids, features, text, direction, baseline) as &dyn ShapeKeySlice)
       .cloned()
   }
 
   pub fn font_db(&self) -> &Sc<RefCell<FontDB>> { &self.font_db }
 }
 
+/// The length of the common prefix of `a` and `b` that is followed by a
+/// common whitespace.
+fn common_prefix(a: &str, b: &str) -> usize {
+  let mut len = 0;
+  for ((idx, ca), cb) in a.char_indices().zip(b.chars()) {
+    if ca != cb {
+      break;
+    }
+    if ca.is_whitespace() {
+      len = idx;
+    }
+  }
+  len
+}
+
+/// The length of the common suffix of `a` and `b` that is preceded by a common
+/// whitespace.
+fn common_suffix(a: &str, b: &str) -> usize {
+  let mut len = 0;
+  for ((idx, ca), cb) in a.char_indices().rev().zip(b.chars().rev()) {
+    if ca != cb {
+      break;
+    }
+    if ca.is_whitespace() {
+      len = a.len() - idx - ca.len_utf8();
+    }
+  }
+  len
+}
+
 fn collect_miss_part<'a>(
   glyphs: &[Glyph], new_part: &[(usize, usize, FallBackFaceHelper<'a>)],
 ) -> Vec<(usize, usize, FallBackFaceHelper<'a>)> {
@@ -181,8 +358,9 @@ fn collect_miss_part<'a>(
 }
 
 fn regen_miss_part<'a>(
-  text: &str, dir: TextDirection, baseline: GlyphBaseline, glyphs: &mut Vec<Glyph>,
-  miss_part: Vec<(usize, usize, FallBackFaceHelper<'a>)>, mut buffer: UnicodeBuffer,
+  text: &str, dir: TextDirection, features: &[Feature], baseline: GlyphBaseline,
+  glyphs: &mut Vec<Glyph>, miss_part: Vec<(usize, usize, FallBackFaceHelper<'a>)>,
+  mut buffer: UnicodeBuffer,
 ) -> (UnicodeBuffer, Vec<(usize, usize, FallBackFaceHelper<'a>)>) {
   let is_rtl = matches!(dir, TextDirection::RightToLeft | TextDirection::BottomToTop);
   let hb_direction = dir.into();
@@ -211,7 +389,8 @@ fn regen_miss_part<'a>(
     if let Some(face) = helper.next_fallback_face(miss_text) {
       buffer.push_str(miss_text);
       buffer.set_direction(hb_direction);
-      let mut res = TextShaper::directly_shape(buffer, baseline, dir.is_horizontal(), &face);
+      let mut res =
+        TextShaper::directly_shape(buffer, features, baseline, dir.is_horizontal(), &face);
       buffer = res.buffer;
       for g in res.glyphs.iter_mut() {
         g.cluster += miss_range.start as u32;
@@ -227,6 +406,7 @@ fn regen_miss_part<'a>(
 
 trait ShapeKeySlice {
   fn face_ids(&self) -> &[ID];
+  fn features(&self) -> &[(Tag, u32)];
   fn text(&self) -> &str;
   fn direction(&self) -> TextDirection;
   fn baseline(&self) -> GlyphBaseline;
@@ -239,6 +419,7 @@ impl<'a> std::borrow::Borrow<dyn ShapeKeySlice + 'a> for ShapeKey {
 impl Hash for dyn ShapeKeySlice + '_ {
   fn hash<H: Hasher>(&self, state: &mut H) {
     self.face_ids().hash(state);
+    self.features().hash(state);
     self.text().hash(state);
     self.direction().hash(state);
     self.baseline().hash(state);
@@ -248,6 +429,7 @@ impl Hash for dyn ShapeKeySlice + '_ {
 impl PartialEq for dyn ShapeKeySlice + '_ {
   fn eq(&self, other: &Self) -> bool {
     self.face_ids() == other.face_ids()
+      && self.features() == other.features()
       && self.text() == other.text()
       && self.direction() == other.direction()
       && self.baseline() == other.baseline()
@@ -259,6 +441,8 @@ impl Eq for dyn ShapeKeySlice + '_ {}
 impl ShapeKeySlice for ShapeKey {
   fn face_ids(&self) -> &[ID] { &self.face_ids }
 
+  fn features(&self) -> &[(Tag, u32)] { &self.features }
+
   fn text(&self) -> &str { &self.text }
 
   fn direction(&self) -> TextDirection { self.direction }
@@ -266,14 +450,16 @@ impl ShapeKeySlice for ShapeKey {
   fn baseline(&self) -> GlyphBaseline { self.baseline }
 }
 
-impl ShapeKeySlice for (&[ID], &str, TextDirection, GlyphBaseline) {
+impl ShapeKeySlice for (&[ID], &[(Tag, u32)], &str, TextDirection, GlyphBaseline) {
   fn face_ids(&self) -> &[ID] { self.0 }
 
-  fn text(&self) -> &str { self.1 }
+  fn features(&self) -> &[(Tag, u32)] { self.1 }
 
-  fn direction(&self) -> TextDirection { self.2 }
+  fn text(&self) -> &str { self.2 }
 
-  fn baseline(&self) -> GlyphBaseline { self.3 }
+  fn direction(&self) -> TextDirection { self.3 }
+
+  fn baseline(&self) -> GlyphBaseline { self.4 }
 }
 
 impl From<TextDirection> for rustybuzz::Direction {
@@ -365,7 +551,7 @@ mod tests {
     // No cache exists
     assert!(
       shaper
-        .get_cache(&text, &ids, dir, baseline)
+        .get_cache(&text, &ids, &[], dir, baseline)
         .is_none()
     );
 
@@ -374,7 +560,7 @@ mod tests {
 
     assert!(
       shaper
-        .get_cache(&text, &ids, dir, baseline)
+        .get_cache(&text, &ids, &[], dir, baseline)
         .is_some()
     );
 
@@ -382,7 +568,7 @@ mod tests {
     shaper.end_frame();
     assert!(
       shaper
-        .get_cache(&text, &ids, dir, baseline)
+        .get_cache(&text, &ids, &[], dir, baseline)
         .is_none()
     );
   }
@@ -562,4 +748,141 @@ mod tests {
     );
     assert!(res.glyphs.len() == 7);
   }
+
+  fn roboto_shaper() -> (TextShaper, Vec<ID>) {
+    let shaper = TextShaper::new(<_>::default());
+    let path = env!("CARGO_MANIFEST_DIR").to_owned();
+    let _ = shaper
+      .font_db
+      .borrow_mut()
+      .load_font_file(path + "/../themes/material/src/fonts/Roboto-Regular.ttf");
+    let ids = shaper
+      .font_db
+      .borrow_mut()
+      .select_all_match(&FontFace {
+        families: Box::new([FontFamily::Name("Roboto".into())]),
+        ..<_>::default()
+      });
+    (shaper, ids)
+  }
+
+  #[test]
+  fn reshape_edited_word() {
+    let (mut shaper, ids) = roboto_shaper();
+    let dir = TextDirection::LeftToRight;
+    let baseline = GlyphBaseline::Alphabetic;
+
+    shaper.shape_text(&"hello brave world".into(), &ids, dir, baseline);
+    let stats = shaper.stats();
+    assert_eq!(stats.shaped_bytes, 17);
+
+    let edited: Substr = "hello bravo world".into();
+    let res = shaper.shape_text(&edited, &ids, dir, baseline);
+    let stats = shaper.stats();
+    // Only the edited word with the spaces around it is shaped, "hello" and
+    // "world" are reused.
+    assert_eq!(stats.shaped_bytes, 17 + 7);
+    assert_eq!(stats.reused_glyphs, 10);
+
+    let (mut fresh, fresh_ids) = roboto_shaper();
+    let expected = fresh.shape_text(&edited, &fresh_ids, dir, baseline);
+    let layout = |glyphs: &[Glyph]| -> Vec<_> {
+      glyphs
+        .iter()
+        .map(|g| (g.glyph_id, g.cluster, g.x_advance))
+        .collect()
+    };
+    assert_eq!(layout(&res.glyphs), layout(&expected.glyphs));
+
+    shaper.shape_text(&edited, &ids, dir, baseline);
+    assert_eq!(shaper.stats().cache_hits, 1);
+  }
+
+  #[test]
+  fn tabular_figures() {
+    let (mut shaper, ids) = roboto_shaper();
+    let text: Substr = "1234567890".into();
+    let mut digit_spread = |features: &[(Tag, u32)]| {
+      let res = shaper.shape_text_with_features(
+        &text,
+        &ids,
+        features,
+        TextDirection::LeftToRight,
+        GlyphBaseline::Alphabetic,
+      );
+      let advances = res.glyphs.iter().map(|g| g.x_advance);
+      advances.clone().max().unwrap() - advances.min().unwrap()
+    };
+
+    let pnum = Tag::from_bytes(b"pnum");
+    let tnum = Tag::from_bytes(b"tnum");
+    assert!(digit_spread(&[(pnum, 1)]) > GlyphUnit::STANDARD_EM / 10.);
+    // The tabular figures of Roboto differ by a fraction of a pixel at most.
+    assert!(digit_spread(&[(tnum, 1)]) < GlyphUnit::STANDARD_EM / 100.);
+  }
+
+  #[test]
+  fn disable_ligatures() {
+    let (mut shaper, ids) = roboto_shaper();
+    let text: Substr = "fi".into();
+    let dir = TextDirection::LeftToRight;
+    let baseline = GlyphBaseline::Alphabetic;
+
+    let res = shaper.shape_text(&text, &ids, dir, baseline);
+    assert_eq!(res.glyphs.len(), 1);
+
+    let liga = [(Tag::from_bytes(b"liga"), 0)];
+    let res = shaper.shape_text_with_features(&text, &ids, &liga, dir, baseline);
+    assert_eq!(res.glyphs.len(), 2);
+    assert!(
+      shaper
+        .get_cache(&text, &ids, &liga, dir, baseline)
+        .is_some()
+    );
+  }
+
+  #[test]
+  fn shape_uppercase_transform() {
+    let (mut shaper, ids) = roboto_shaper();
+    let dir = TextDirection::LeftToRight;
+    let baseline = GlyphBaseline::Alphabetic;
+    let mut glyph_ids = |text: &str, locale: Option<CaseLocale>| {
+      let text: Substr = text.to_string().into();
+      let res = match locale {
+        Some(locale) => {
+          let upper = TextTransform::Upper;
+          shaper.shape_text_with_transform(&text, upper, locale, &ids, &[], dir, baseline)
+        }
+        None => shaper.shape_text(&text, &ids, dir, baseline),
+      };
+      let glyph_ids: Vec<_> = res.glyphs.iter().map(|g| g.glyph_id).collect();
+      let clusters: Vec<_> = res.glyphs.iter().map(|g| g.cluster).collect();
+      (glyph_ids, clusters)
+    };
+
+    let (upper, clusters) = glyph_ids("straße in", Some(CaseLocale::Default));
+    assert_eq!(upper, glyph_ids("STRASSE IN", None).0);
+    // The clusters are the byte offsets of the original text, the `ß` of 2 bytes
+    // is shaped as two glyphs.
+    assert_eq!(clusters, [0, 1, 2, 3, 4, 4, 6, 7, 8, 9]);
+
+    // The Turkish uppercase of `i` is the dotted `İ`.
+    let (upper, _) = glyph_ids("in", Some(CaseLocale::Turkic));
+    assert_eq!(upper, glyph_ids("İN", None).0);
+  }
+
+  #[test]
+  fn capitalize_text() {
+    let (text, map) = TextTransform::Capitalize
+      .apply("hello wörld", CaseLocale::Default)
+      .unwrap();
+    assert_eq!(text, "Hello Wörld");
+    assert_eq!(map.len(), text.len());
+    let (text, _) = TextTransform::Lower
+      .apply("İI", CaseLocale::Turkic)
+      .unwrap();
+    assert_eq!(text, "iı");
+    let none = TextTransform::None.apply("hello", CaseLocale::Default);
+    assert!(none.is_none());
+  }
 }
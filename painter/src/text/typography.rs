@@ -1,12 +1,17 @@
 use std::ops::Range;
 
 use ribir_algo::Sc;
-use ribir_geom::Size;
+use ribir_geom::{Point, Rect, Size, Vector};
+use rustybuzz::ttf_parser::GlyphId;
 use smallvec::{SmallVec, smallvec};
 use unicode_script::{Script, UnicodeScript};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{Glyph, GlyphUnit, TextAlign, TextOverflow, shaper::ShapeResult};
+use crate::{
+  Color, Glyph, GlyphUnit, ID, TextAlign, TextOverflow,
+  font_db::FontDB,
+  shaper::{NEWLINE_GLYPH_ID, ShapeResult},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlaceLineDirection {
@@ -46,6 +51,21 @@ pub struct VisualLine {
   pub width: GlyphUnit,
   /// The glyph position is relative the line x/y
   pub glyphs: Vec<Glyph>,
+  /// The spans of the runs placed in the line, each is the range of its glyphs
+  /// in `glyphs` and the index of the run in all the input runs.
+  pub runs: Vec<(Range<usize>, usize)>,
+  /// The boxes reserved by the placeholder runs in the line, with the id of
+  /// their placeholders. The rectangle is relative the line x/y.
+  pub placeholders: Vec<(u64, Rect<GlyphUnit>)>,
+  /// If the line is in a right-to-left paragraph.
+  pub rtl: bool,
+  /// The maximum ascent of the fonts of the glyphs in the line.
+  pub ascent: GlyphUnit,
+  /// The maximum descent of the fonts of the glyphs in the line, it's positive
+  /// below the baseline.
+  pub descent: GlyphUnit,
+  /// The maximum line gap of the fonts of the glyphs in the line.
+  pub line_gap: GlyphUnit,
 }
 
 pub struct VisualInfos {
@@ -53,9 +73,40 @@ pub struct VisualInfos {
   pub text_align: TextAlign,
   /// if the typography result over the bounds provide by caller.
   pub over_bounds: bool,
+  /// if all the paragraphs start inside the bounds provide by caller. When it's
+  /// `false`, some paragraphs are invisible if the text is clipped by the
+  /// bounds.
+  pub fully_placed: bool,
   pub line_dir: PlaceLineDirection,
   pub visual_width: GlyphUnit,
   pub visual_height: GlyphUnit,
+  /// The background colors of the runs, and the text range of each run.
+  pub run_backgrounds: Vec<(Range<usize>, Color)>,
+  /// If the text is truncated because it needs more lines than the maximum
+  /// line count.
+  pub truncated: bool,
+  /// The em size of each input run, indexed by the run index.
+  pub run_ems: Vec<GlyphUnit>,
+}
+
+/// The vertical metrics of the fonts, see [`VisualInfos::line_metrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineMetrics {
+  pub ascent: GlyphUnit,
+  pub descent: GlyphUnit,
+  pub line_gap: GlyphUnit,
+}
+
+/// The contiguous glyphs of a line with the same face and font size, it's
+/// used to hand off the typography result to an external renderer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionedRun {
+  pub face_id: ID,
+  /// The em size of the glyphs.
+  pub font_size: GlyphUnit,
+  /// The glyphs with the top-left position of their boxes, relative to the
+  /// typography result.
+  pub glyphs: Vec<(GlyphId, Point<GlyphUnit>)>,
 }
 
 /// Typography the glyphs in a bounds.
@@ -72,6 +123,20 @@ pub struct TypographyMan<Paras> {
   inline_cursor: GlyphUnit,
   visual_lines: SmallVec<[VisualLine; 1]>,
   over_bounds: bool,
+  fully_placed: bool,
+  /// Only break the lines to measure the size, the glyphs are not kept.
+  measure_only: bool,
+  run_backgrounds: Vec<(Range<usize>, Color)>,
+  run_ems: Vec<GlyphUnit>,
+  /// The count of the runs consumed, used as the index of the next run.
+  run_count: usize,
+  /// How far the shifted glyphs of the current line extend before and after
+  /// the line in the cross direction.
+  line_overflow: (GlyphUnit, GlyphUnit),
+  max_lines: Option<usize>,
+  truncated: bool,
+  /// If the paragraph in placing is right-to-left.
+  para_rtl: bool,
 }
 
 impl<Paras> TypographyMan<Paras>
@@ -92,13 +157,43 @@ where
       inline_cursor: GlyphUnit::ZERO,
       visual_lines: smallvec![],
       over_bounds: false,
+      fully_placed: true,
+      measure_only: false,
+      run_backgrounds: vec![],
+      run_ems: vec![],
+      run_count: 0,
+      line_overflow: (GlyphUnit::ZERO, GlyphUnit::ZERO),
+      max_lines: None,
+      truncated: false,
+      para_rtl: false,
     }
   }
 
-  pub fn typography_all(mut self) -> VisualInfos {
+  /// Limit the count of the visual lines, the typography stops after placing
+  /// `max_lines` lines and the rest of the text is truncated. It's different
+  /// from the bounds, the lines over the bounds are still placed.
+  pub fn with_max_lines(mut self, max_lines: Option<usize>) -> Self {
+    self.max_lines = max_lines;
+    self
+  }
+
+  /// Measure the visual size of the inputs, the lines are broken the same as
+  /// `typography_all`, but the glyphs are not placed into the lines.
+  pub fn measure_all(mut self) -> Size<GlyphUnit> {
+    self.measure_only = true;
     while let Some(p) = self.inputs.next() {
       self.consume_paragraph(p);
     }
+    let (width, height) = self.visual_size();
+    Size::new(width, height)
+  }
+
+  pub fn typography_all(mut self) -> VisualInfos {
+    while let Some(p) = self.inputs.next() {
+      if self.consume_paragraph(p) {
+        self.fully_placed = false;
+      }
+    }
 
     if self.line_dir.is_reverse() {
       self.visual_lines.reverse();
@@ -113,7 +208,11 @@ where
       text_align: self.text_align,
       visual_lines: self.visual_lines,
       over_bounds: self.over_bounds,
+      fully_placed: self.fully_placed,
       line_dir: self.line_dir,
+      run_backgrounds: self.run_backgrounds,
+      truncated: self.truncated,
+      run_ems: self.run_ems,
     }
   }
 
@@ -151,6 +250,7 @@ where
       if self.line_dir.is_horizontal() {
         l.y += text_align_offset(l.height, visual_height, text_align);
       } else {
+        let text_align = physical_align(text_align, l.rtl);
         l.x += text_align_offset(l.width, visual_width, text_align);
       }
     });
@@ -174,8 +274,17 @@ where
     (width, height)
   }
 
-  /// consume paragraph and return if early break because over boundary.
+  /// consume paragraph and return if the paragraph starts out of the bounds.
   fn consume_paragraph(&mut self, runs: SmallVec<[InputRun; 1]>) -> bool {
+    if !self.can_place_line() {
+      return true;
+    }
+    let out_of_bounds = if self.line_dir.is_horizontal() {
+      self.bounds.width <= self.lines_extent()
+    } else {
+      self.bounds.height <= self.lines_extent()
+    };
+    self.para_rtl = runs.first().is_some_and(|r| r.rtl_para);
     self.begin_line();
 
     if self.line_dir.is_horizontal() {
@@ -191,7 +300,7 @@ where
     }
     self.end_line();
 
-    false
+    out_of_bounds
   }
 
   fn consume_run_with_letter_space_cursor(
@@ -206,19 +315,45 @@ where
   }
 
   fn consume_run(&mut self, run: &InputRun, cursor: &mut impl InlineCursor) {
+    if self.truncated {
+      return;
+    }
     let font_size = run.font_size_factor * GlyphUnit::PIXELS_PER_EM as f32;
     let em = GlyphUnit::from_pixel(font_size);
+    let metrics = run.metrics.cast_to(font_size);
     let text = run.text();
     let base = run.range.start as u32;
     let line_offset = (self.line_height - em) / 2.;
+    // The shift of the glyphs in the cross direction, the superscript is raised
+    // above the baseline in a horizontal line, and moved to the right in a
+    // vertical line.
+    let shift = GlyphUnit::from_pixel(font_size * run.baseline_shift);
+    let shift = if self.line_dir.is_horizontal() { shift } else { -shift };
     let is_auto_wrap = self.overflow.is_auto_wrap();
+    let run_index = self.run_count;
+    self.run_count += 1;
+    self.run_ems.push(em);
+    if let Some(color) = run.background {
+      self
+        .run_backgrounds
+        .push((run.range.clone(), color));
+    }
 
     let new_line = |this: &mut Self, cursor: &mut dyn InlineCursor| {
+      if !this.can_place_line() {
+        return false;
+      }
       this.end_line();
       this.begin_line();
       cursor.reset();
+      true
     };
 
+    if let Some((id, size)) = run.placeholder {
+      self.consume_placeholder(id, size, is_auto_wrap, cursor);
+      return;
+    }
+
     for word in run.word_glyphs() {
       let width: GlyphUnit = word
         .clone()
@@ -227,15 +362,16 @@ where
       if is_auto_wrap
         && self.inline_cursor != GlyphUnit::ZERO
         && self.is_over_line_bound(width + self.inline_cursor)
+        && !new_line(self, cursor)
       {
-        new_line(self, cursor);
+        return;
       }
 
       let mut word = word.peekable();
       while let Some(g) = word.peek() {
         let mut at = (*g).clone();
 
-        cursor.advance_glyph(&mut at, line_offset, text);
+        cursor.advance_glyph(&mut at, line_offset + shift, text);
 
         at.cluster += base;
 
@@ -243,23 +379,97 @@ where
           || !is_auto_wrap
           || !self.is_over_line_bound(cursor.position())
         {
-          self.push_glyph(at);
+          if shift != GlyphUnit::ZERO {
+            self.extend_line_overflow(line_offset, shift, em);
+          }
+          self.push_glyph(at, run_index, metrics);
           self.inline_cursor = cursor.position();
           word.next();
-        } else {
-          new_line(self, cursor);
+        } else if !new_line(self, cursor) {
+          return;
         }
       }
     }
   }
 
-  fn push_glyph(&mut self, g: Glyph) {
-    let line = self.visual_lines.last_mut();
-    line.unwrap().glyphs.push(g)
+  /// Return if a new line can be placed, the typography is truncated if the
+  /// lines already reach the maximum line count.
+  fn can_place_line(&mut self) -> bool {
+    if self
+      .max_lines
+      .is_some_and(|max| self.visual_lines.len() >= max)
+    {
+      self.truncated = true;
+    }
+    !self.truncated
+  }
+
+  /// Reserve a box of the `size` in the line, it's placed at the start of the
+  /// line in the cross direction, and the line grows to contain it.
+  fn consume_placeholder(
+    &mut self, id: u64, size: Size<GlyphUnit>, is_auto_wrap: bool, cursor: &mut impl InlineCursor,
+  ) {
+    let hor_line = !self.line_dir.is_horizontal();
+    let (inline, cross) =
+      if hor_line { (size.width, size.height) } else { (size.height, size.width) };
+    if is_auto_wrap
+      && self.inline_cursor != GlyphUnit::ZERO
+      && self.is_over_line_bound(self.inline_cursor + inline)
+    {
+      if !self.can_place_line() {
+        return;
+      }
+      self.end_line();
+      self.begin_line();
+      cursor.reset();
+    }
+
+    let start = cursor.position();
+    cursor.advance(inline);
+    self.inline_cursor = cursor.position();
+    let after = &mut self.line_overflow.1;
+    *after = (*after).max(cross - self.line_height);
+    if !self.measure_only {
+      let origin = if hor_line {
+        Point::new(start, GlyphUnit::ZERO)
+      } else {
+        Point::new(GlyphUnit::ZERO, start)
+      };
+      let line = self.visual_lines.last_mut().unwrap();
+      line
+        .placeholders
+        .push((id, Rect::new(origin, size)));
+    }
+  }
+
+  /// Extend the current line to contain a glyph shifted by `shift` in the
+  /// cross direction, the extent of the glyph without shift is not counted.
+  fn extend_line_overflow(&mut self, line_offset: GlyphUnit, shift: GlyphUnit, em: GlyphUnit) {
+    let start = line_offset + shift;
+    let end = start + em;
+    let (before, after) = &mut self.line_overflow;
+    *before = (*before).max(line_offset.min(GlyphUnit::ZERO) - start);
+    *after = (*after).max(end - self.line_height.max(line_offset + em));
+  }
+
+  fn push_glyph(&mut self, g: Glyph, run_index: usize, metrics: LineMetrics) {
+    if self.measure_only {
+      return;
+    }
+    let line = self.visual_lines.last_mut().unwrap();
+    line.ascent = line.ascent.max(metrics.ascent);
+    line.descent = line.descent.max(metrics.descent);
+    line.line_gap = line.line_gap.max(metrics.line_gap);
+    let idx = line.glyphs.len();
+    match line.runs.last_mut() {
+      Some((rg, run)) if *run == run_index => rg.end = idx + 1,
+      _ => line.runs.push((idx..idx + 1, run_index)),
+    }
+    line.glyphs.push(g)
   }
 
   fn begin_line(&mut self) {
-    let mut line = VisualLine::default();
+    let mut line = VisualLine { rtl: self.para_rtl, ..<_>::default() };
     if self.line_dir.is_horizontal() {
       line.width = self.line_height;
     } else {
@@ -270,11 +480,26 @@ where
 
   fn end_line(&mut self) {
     let line = self.visual_lines.last_mut().unwrap();
+    let (before, after) = std::mem::take(&mut self.line_overflow);
     // we will reorder the line after consumed all inputs.
     if self.line_dir.is_horizontal() {
       line.height = self.inline_cursor;
+      line.width += before + after;
+      for g in line.glyphs.iter_mut() {
+        g.x_offset += before;
+      }
+      for (_, rect) in line.placeholders.iter_mut() {
+        rect.origin.x += before;
+      }
     } else {
       line.width = self.inline_cursor;
+      line.height += before + after;
+      for g in line.glyphs.iter_mut() {
+        g.y_offset += before;
+      }
+      for (_, rect) in line.placeholders.iter_mut() {
+        rect.origin.y += before;
+      }
     }
     self.over_bounds |= self.is_over_line_bound(self.inline_cursor);
     self.over_bounds |= self.is_last_line_over();
@@ -295,17 +520,19 @@ where
 
   fn is_last_line_over(&self) -> bool {
     if self.line_dir.is_horizontal() {
-      self.bounds.width
-        < self
-          .visual_lines
-          .iter()
-          .fold(GlyphUnit::ZERO, |acc, l| acc + l.width)
+      self.bounds.width < self.lines_extent()
     } else {
-      self.bounds.height
-        < self
-          .visual_lines
-          .iter()
-          .fold(GlyphUnit::ZERO, |acc, l| acc + l.height)
+      self.bounds.height < self.lines_extent()
+    }
+  }
+
+  /// The extent of all the placed lines along the direction of placing lines.
+  fn lines_extent(&self) -> GlyphUnit {
+    let lines = self.visual_lines.iter();
+    if self.line_dir.is_horizontal() {
+      lines.fold(GlyphUnit::ZERO, |acc, l| acc + l.width)
+    } else {
+      lines.fold(GlyphUnit::ZERO, |acc, l| acc + l.height)
     }
   }
 }
@@ -316,6 +543,20 @@ pub struct InputRun {
   pub(crate) font_size_factor: f32,
   pub(crate) letter_space: GlyphUnit,
   pub(crate) range: Range<usize>,
+  /// The background color to highlight the glyphs of the run.
+  pub(crate) background: Option<Color>,
+  /// The shift of the baseline in em of the run, a positive value raises the
+  /// glyphs above the baseline, such as a superscript, and a negative value
+  /// lowers them, such as a subscript.
+  pub(crate) baseline_shift: f32,
+  /// The id and the size of the box reserved by a placeholder run, it has no
+  /// glyph.
+  pub(crate) placeholder: Option<(u64, Size<GlyphUnit>)>,
+  /// If the run is in a right-to-left paragraph, the lines of the paragraph
+  /// are aligned by it.
+  pub(crate) rtl_para: bool,
+  /// The vertical metrics of the fonts of the run in the standard em.
+  pub(crate) metrics: LineMetrics,
   reorder_text: String,
 }
 
@@ -358,7 +599,8 @@ impl InlineCursor for VInlineCursor {
   fn advance_glyph(&mut self, g: &mut Glyph, line_offset: GlyphUnit, _: &str) {
     g.x_offset += line_offset;
     g.y_offset += self.pos;
-    self.pos = g.y_offset + g.y_advance;
+    // The offset of a vertical glyph is from its origin, it never moves the cursor.
+    self.pos += g.y_advance;
   }
 
   fn advance(&mut self, c: GlyphUnit) { self.pos += c; }
@@ -373,6 +615,7 @@ impl InlineCursor for VInlineCursor {
 impl<'a, I: InlineCursor> InlineCursor for LetterSpaceCursor<'a, I> {
   fn advance_glyph(&mut self, g: &mut Glyph, line_offset: GlyphUnit, origin_text: &str) {
     let cursor = &mut self.inner_cursor;
+    let start = cursor.position();
     cursor.advance_glyph(g, line_offset, origin_text);
 
     let c = origin_text[g.cluster as usize..]
@@ -380,7 +623,10 @@ impl<'a, I: InlineCursor> InlineCursor for LetterSpaceCursor<'a, I> {
       .next()
       .unwrap();
     if letter_spacing_char(c) {
-      cursor.advance(self.letter_space);
+      // A negative letter space can't move the cursor before the start of this
+      // glyph, so the next glyph never be placed before it.
+      let space = self.letter_space.max(start - cursor.position());
+      cursor.advance(space);
     }
   }
 
@@ -392,7 +638,7 @@ impl<'a, I: InlineCursor> InlineCursor for LetterSpaceCursor<'a, I> {
       .next()
       .unwrap();
     if letter_spacing_char(c) {
-      advance += self.letter_space;
+      advance = (advance + self.letter_space).max(GlyphUnit::ZERO);
     }
 
     advance
@@ -405,6 +651,24 @@ impl<'a, I: InlineCursor> InlineCursor for LetterSpaceCursor<'a, I> {
   fn reset(&mut self) { self.inner_cursor.reset(); }
 }
 
+impl LineMetrics {
+  fn max(self, other: Self) -> Self {
+    LineMetrics {
+      ascent: self.ascent.max(other.ascent),
+      descent: self.descent.max(other.descent),
+      line_gap: self.line_gap.max(other.line_gap),
+    }
+  }
+
+  fn cast_to(self, pixel_per_em: f32) -> Self {
+    LineMetrics {
+      ascent: self.ascent.cast_to(pixel_per_em),
+      descent: self.descent.cast_to(pixel_per_em),
+      line_gap: self.line_gap.cast_to(pixel_per_em),
+    }
+  }
+}
+
 impl PlaceLineDirection {
   pub fn is_horizontal(&self) -> bool {
     matches!(self, PlaceLineDirection::LeftToRight | PlaceLineDirection::RightToLeft)
@@ -415,11 +679,203 @@ impl PlaceLineDirection {
   }
 }
 
+impl VisualInfos {
+  /// Return the rectangles to paint the background colors of the runs.
+  ///
+  /// The contiguous glyphs with the same background color in a line are merged
+  /// into one rectangle, which spans the whole line in the cross direction.
+  pub fn background_rects(&self) -> Vec<(Color, Rect<GlyphUnit>)> {
+    let mut rects = vec![];
+    if self.run_backgrounds.is_empty() {
+      return rects;
+    }
+
+    let hor_line = !self.line_dir.is_horizontal();
+    let background_of = |cluster: u32| {
+      self
+        .run_backgrounds
+        .iter()
+        .find(|(rg, _)| rg.contains(&(cluster as usize)))
+        .map(|(_, color)| *color)
+    };
+    for line in &self.visual_lines {
+      let line_rect = |start: GlyphUnit, end: GlyphUnit| {
+        if hor_line {
+          Rect::new(Point::new(line.x + start, line.y), Size::new(end - start, line.height))
+        } else {
+          Rect::new(Point::new(line.x, line.y + start), Size::new(line.width, end - start))
+        }
+      };
+
+      let mut merging: Option<(Color, GlyphUnit, GlyphUnit)> = None;
+      for g in &line.glyphs {
+        let (start, end) = if hor_line {
+          (g.x_offset, g.x_offset + g.x_advance)
+        } else {
+          (g.y_offset, g.y_offset + g.y_advance)
+        };
+        let color = background_of(g.cluster);
+        match (&mut merging, color) {
+          (Some((c, _, e)), Some(color)) if *c == color => *e = end,
+          _ => {
+            if let Some((c, s, e)) = merging.take() {
+              rects.push((c, line_rect(s, e)));
+            }
+            merging = color.map(|c| (c, start, end));
+          }
+        }
+      }
+      if let Some((c, s, e)) = merging {
+        rects.push((c, line_rect(s, e)));
+      }
+    }
+    rects
+  }
+
+  /// Return the index of the visual line that contains the `cluster`, the
+  /// clusters past the end of the text belong to the last line in the logical
+  /// order. Return `None` if there is no line.
+  ///
+  /// The empty lines have no cluster, so a cluster never belongs to them unless
+  /// no line before it has any cluster.
+  pub fn line_of_cluster(&self, cluster: u32) -> Option<usize> {
+    let lines = &self.visual_lines;
+    // The lines are placed in the logical order, or in the reverse order if the
+    // lines are placed reversely.
+    let visual_idx = |i: usize| if self.line_dir.is_reverse() { lines.len() - 1 - i } else { i };
+    let starts_before = |i: &usize| {
+      lines[*i]
+        .glyphs
+        .iter()
+        .map(|g| g.cluster)
+        .min()
+        .is_some_and(|start| start <= cluster)
+    };
+    (0..lines.len())
+      .map(visual_idx)
+      .rfind(starts_before)
+      .or_else(|| (!lines.is_empty()).then(|| visual_idx(0)))
+  }
+
+  /// Return the rectangles to clip the text to reveal its first `n_clusters`
+  /// clusters, such as for a typewriter effect. The clusters are counted in the
+  /// logical order, so the rectangle of a right-to-left line grows from the
+  /// right.
+  ///
+  /// Every line with a revealed cluster has a rectangle, from the first to the
+  /// last revealed glyph in it, so the fully revealed lines are covered by
+  /// their glyphs and the line of the last revealed cluster is partially
+  /// covered. The line breaks are invisible, so the empty lines have nothing to
+  /// reveal and no rectangle.
+  pub fn reveal_clip_at(&self, n_clusters: usize) -> Vec<Rect<GlyphUnit>> {
+    let mut clusters: Vec<u32> = self
+      .visual_lines
+      .iter()
+      .flat_map(|l| l.glyphs.iter().map(|g| g.cluster))
+      .collect();
+    clusters.sort_unstable();
+    clusters.dedup();
+    let last = n_clusters
+      .checked_sub(1)
+      .and_then(|i| clusters.get(i).or(clusters.last()));
+    let Some(&last) = last else { return vec![] };
+
+    let hor_line = !self.line_dir.is_horizontal();
+    self
+      .visual_lines
+      .iter()
+      .filter_map(|line| {
+        let (start, end) = line
+          .glyphs
+          .iter()
+          .filter(|g| g.cluster <= last && g.glyph_id != NEWLINE_GLYPH_ID)
+          .map(|g| {
+            if hor_line {
+              (g.x_offset, g.x_offset + g.x_advance)
+            } else {
+              (g.y_offset, g.y_offset + g.y_advance)
+            }
+          })
+          .reduce(|(s, e), (g_s, g_e)| (s.min(g_s), e.max(g_e)))?;
+        let rect = if hor_line {
+          Rect::new(Point::new(line.x + start, line.y), Size::new(end - start, line.height))
+        } else {
+          Rect::new(Point::new(line.x, line.y + start), Size::new(line.width, end - start))
+        };
+        Some(rect)
+      })
+      .collect()
+  }
+
+  /// Return the rectangles of the boxes reserved by the placeholder runs, with
+  /// the id of their placeholders.
+  pub fn placeholder_rects(&self) -> impl Iterator<Item = (u64, Rect<GlyphUnit>)> + '_ {
+    self.visual_lines.iter().flat_map(|l| {
+      l.placeholders
+        .iter()
+        .map(move |(id, rect)| (*id, rect.translate(Vector::new(l.x, l.y))))
+    })
+  }
+
+  /// Return the glyphs grouped by the contiguous runs of the same face and
+  /// font size in every line, with their positions relative to the typography
+  /// result. The runs are in the order of the lines, and the glyphs of a run
+  /// are in the visual order.
+  pub fn to_positioned_runs(&self) -> Vec<PositionedRun> {
+    let mut runs: Vec<PositionedRun> = vec![];
+    for line in &self.visual_lines {
+      let line_start = runs.len();
+      for (idx, g) in line.glyphs.iter().enumerate() {
+        let font_size = line
+          .run_of(idx)
+          .map_or(GlyphUnit::STANDARD_EM, |run| self.run_ems[run]);
+        let pos = Point::new(line.x + g.x_offset, line.y + g.y_offset);
+        match runs[line_start..].last_mut() {
+          Some(run) if run.face_id == g.face_id && run.font_size == font_size => {
+            run.glyphs.push((g.glyph_id, pos))
+          }
+          _ => runs.push(PositionedRun {
+            face_id: g.face_id,
+            font_size,
+            glyphs: vec![(g.glyph_id, pos)],
+          }),
+        }
+      }
+    }
+    runs
+  }
+
+  /// Return the vertical metrics of the visual line at the `index`, they are
+  /// the maximum of the fonts of its glyphs, so a caller can align the content
+  /// beside the text to its baseline.
+  pub fn line_metrics(&self, index: usize) -> Option<LineMetrics> {
+    let l = self.visual_lines.get(index)?;
+    Some(LineMetrics { ascent: l.ascent, descent: l.descent, line_gap: l.line_gap })
+  }
+
+  /// Return the rectangle of the visual line at the `index`.
+  pub fn line_rect(&self, index: usize) -> Option<Rect<GlyphUnit>> {
+    self
+      .visual_lines
+      .get(index)
+      .map(|l| Rect::new(Point::new(l.x, l.y), Size::new(l.width, l.height)))
+  }
+}
+
 impl VisualLine {
   pub fn line_height(&self, line_dir: PlaceLineDirection) -> GlyphUnit {
     if line_dir.is_horizontal() { self.width } else { self.height }
   }
 
+  /// Return the index of the run that placed the glyph at `glyph_idx`.
+  pub fn run_of(&self, glyph_idx: usize) -> Option<usize> {
+    self
+      .runs
+      .iter()
+      .find(|(rg, _)| rg.contains(&glyph_idx))
+      .map(|(_, run)| *run)
+  }
+
   pub fn glyphs_iter(&self, hor_line: bool) -> impl DoubleEndedIterator<Item = Glyph> + '_ {
     self.glyphs.iter().map(move |g| {
       let mut g = g.clone();
@@ -445,6 +901,16 @@ pub(crate) fn text_align_offset(
   }
 }
 
+/// Resolve the text align of a horizontal line to the physical align, that the
+/// start is the left.
+pub(crate) fn physical_align(text_align: TextAlign, rtl: bool) -> TextAlign {
+  match text_align {
+    TextAlign::Start if rtl => TextAlign::End,
+    TextAlign::End if rtl => TextAlign::Start,
+    align => align,
+  }
+}
+
 /// Check if a char support apply letter spacing.
 fn letter_spacing_char(c: char) -> bool {
   let script = c.script();
@@ -484,7 +950,76 @@ impl InputRun {
       .iter()
       .filter_map(|gh| text[gh.cluster as usize..].chars().next())
       .collect();
-    Self { shape_result, font_size_factor, letter_space, range, reorder_text }
+    Self {
+      shape_result,
+      font_size_factor,
+      letter_space,
+      range,
+      background: None,
+      baseline_shift: 0.,
+      placeholder: None,
+      rtl_para: false,
+      metrics: LineMetrics::default(),
+      reorder_text,
+    }
+  }
+
+  /// Create a run that reserves a box of the `size` in the text flow, such as
+  /// for an inline widget. The following text is placed after the box, and
+  /// the rectangle of the box is reported by `VisualInfos::placeholder_rects`
+  /// with the `id`.
+  ///
+  /// The `range` is the range of the text replaced by the placeholder, usually
+  /// an object replacement character.
+  pub fn placeholder(id: u64, size: Size<GlyphUnit>, range: Range<usize>) -> Self {
+    let shape_result = Sc::new(ShapeResult { text: "".into(), glyphs: vec![] });
+    let mut run = Self::new(shape_result, 1., GlyphUnit::ZERO, range);
+    run.placeholder = Some((id, size));
+    run
+  }
+
+  /// Highlight the glyphs of the run with the `background` color, the
+  /// rectangles to paint are reported by `VisualInfos::background_rects`.
+  pub fn with_background(mut self, background: Option<Color>) -> Self {
+    self.background = background;
+    self
+  }
+
+  /// Shift the baseline of the run by `em`, a positive value raises the glyphs
+  /// like a superscript, and a negative value lowers them like a subscript.
+  /// The line grows to contain the shifted glyphs.
+  pub fn with_baseline_shift(mut self, em: f32) -> Self {
+    self.baseline_shift = em;
+    self
+  }
+
+  /// Measure the vertical metrics of the run by the faces of its glyphs.
+  pub(crate) fn with_font_metrics(mut self, font_db: &FontDB) -> Self {
+    let mut faces: SmallVec<[ID; 1]> = smallvec![];
+    // The line break is shaped by any fallback face, it's invisible and not
+    // measured.
+    for g in self.shape_result.glyphs.iter() {
+      if g.glyph_id != NEWLINE_GLYPH_ID && !faces.contains(&g.face_id) {
+        faces.push(g.face_id);
+      }
+    }
+    for id in faces {
+      let Some(face) = font_db.try_get_face_data(id) else { continue };
+      let scale = GlyphUnit::UNITS_PER_EM as f32 / face.units_per_em() as f32;
+      let unit = |v: i16| GlyphUnit::new((v as f32 * scale).ceil() as i32);
+      let metrics = LineMetrics {
+        ascent: unit(face.ascender()),
+        descent: unit(-face.descender()),
+        line_gap: unit(face.line_gap()),
+      };
+      self.metrics = self.metrics.max(metrics);
+    }
+    self
+  }
+
+  pub(crate) fn with_rtl_para(mut self, rtl: bool) -> Self {
+    self.rtl_para = rtl;
+    self
   }
 
   #[inline]
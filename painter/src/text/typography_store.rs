@@ -2,10 +2,14 @@ use std::{cell::RefCell, ops::Range};
 
 use font_db::GlyphBaseline;
 use ribir_algo::{FrameCache, Sc, Substr};
-use ribir_geom::{Point, Rect, Size};
+use ribir_geom::{Point, Rect, Size, Transform};
+use smallvec::SmallVec;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
+  FillRule, PixelImage,
   font_db::FontDB,
+  rasterizer::AlphaRasterizer,
   shaper::{NEWLINE_GLYPH_ID, TextShaper},
   text_reorder::ReorderResult,
   typography::*,
@@ -17,6 +21,9 @@ struct RunKey {
   pub ids: Box<[ID]>,
   pub line_height: GlyphUnit,
   pub letter_space: GlyphUnit,
+  pub features: Box<[(Tag, u32)]>,
+  pub transform: TextTransform,
+  pub locale: CaseLocale,
   pub text: Substr,
 }
 
@@ -44,6 +51,7 @@ pub struct TypographyStore {
 #[derive(Clone)]
 pub struct VisualGlyphs {
   font_size: f32,
+  palette_index: u16,
   x: GlyphUnit,
   y: GlyphUnit,
   visual_info: Sc<VisualInfos>,
@@ -52,15 +60,20 @@ pub struct VisualGlyphs {
 
 impl VisualGlyphs {
   pub fn new(
-    font_size: f32, line_dir: PlaceLineDirection, order_info: Sc<ReorderResult>,
-    bound_width: GlyphUnit, bound_height: GlyphUnit, visual_info: Sc<VisualInfos>,
+    font_size: f32, palette_index: u16, line_dir: PlaceLineDirection,
+    order_info: Sc<ReorderResult>, bound_width: GlyphUnit, bound_height: GlyphUnit,
+    visual_info: Sc<VisualInfos>,
   ) -> Self {
     let (mut x, mut y) = <_>::default();
 
     if line_dir.is_horizontal() {
       y += text_align_offset(visual_info.visual_height, bound_height, visual_info.text_align);
     } else {
-      x += text_align_offset(visual_info.visual_width, bound_width, visual_info.text_align);
+      // The lines are aligned as right-to-left only if all of them are.
+      let lines = &visual_info.visual_lines;
+      let rtl = !lines.is_empty() && lines.iter().all(|l| l.rtl);
+      let text_align = physical_align(visual_info.text_align, rtl);
+      x += text_align_offset(visual_info.visual_width, bound_width, text_align);
     }
 
     if line_dir == PlaceLineDirection::RightToLeft {
@@ -69,10 +82,14 @@ impl VisualGlyphs {
     if line_dir == PlaceLineDirection::BottomToTop {
       y += bound_height - visual_info.visual_height
     }
-    Self { font_size, x, y, visual_info, order_info }
+    Self { font_size, palette_index, x, y, visual_info, order_info }
   }
 
   pub fn font_size(&self) -> f32 { self.font_size }
+
+  /// The CPAL palette to color the COLR glyphs, see
+  /// [`TextStyle::palette_index`].
+  pub fn palette_index(&self) -> u16 { self.palette_index }
 }
 
 impl TypographyStore {
@@ -93,7 +110,63 @@ impl TypographyStore {
     &mut self, text: Substr, style: &TextStyle, bounds: Size, text_align: TextAlign,
     baseline: GlyphBaseline, line_dir: PlaceLineDirection,
   ) -> VisualGlyphs {
-    let TextStyle { font_size, ref font_face, letter_space, line_height, overflow } = *style;
+    let info = self.reorder.reorder_text(&text).clone();
+    let (key, bounds) = self.typography_key(text, style, bounds, text_align, baseline, line_dir);
+    let infos = if let Some(infos) = self.cache.get(&key).cloned() {
+      infos
+    } else {
+      let visual_info = self
+        .typography_man(&key, &info, bounds)
+        .typography_all();
+      let infos = Sc::new(visual_info);
+      self.cache.put(key, infos.clone());
+      infos
+    };
+
+    let TextStyle { font_size, palette_index, .. } = *style;
+    VisualGlyphs::new(font_size, palette_index, line_dir, info, bounds.width, bounds.height, infos)
+  }
+
+  /// Measure the visual size of the text in pixel, it breaks the lines the same
+  /// as `typography` but not places the glyphs, so it's cheaper if only the
+  /// size is needed.
+  pub fn measure(
+    &mut self, text: Substr, style: &TextStyle, bounds: Size, text_align: TextAlign,
+    baseline: GlyphBaseline, line_dir: PlaceLineDirection,
+  ) -> Size {
+    let info = self.reorder.reorder_text(&text).clone();
+    let (key, bounds) = self.typography_key(text, style, bounds, text_align, baseline, line_dir);
+    let cached = self
+      .cache
+      .get(&key)
+      .map(|infos| Size::new(infos.visual_width, infos.visual_height));
+    let size = cached.unwrap_or_else(|| {
+      self
+        .typography_man(&key, &info, bounds)
+        .measure_all()
+    });
+
+    let to_pixel = |v: GlyphUnit| v.cast_to(style.font_size).into_pixel();
+    Size::new(to_pixel(size.width), to_pixel(size.height))
+  }
+
+  pub fn font_db(&self) -> &Sc<RefCell<FontDB>> { &self.font_db }
+
+  fn typography_key(
+    &mut self, text: Substr, style: &TextStyle, bounds: Size, text_align: TextAlign,
+    baseline: GlyphBaseline, line_dir: PlaceLineDirection,
+  ) -> (TypographyKey, Size<GlyphUnit>) {
+    let TextStyle {
+      font_size,
+      ref font_face,
+      letter_space,
+      line_height,
+      overflow,
+      ref font_features,
+      palette_index: _,
+      text_transform: transform,
+      case_locale: locale,
+    } = *style;
     // Since we cache the result of the standard font size, we must ensure that all
     // variables are cast relative to this standard font size.
     let scale = font_size / GlyphUnit::PIXELS_PER_EM as f32;
@@ -103,54 +176,90 @@ impl TypographyStore {
     );
     let letter_space =
       GlyphUnit::from_pixel(letter_space / font_size * GlyphUnit::PIXELS_PER_EM as f32);
-    let line_height =
-      GlyphUnit::from_pixel(line_height / font_size * GlyphUnit::PIXELS_PER_EM as f32);
+    let line_height = GlyphUnit::from_pixel(
+      line_height.to_pixel(font_size) / font_size * GlyphUnit::PIXELS_PER_EM as f32,
+    );
 
-    let info = self.reorder.reorder_text(&text).clone();
     let ids = self
       .font_db
       .borrow_mut()
       .select_all_match(font_face)
       .into_boxed_slice();
-    let runs = [RunKey { ids, line_height, letter_space, text }].into();
+    let features = font_features.clone().into_boxed_slice();
+    let runs =
+      [RunKey { ids, line_height, letter_space, features, transform, locale, text }].into();
     let key = TypographyKey::new(runs, bounds, text_align, line_dir, overflow, baseline);
-    let infos = if let Some(infos) = self.cache.get(&key).cloned() {
-      infos
-    } else {
-      let ids = &key.runs[0].ids;
-      let text = &key.runs[0].text;
-      let inputs = info.paras.iter().map(|p| {
-        p.runs
-          .iter()
-          .map(|r| {
-            let dir = if r.is_empty() || p.levels[r.start].is_ltr() {
-              TextDirection::LeftToRight
-            } else {
-              TextDirection::RightToLeft
-            };
-
-            let shape_result = self
-              .shaper
-              .shape_text(&text.substr(r.clone()), ids, dir, baseline);
-            InputRun::new(shape_result, 1., letter_space, r.clone())
-          })
-          .collect()
-      });
+    (key, bounds)
+  }
 
-      let t_man = TypographyMan::new(inputs, line_dir, text_align, line_height, bounds, overflow);
-      let visual_info = t_man.typography_all();
-      let infos = Sc::new(visual_info);
-      self.cache.put(key, infos.clone());
-      infos
-    };
+  fn typography_man<'a>(
+    &'a mut self, key: &'a TypographyKey, info: &'a ReorderResult, bounds: Size<GlyphUnit>,
+  ) -> TypographyMan<impl DoubleEndedIterator<Item = SmallVec<[InputRun; 1]>> + 'a> {
+    let RunKey { ids, line_height, letter_space, features, transform, locale, text } = &key.runs[0];
+    let vertical = key.line_dir.is_horizontal();
+    let inputs = info.paras.iter().map(move |p| {
+      p.runs
+        .iter()
+        .map(|r| {
+          // The lines placed horizontally hold the text set vertically.
+          let dir = if vertical {
+            TextDirection::TopToBottom
+          } else if r.is_empty() || p.levels[r.start].is_ltr() {
+            TextDirection::LeftToRight
+          } else {
+            TextDirection::RightToLeft
+          };
 
-    VisualGlyphs::new(font_size, line_dir, info, bounds.width, bounds.height, infos.clone())
-  }
+          let shape_result = self.shaper.shape_text_with_transform(
+            &text.substr(r.clone()),
+            *transform,
+            *locale,
+            ids,
+            features,
+            dir,
+            key.baseline,
+          );
+          InputRun::new(shape_result, 1., *letter_space, r.clone())
+            .with_rtl_para(p.level.is_rtl())
+            .with_font_metrics(&self.font_db.borrow())
+        })
+        .collect()
+    });
 
-  pub fn font_db(&self) -> &Sc<RefCell<FontDB>> { &self.font_db }
+    let TypographyKey { line_dir, text_align, overflow, .. } = *key;
+    TypographyMan::new(inputs, line_dir, text_align, *line_height, bounds, overflow)
+  }
 }
 
 impl VisualGlyphs {
+  /// Rasterize the outlines of the glyphs to an alpha image on CPU without a
+  /// window, the `scale` is the ratio of the image pixel to the logic pixel.
+  ///
+  /// The glyphs without an outline, such as the bitmap or SVG glyphs, are not
+  /// rasterized.
+  pub fn rasterize(&self, font_db: &FontDB, scale: f32) -> PixelImage {
+    let size = (self.visual_rect().size * scale)
+      .ceil()
+      .to_i32()
+      .cast_unit();
+    let mut raster = AlphaRasterizer::new(size);
+    for g in self.glyphs() {
+      let Some(face) = font_db.try_get_face_data(g.face_id) else { continue };
+      let Some(path) = face.outline_glyph(g.glyph_id) else { continue };
+
+      // The same transform as the painter draws the glyph.
+      let unit = face.units_per_em() as f32;
+      let font_scale = self.font_size / unit;
+      let bounds = g.bounds();
+      let ts = Transform::translation(0., -unit)
+        .then_scale(font_scale, -font_scale)
+        .then_translate(bounds.origin.to_vector())
+        .then_scale(scale, scale);
+      raster.add_path(&path, &ts);
+    }
+    raster.rasterize(FillRule::NonZero)
+  }
+
   /// return a visual rect to place the text in pixel.
   pub fn visual_rect(&self) -> Rect {
     let info = &self.visual_info;
@@ -458,6 +567,13 @@ impl VisualGlyphs {
 
   pub fn glyph_row_count(&self) -> usize { self.visual_info.visual_lines.len() }
 
+  /// Return if the text is over the bounds of the typography.
+  pub fn is_over_bounds(&self) -> bool { self.visual_info.over_bounds }
+
+  /// Return if all the paragraphs start inside the bounds of the typography,
+  /// the paragraphs out of the bounds are invisible if the text is clipped.
+  pub fn is_fully_placed(&self) -> bool { self.visual_info.fully_placed }
+
   fn is_horizontal_line(&self) -> bool { !self.visual_info.line_dir.is_horizontal() }
 }
 
@@ -484,6 +600,38 @@ impl TypographyKey {
   }
 }
 
+/// Return the byte range of the word that the `cluster` belongs to.
+///
+/// The words are split by the Unicode word boundaries (UAX #29) of the origin
+/// text, so it's independent of the layout direction. A run of punctuation or
+/// whitespace is a word by itself, and every CJK ideograph is a word.
+pub fn word_range_at(text: &str, cluster: usize) -> Range<usize> {
+  if cluster >= text.len() {
+    return text.len()..text.len();
+  }
+
+  let is_punct = |w: &str| {
+    w.chars()
+      .all(|c| !c.is_alphanumeric() && !c.is_whitespace())
+  };
+  let words: Vec<_> = text.split_word_bound_indices().collect();
+  let idx = words.partition_point(|(start, w)| start + w.len() <= cluster);
+  let (start, word) = words[idx];
+  let mut rg = start..start + word.len();
+  if is_punct(word) {
+    let before = words[..idx]
+      .iter()
+      .rev()
+      .take_while(|(_, w)| is_punct(w));
+    let after = words[idx + 1..]
+      .iter()
+      .take_while(|(_, w)| is_punct(w));
+    rg.start = before.last().map_or(rg.start, |(s, _)| *s);
+    rg.end = after.last().map_or(rg.end, |(s, w)| s + w.len());
+  }
+  rg
+}
+
 #[cfg(test)]
 mod tests {
   use core::f32;
@@ -502,7 +650,17 @@ mod tests {
     FontFace { families: Box::new([FontFamily::Name("DejaVu Sans".into())]), ..<_>::default() }
   }
   fn text_style(font_size: f32, overflow: TextOverflow, letter_space: f32) -> TextStyle {
-    TextStyle { font_size, font_face: test_face(), letter_space, line_height: font_size, overflow }
+    TextStyle {
+      font_size,
+      font_face: test_face(),
+      letter_space,
+      line_height: font_size.into(),
+      overflow,
+      font_features: vec![],
+      palette_index: 0,
+      text_transform: TextTransform::None,
+      case_locale: CaseLocale::Default,
+    }
   }
   fn zero_letter_space_style(font_size: f32, overflow: TextOverflow) -> TextStyle {
     text_style(font_size, overflow, 0.)
@@ -705,6 +863,153 @@ mod tests {
     ],);
   }
 
+  #[test]
+  fn negative_letter_space() {
+    fn glyphs_x(letter_space: f32) -> Vec<f32> {
+      let style = text_style(10., TextOverflow::Overflow, letter_space);
+      let info = typography_text(
+        "Hello".into(),
+        &style,
+        Size::new(f32::MAX, f32::MAX),
+        TextAlign::Start,
+        PlaceLineDirection::TopToBottom,
+      );
+      info
+        .glyphs()
+        .map(|g| g.bounds().min_x())
+        .collect()
+    }
+
+    let normal = glyphs_x(0.);
+    let tight = glyphs_x(-1.);
+    assert_eq!(normal.len(), tight.len());
+    for (i, (n, t)) in normal.iter().zip(tight.iter()).enumerate() {
+      assert!((n - i as f32 - t).abs() < 0.01);
+    }
+
+    // The overlap is clamped, so glyphs never be placed in reverse order.
+    let overlap = glyphs_x(-100.);
+    assert!(overlap.windows(2).all(|w| w[0] <= w[1]));
+  }
+
+  #[test]
+  fn text_transform_style() {
+    let glyphs = |text: &'static str, text_transform| {
+      let style =
+        TextStyle { text_transform, ..zero_letter_space_style(14., TextOverflow::Overflow) };
+      let visual = typography_text(
+        text.into(),
+        &style,
+        Size::new(f32::MAX, f32::MAX),
+        TextAlign::Start,
+        PlaceLineDirection::TopToBottom,
+      );
+      visual
+        .glyphs()
+        .map(|g| (g.glyph_id, g.cluster))
+        .collect::<Vec<_>>()
+    };
+
+    let upper = glyphs("hello world", TextTransform::Upper);
+    assert_eq!(upper, glyphs("HELLO WORLD", TextTransform::None));
+    assert_ne!(upper, glyphs("hello world", TextTransform::None));
+
+    // The same text with different transforms is not shared by the cache.
+    let mut store = test_store();
+    let mut typography = |text_transform| {
+      let style =
+        TextStyle { text_transform, ..zero_letter_space_style(14., TextOverflow::Overflow) };
+      let visual = store.typography(
+        "hello".into(),
+        &style,
+        Size::new(f32::MAX, f32::MAX),
+        TextAlign::Start,
+        GlyphBaseline::Alphabetic,
+        PlaceLineDirection::TopToBottom,
+      );
+      visual.visual_rect().size
+    };
+    assert_ne!(typography(TextTransform::None), typography(TextTransform::Upper));
+  }
+
+  #[test]
+  fn over_bounds_flags() {
+    let style = zero_letter_space_style(14., TextOverflow::Overflow);
+    let typography = |bounds: Size| {
+      typography_text(
+        "a\nb\nc".into(),
+        &style,
+        bounds,
+        TextAlign::Start,
+        PlaceLineDirection::TopToBottom,
+      )
+    };
+
+    let visual = typography(Size::new(100., 100.));
+    assert!(!visual.is_over_bounds());
+    assert!(visual.is_fully_placed());
+
+    let visual = typography(Size::new(100., 20.));
+    assert!(visual.is_over_bounds());
+    assert!(!visual.is_fully_placed());
+    // All the paragraphs are still laid out.
+    assert_eq!(visual.glyph_row_count(), 3);
+  }
+
+  #[test]
+  fn line_height_factor() {
+    fn text_height(line_height: LineHeight) -> f32 {
+      let style = TextStyle { line_height, ..zero_letter_space_style(14., TextOverflow::Overflow) };
+      let info = typography_text(
+        "Hello\nworld!".into(),
+        &style,
+        Size::new(f32::MAX, f32::MAX),
+        TextAlign::Start,
+        PlaceLineDirection::TopToBottom,
+      );
+      info.visual_rect().height()
+    }
+
+    assert_eq!(text_height(LineHeight::Factor(1.)), 28.);
+    assert_eq!(text_height(LineHeight::Factor(2.)), 56.);
+    assert_eq!(text_height(LineHeight::Factor(2.)), text_height(LineHeight::Absolute(28.)));
+  }
+
+  #[test]
+  fn rasterize_text() {
+    let mut store = test_store();
+    let style = TextStyle {
+      line_height: 40f32.into(),
+      ..zero_letter_space_style(20., TextOverflow::Overflow)
+    };
+    let glyphs = store.typography(
+      "Hi".into(),
+      &style,
+      Size::new(f32::MAX, f32::MAX),
+      TextAlign::Start,
+      GlyphBaseline::Alphabetic,
+      PlaceLineDirection::TopToBottom,
+    );
+
+    let img = glyphs.rasterize(&store.font_db().borrow(), 1.);
+    let width = img.width() as usize;
+    let rows: Vec<_> = img.pixel_bytes().chunks(width).collect();
+    assert_eq!(rows.len(), 40);
+
+    // The glyphs are placed in the middle of the line, so the margins are empty.
+    assert!(
+      rows[..8]
+        .iter()
+        .all(|r| r.iter().all(|a| *a == 0))
+    );
+    assert!(
+      rows[32..]
+        .iter()
+        .all(|r| r.iter().all(|a| *a == 0))
+    );
+    assert!(rows[20].iter().any(|a| *a > 0));
+  }
+
   #[test]
   fn cache_test() {
     let mut store = test_store();
@@ -831,4 +1136,553 @@ mod tests {
     );
     assert_eq!(1, store.cache.len());
   }
+
+  #[test]
+  fn align_vertical_lines() {
+    // The lines are placed horizontally, so the text is aligned vertically.
+    let style = zero_letter_space_style(16., TextOverflow::Overflow);
+    let bounds = Size::new(100., 100.);
+    let origin_y = |text_align| {
+      let glyphs =
+        typography_text("Hi".into(), &style, bounds, text_align, PlaceLineDirection::LeftToRight);
+      let rect = glyphs.visual_rect();
+      (rect.origin.y, rect.size.height)
+    };
+
+    let (top, height) = origin_y(TextAlign::Start);
+    assert_eq!(top, 0.);
+    assert!(height > 0. && height < bounds.height);
+    let center = origin_y(TextAlign::Center).0;
+    assert!((center - (bounds.height - height) / 2.).abs() < 0.1);
+    let bottom = origin_y(TextAlign::End).0;
+    assert!((bottom - (bounds.height - height)).abs() < 0.1);
+  }
+
+  #[test]
+  fn line_of_cluster() {
+    let text = "Hello\nribir\nworld!";
+    let style = zero_letter_space_style(14., TextOverflow::Overflow);
+    let glyphs = typography_text(
+      text.into(),
+      &style,
+      Size::new(f32::MAX, f32::MAX),
+      TextAlign::Start,
+      PlaceLineDirection::TopToBottom,
+    );
+    let infos = &glyphs.visual_info;
+
+    assert_eq!(infos.visual_lines.len(), 3);
+    assert_eq!(infos.line_of_cluster(0), Some(0));
+    assert_eq!(infos.line_of_cluster(5), Some(0));
+    assert_eq!(infos.line_of_cluster(6), Some(1));
+    assert_eq!(infos.line_of_cluster(13), Some(2));
+    // The clusters past the end belong to the last line.
+    assert_eq!(infos.line_of_cluster(text.len() as u32), Some(2));
+    assert_eq!(infos.line_of_cluster(100), Some(2));
+
+    let first = infos.line_rect(0).unwrap();
+    let second = infos.line_rect(1).unwrap();
+    assert_eq!(first.origin.y + first.size.height, second.origin.y);
+    assert!(infos.line_rect(3).is_none());
+
+    let typography = |text: &'static str, dir| {
+      let bounds = Size::new(f32::MAX, f32::MAX);
+      typography_text(text.into(), &style, bounds, TextAlign::Start, dir).visual_info
+    };
+
+    // The blank line only has its line break.
+    let infos = typography("a\n\nb", PlaceLineDirection::TopToBottom);
+    assert_eq!(infos.visual_lines.len(), 3);
+    let blank_clusters = infos.visual_lines[1]
+      .glyphs
+      .iter()
+      .map(|g| g.cluster)
+      .collect::<Vec<_>>();
+    assert_eq!(blank_clusters, [2]);
+    assert_eq!(infos.line_of_cluster(0), Some(0));
+    assert_eq!(infos.line_of_cluster(2), Some(1));
+    assert_eq!(infos.line_of_cluster(3), Some(2));
+
+    // The lines are placed reversely.
+    let infos = typography("a\n\nb", PlaceLineDirection::BottomToTop);
+    assert_eq!(infos.line_of_cluster(0), Some(2));
+    assert_eq!(infos.line_of_cluster(3), Some(0));
+    assert_eq!(infos.line_of_cluster(100), Some(0));
+
+    // A text starts with a blank line.
+    let infos = typography("\nb", PlaceLineDirection::TopToBottom);
+    assert_eq!(infos.line_of_cluster(0), Some(0));
+    assert_eq!(infos.line_of_cluster(1), Some(1));
+  }
+
+  #[test]
+  fn run_backgrounds() {
+    let mut store = test_store();
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let text: Substr = "Hello world!".into();
+    let mut run = |rg: Range<usize>, background| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, &ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, 1., GlyphUnit::ZERO, rg).with_background(background)
+    };
+    // The first two runs have the same background, and the last one differs.
+    let runs: SmallVec<[InputRun; 1]> = smallvec::smallvec![
+      run(0..3, Some(Color::RED)),
+      run(3..6, Some(Color::RED)),
+      run(6..12, Some(Color::BLUE)),
+    ];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32);
+    let infos = TypographyMan::new(
+      std::iter::once(runs),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    let rects = infos.background_rects();
+    assert_eq!(rects.len(), 2);
+    let (red, red_rect) = rects[0];
+    let (blue, blue_rect) = rects[1];
+    assert_eq!((red, blue), (Color::RED, Color::BLUE));
+    assert_eq!(red_rect.origin.x, GlyphUnit::ZERO);
+    assert_eq!(red_rect.origin.x + red_rect.size.width, blue_rect.origin.x);
+    assert_eq!(blue_rect.origin.x + blue_rect.size.width, infos.visual_width);
+    assert_eq!(red_rect.size.height, line_height);
+    assert_eq!(blue_rect.size.height, line_height);
+  }
+
+  #[test]
+  fn superscript_run() {
+    let mut store = test_store();
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let text: Substr = "x2".into();
+    let mut run = |rg: Range<usize>| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, &ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, 1., GlyphUnit::ZERO, rg)
+    };
+    let runs: SmallVec<[InputRun; 1]> =
+      smallvec::smallvec![run(0..1), run(1..2).with_baseline_shift(0.5)];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32);
+    let infos = TypographyMan::new(
+      std::iter::once(runs),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    let line = &infos.visual_lines[0];
+    let (base, sup) = (&line.glyphs[0], &line.glyphs[1]);
+    let shift = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32 * 0.5);
+    assert_eq!(base.y_offset - sup.y_offset, shift);
+    // The line grows to contain the raised glyph.
+    assert_eq!(sup.y_offset, GlyphUnit::ZERO);
+    assert_eq!(line.height, line_height + shift);
+    assert_eq!(infos.visual_height, line_height + shift);
+  }
+
+  #[test]
+  fn glyph_run_index() {
+    let mut store = test_store();
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let text: Substr = "abcde".into();
+    let mut run = |rg: Range<usize>| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, &ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, 1., GlyphUnit::ZERO, rg)
+    };
+    let runs: SmallVec<[InputRun; 1]> = smallvec::smallvec![run(0..2), run(2..5)];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32);
+    let infos = TypographyMan::new(
+      std::iter::once(runs),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    let line = &infos.visual_lines[0];
+    assert_eq!(line.runs, [(0..2, 0), (2..5, 1)]);
+    let run_of_glyphs: Vec<_> = (0..line.glyphs.len())
+      .map(|i| line.run_of(i))
+      .collect();
+    assert_eq!(run_of_glyphs, [Some(0), Some(0), Some(1), Some(1), Some(1)]);
+    assert_eq!(line.run_of(5), None);
+  }
+
+  #[test]
+  fn max_lines() {
+    let mut store = test_store();
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let text: Substr = "Hello Hello Hello".into();
+    let dir = TextDirection::LeftToRight;
+    let shape_result = store
+      .shaper
+      .shape_text(&text, &ids, dir, GlyphBaseline::Alphabetic);
+    // Only a word and its space fit in a line.
+    let first_word = shape_result.glyphs[..6]
+      .iter()
+      .fold(GlyphUnit::ZERO, |acc, g| acc + g.x_advance);
+    let bounds = Size::new(first_word + GlyphUnit::from_pixel(1.), GlyphUnit::MAX);
+
+    let typography = |max_lines| {
+      let run = InputRun::new(shape_result.clone(), 1., GlyphUnit::ZERO, 0..text.len());
+      let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32);
+      TypographyMan::new(
+        std::iter::once(smallvec::smallvec![run]),
+        PlaceLineDirection::TopToBottom,
+        TextAlign::Start,
+        line_height,
+        bounds,
+        TextOverflow::AutoWrap,
+      )
+      .with_max_lines(max_lines)
+      .typography_all()
+    };
+
+    let infos = typography(None);
+    assert_eq!(infos.visual_lines.len(), 3);
+    assert!(!infos.truncated);
+
+    let infos = typography(Some(2));
+    assert_eq!(infos.visual_lines.len(), 2);
+    assert!(infos.truncated);
+    let last = &infos.visual_lines[1];
+    assert_eq!(last.glyphs.first().map(|g| g.cluster), Some(6));
+    assert_eq!(last.glyphs.last().map(|g| g.cluster), Some(11));
+  }
+
+  #[test]
+  fn inline_placeholder() {
+    let mut store = test_store();
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let text: Substr = "ab\u{FFFC}cd".into();
+    let mut run = |rg: Range<usize>| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, &ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, 1., GlyphUnit::ZERO, rg)
+    };
+    let size = Size::new(GlyphUnit::from_pixel(30.), GlyphUnit::from_pixel(8.));
+    let runs: SmallVec<[InputRun; 1]> =
+      smallvec::smallvec![run(0..2), InputRun::placeholder(7, size, 2..5), run(5..7)];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32);
+    let infos = TypographyMan::new(
+      std::iter::once(runs),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    let line = &infos.visual_lines[0];
+    assert_eq!(line.glyphs.len(), 4);
+    let b = &line.glyphs[1];
+    let rects: Vec<_> = infos.placeholder_rects().collect();
+    assert_eq!(
+      rects,
+      [(7, Rect::new(Point::new(b.x_offset + b.x_advance, GlyphUnit::ZERO), size))]
+    );
+    // The following text advances past the placeholder.
+    let c = &line.glyphs[2];
+    assert_eq!(c.x_offset, rects[0].1.max_x());
+    assert_eq!(c.cluster, 5);
+    assert_eq!(line.runs, [(0..2, 0), (2..4, 2)]);
+  }
+
+  #[test]
+  fn reveal_clip_grows() {
+    let style = zero_letter_space_style(14., TextOverflow::Overflow);
+    let typography = |text: &'static str| {
+      let bounds = (f32::MAX, f32::MAX).into();
+      let dir = PlaceLineDirection::TopToBottom;
+      typography_text(text.into(), &style, bounds, TextAlign::Start, dir).visual_info
+    };
+
+    let infos = typography("Hello");
+    assert!(infos.reveal_clip_at(0).is_empty());
+    let clips: Vec<_> = (1..=5).map(|n| infos.reveal_clip_at(n)).collect();
+    assert!(clips.iter().all(|c| c.len() == 1));
+    assert!(
+      clips
+        .windows(2)
+        .all(|w| w[0][0].size.width < w[1][0].size.width)
+    );
+    // The clip starts from the first glyph.
+    let origin = clips[0][0].origin;
+    assert!(clips.iter().all(|c| c[0].origin == origin));
+    assert_eq!(infos.reveal_clip_at(100), clips[4]);
+  }
+
+  #[test]
+  fn reveal_multi_line_clip() {
+    let style = zero_letter_space_style(14., TextOverflow::Overflow);
+    let bounds = (f32::MAX, f32::MAX).into();
+    let typography = |text: &'static str| {
+      let dir = PlaceLineDirection::TopToBottom;
+      typography_text(text.into(), &style, bounds, TextAlign::Start, dir).visual_info
+    };
+
+    let hello = typography("Hello").reveal_clip_at(5)[0];
+    let infos = typography("Hello\nworld");
+    let second = infos.line_rect(1).unwrap();
+
+    // Only the first line is revealed, and partially.
+    let clip = infos.reveal_clip_at(1);
+    assert_eq!(clip.len(), 1);
+    assert_eq!(clip[0].origin, hello.origin);
+    assert!(clip[0].size.width < hello.size.width);
+    assert_eq!(infos.reveal_clip_at(5), [hello]);
+
+    // The first line is fully revealed, and the second line partially.
+    let full = infos.reveal_clip_at(100);
+    let clip = infos.reveal_clip_at(7);
+    assert_eq!(clip.len(), 2);
+    assert_eq!(clip[0], full[0]);
+    assert_eq!(clip[1].origin, full[1].origin);
+    assert_eq!(clip[1].origin.y, second.origin.y);
+    assert!(clip[1].size.width > GlyphUnit::ZERO);
+    assert!(clip[1].size.width < full[1].size.width);
+
+    // The empty line has nothing to reveal.
+    let infos = typography("Hello\n\nworld");
+    assert_eq!(infos.reveal_clip_at(100).len(), 2);
+  }
+
+  #[test]
+  fn measure_same_as_typography() {
+    let text: Substr = "Hello world! Nice to meet you.\nThe second paragraph.".into();
+    let bounds = Size::new(120., f32::INFINITY);
+    let styles = [
+      zero_letter_space_style(14., TextOverflow::Overflow),
+      zero_letter_space_style(14., TextOverflow::AutoWrap),
+      text_style(20., TextOverflow::AutoWrap, 2.),
+    ];
+    for style in styles.iter() {
+      for text_align in [TextAlign::Start, TextAlign::End] {
+        let dir = PlaceLineDirection::TopToBottom;
+        let glyphs = typography_text(text.clone(), style, bounds, text_align, dir);
+        let size = test_store().measure(
+          text.clone(),
+          style,
+          bounds,
+          text_align,
+          GlyphBaseline::Alphabetic,
+          dir,
+        );
+        assert_eq!(size, glyphs.visual_rect().size);
+      }
+    }
+  }
+
+  #[test]
+  fn start_align_respect_direction() {
+    let style = zero_letter_space_style(14., TextOverflow::Overflow);
+    let bounds = Size::new(100., 100.);
+    let visual_rect = |text: &'static str, align| {
+      typography_text(text.into(), &style, bounds, align, PlaceLineDirection::TopToBottom)
+        .visual_rect()
+    };
+
+    let ltr = visual_rect("abc", TextAlign::Start);
+    assert_eq!(ltr.min_x(), 0.);
+
+    // The start of a right-to-left paragraph is the right.
+    let rtl = visual_rect("אבג", TextAlign::Start);
+    assert!(rtl.min_x() > 0.);
+    assert!((rtl.max_x() - 100.).abs() < 0.01);
+
+    let rtl = visual_rect("אבג", TextAlign::End);
+    assert_eq!(rtl.min_x(), 0.);
+  }
+
+  #[test]
+  fn word_range() {
+    let text = "Hello, world!! don't 3.14 你好";
+    let word_at = |cluster: usize| &text[word_range_at(text, cluster)];
+
+    assert_eq!(word_at(0), "Hello");
+    assert_eq!(word_at(4), "Hello");
+    assert_eq!(word_at(5), ",");
+    assert_eq!(word_at(6), " ");
+    assert_eq!(word_at(9), "world");
+    // A run of punctuation is a word.
+    assert_eq!(word_at(12), "!!");
+    assert_eq!(word_at(13), "!!");
+    assert_eq!(word_at(17), "don't");
+    assert_eq!(word_at(22), "3.14");
+    // Every ideograph is a word.
+    assert_eq!(word_at(26), "你");
+    assert_eq!(word_at(27), "你");
+    assert_eq!(word_at(29), "好");
+    assert_eq!(word_range_at(text, text.len()), text.len()..text.len());
+  }
+
+  #[test]
+  fn whitespace_run_is_a_word() {
+    let text = "a   b";
+    assert_eq!(word_range_at(text, 2), 1..4);
+    assert_eq!(word_range_at(text, 4), 4..5);
+  }
+
+  #[test]
+  fn positioned_runs() {
+    let mut store = test_store();
+    let _ = store
+      .font_db
+      .borrow_mut()
+      .load_font_file(env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/GaramondNo8-Reg.ttf");
+    let garamond =
+      FontFace { families: Box::new([FontFamily::Name("GaramondNo8".into())]), ..<_>::default() };
+    let dejavu_ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let garamond_ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&garamond);
+    let text: Substr = "abcdef".into();
+    let mut run = |rg: Range<usize>, ids: &[ID], factor: f32| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, factor, GlyphUnit::ZERO, rg)
+    };
+    let runs: SmallVec<[InputRun; 1]> = smallvec::smallvec![
+      run(0..2, &dejavu_ids, 1.),
+      run(2..4, &garamond_ids, 1.),
+      run(4..5, &garamond_ids, 1.),
+      run(5..6, &garamond_ids, 2.),
+    ];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32 * 2.);
+    let infos = TypographyMan::new(
+      std::iter::once(runs),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    let runs = infos.to_positioned_runs();
+    // The adjacent runs of the same face and size are merged.
+    let groups: Vec<_> = runs
+      .iter()
+      .map(|r| (r.face_id, r.font_size, r.glyphs.len()))
+      .collect();
+    let em = GlyphUnit::STANDARD_EM;
+    let em2 = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32 * 2.);
+    assert_eq!(groups, [
+      (dejavu_ids[0], em, 2),
+      (garamond_ids[0], em, 3),
+      (garamond_ids[0], em2, 1)
+    ]);
+
+    let line = &infos.visual_lines[0];
+    let placed: Vec<_> = line
+      .glyphs
+      .iter()
+      .map(|g| (g.glyph_id, Point::new(line.x + g.x_offset, line.y + g.y_offset)))
+      .collect();
+    let positioned: Vec<_> = runs.into_iter().flat_map(|r| r.glyphs).collect();
+    assert_eq!(positioned, placed);
+  }
+
+  #[test]
+  fn line_metrics() {
+    let mut store = test_store();
+    let _ = store
+      .font_db
+      .borrow_mut()
+      .load_font_file(env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/GaramondNo8-Reg.ttf");
+    let garamond =
+      FontFace { families: Box::new([FontFamily::Name("GaramondNo8".into())]), ..<_>::default() };
+    let dejavu_ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let garamond_ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&garamond);
+    let text: Substr = "ab\ncd".into();
+    let mut run = |rg: Range<usize>, ids: &[ID], factor: f32| {
+      let dir = TextDirection::LeftToRight;
+      let text = text.substr(rg.clone());
+      let shape_result = store
+        .shaper
+        .shape_text(&text, ids, dir, GlyphBaseline::Alphabetic);
+      InputRun::new(shape_result, factor, GlyphUnit::ZERO, rg)
+        .with_font_metrics(&store.font_db.borrow())
+    };
+    let first: SmallVec<[InputRun; 1]> = smallvec::smallvec![run(0..3, &dejavu_ids, 1.)];
+    let second: SmallVec<[InputRun; 1]> =
+      smallvec::smallvec![run(3..4, &dejavu_ids, 1.), run(4..5, &garamond_ids, 2.)];
+
+    let line_height = GlyphUnit::from_pixel(GlyphUnit::PIXELS_PER_EM as f32 * 2.);
+    let infos = TypographyMan::new(
+      [first, second].into_iter(),
+      PlaceLineDirection::TopToBottom,
+      TextAlign::Start,
+      line_height,
+      Size::new(GlyphUnit::MAX, GlyphUnit::MAX),
+      TextOverflow::Overflow,
+    )
+    .typography_all();
+
+    // DejaVu Sans: 2048 units per em, ascender 1901, descender -483, line gap 0.
+    let unit = |v: i32| GlyphUnit::new(v * GlyphUnit::UNITS_PER_EM as i32 / 2048);
+    let dejavu = LineMetrics { ascent: unit(1901), descent: unit(483), line_gap: unit(0) };
+    assert_eq!(infos.line_metrics(0), Some(dejavu));
+    // GaramondNo8 in double size: ascender 1919, descender -538, line gap 307.
+    let garamond =
+      LineMetrics { ascent: unit(1919 * 2), descent: unit(538 * 2), line_gap: unit(307 * 2) };
+    assert_eq!(infos.line_metrics(1), Some(garamond));
+    assert_eq!(infos.line_metrics(2), None);
+  }
 }
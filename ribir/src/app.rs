@@ -33,6 +33,9 @@ pub struct WindowAttributes {
   pub max_size: Option<Size>,
   pub position: Option<Point>,
   pub icon: Option<Resource<PixelImage>>,
+  /// The sample count of the multi-sampling anti-aliasing, `1` means no
+  /// multi-sampling.
+  pub msaa_samples: u32,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -123,7 +126,7 @@ impl App {
           }
           WindowEvent::Resized(_) => {
             let size = wnd.shell_wnd().borrow().inner_size();
-            wnd.shell_wnd().borrow_mut().on_resize(size);
+            wnd.on_resize(size);
             request_redraw(&wnd)
           }
           WindowEvent::Focused(focused) => {
@@ -406,6 +409,15 @@ impl AppRunGuard {
     self
   }
 
+  /// Sets the sample count of the multi-sampling anti-aliasing for the edges
+  /// of the geometry. It falls back to `1` if the GPU does not support it.
+  ///
+  /// The default is `1`, no multi-sampling.
+  pub fn with_msaa_samples(&mut self, samples: u32) -> &mut Self {
+    self.wnd_attr().msaa_samples = samples;
+    self
+  }
+
   fn wnd_attr(&mut self) -> &mut WindowAttributes {
     // Should be safe to unwrap because `wnd_attrs` is always `Some` before
     // drop.
@@ -526,6 +538,15 @@ impl WindowAttributes {
     self.icon = Some(icon);
     self
   }
+
+  /// Sets the sample count of the multi-sampling anti-aliasing for the edges
+  /// of the geometry. It falls back to `1` if the GPU does not support it.
+  ///
+  /// The default is `1`, no multi-sampling.
+  pub fn with_msaa_samples(&mut self, samples: u32) -> &mut Self {
+    self.msaa_samples = samples;
+    self
+  }
 }
 
 impl Default for WindowAttributes {
@@ -541,6 +562,7 @@ impl Default for WindowAttributes {
       visible: true,
       decorations: true,
       icon: None,
+      msaa_samples: 1,
     }
   }
 }
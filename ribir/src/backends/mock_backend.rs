@@ -3,7 +3,7 @@ use crate::winit_shell_wnd::WinitBackend;
 pub struct MockBackend;
 
 impl WinitBackend for MockBackend {
-  fn new(_: &winit::window::Window) -> Self { Self }
+  fn new(_: &winit::window::Window, _: u32) -> Self { Self }
 
   fn on_resize(&mut self, _: ribir_core::prelude::DeviceSize) {}
 
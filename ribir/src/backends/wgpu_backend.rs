@@ -9,8 +9,9 @@ pub struct WgpuBackend<'a> {
 }
 
 impl<'a> WinitBackend<'a> for WgpuBackend<'a> {
-  async fn new(window: &'a winit::window::Window) -> WgpuBackend<'a> {
-    let (wgpu, surface) = ribir_gpu::WgpuImpl::new(window).await;
+  async fn new(window: &'a winit::window::Window, msaa_samples: u32) -> WgpuBackend<'a> {
+    let (mut wgpu, surface) = ribir_gpu::WgpuImpl::new(window).await;
+    wgpu.set_msaa_samples(msaa_samples);
     let size = window.inner_size();
     let size = DeviceSize::new(size.width as i32, size.height as i32);
 
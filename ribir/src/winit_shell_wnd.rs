@@ -14,7 +14,7 @@ use crate::{
   prelude::{WindowAttributes, request_redraw},
 };
 pub trait WinitBackend<'a>: Sized {
-  fn new(window: &'a winit::window::Window) -> impl Future<Output = Self>;
+  fn new(window: &'a winit::window::Window, msaa_samples: u32) -> impl Future<Output = Self>;
 
   fn on_resize(&mut self, size: DeviceSize);
 
@@ -262,7 +262,7 @@ impl WinitShellWnd {
     let ptr = &winit_wnd as *const winit::window::Window;
     // Safety: a reference to winit_wnd is valid as long as the WinitShellWnd is
     // alive.
-    let backend = Backend::new(unsafe { &*ptr }).await;
+    let backend = Backend::new(unsafe { &*ptr }, attrs.msaa_samples).await;
 
     // show the window after the render backend is ready
     if attrs.visible {
@@ -1,5 +1,5 @@
 use ribir_core::prelude::*;
-use ribir_widgets::input::{INPUT, TEXT_CARET, TEXT_SELECTION, TEXTAREA};
+use ribir_widgets::input::{CaretBlink, INPUT, TEXT_CARET, TEXT_SELECTION, TEXTAREA};
 
 use crate::md;
 
@@ -7,15 +7,16 @@ pub(super) fn init(classes: &mut Classes) {
   classes.insert(TEXT_CARET, |w| {
     rdl! {
       let mut w = FatObj::new(w);
-      let blink_interval = Duration::from_millis(500);
-      let u = interval(blink_interval, AppCtx::scheduler())
-        .subscribe(move |idx| $w.write().opacity = (idx % 2) as f32);
+      let blink = Provider::of::<Stateful<CaretBlink>>(BuildCtx::get())
+        .map(|b| b.clone_watcher());
       let border = BuildCtx::color()
         .map(|color| Border::only_left(BorderSide::new(2., color.into())));
+      if let Some(blink) = blink {
+        w = w.opacity(pipe!(if $blink.visible() { 1. } else { 0. }));
+      }
       @ $w {
         clamp: BoxClamp::fixed_width(2.),
         border,
-        on_disposed: move |_| u.unsubscribe()
       }
     }
     .into_widget()
@@ -93,7 +93,7 @@ fn init_custom_style(theme: &mut Theme) {
   theme
     .custom_styles
     .set_custom_style(ListItemStyle {
-      padding_style: Some(EdgeInsets { left: 0., right: 24., bottom: 8., top: 8. }),
+      padding_style: Some(EdgeInsets::new(8., 24., 8., 0.)),
       item_align: |num| {
         if num >= 2 { Align::Start } else { Align::Center }
       },
@@ -242,11 +242,15 @@ pub fn typography_theme() -> TypographyTheme {
   ) -> TextTheme {
     TextTheme {
       text: TextStyle {
-        line_height,
+        line_height: line_height.into(),
         font_size,
         letter_space,
         font_face,
         overflow: TextOverflow::Overflow,
+        font_features: vec![],
+        palette_index: 0,
+        text_transform: TextTransform::None,
+        case_locale: CaseLocale::Default,
       },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
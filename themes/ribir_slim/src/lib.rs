@@ -77,11 +77,15 @@ fn typography_theme() -> TypographyTheme {
   ) -> TextTheme {
     TextTheme {
       text: TextStyle {
-        line_height,
+        line_height: line_height.into(),
         font_size,
         letter_space,
         font_face,
         overflow: TextOverflow::Overflow,
+        font_features: vec![],
+        palette_index: 0,
+        text_transform: TextTransform::None,
+        case_locale: CaseLocale::Default,
       },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
@@ -0,0 +1,109 @@
+use ribir_core::{impl_compose_child_for_wrap_render, prelude::*, wrap_render::WrapRender};
+
+/// A widget that paints a drop shadow of its box behind its child. If a
+/// `Radius` is provided, the shadow follows the rounded corners.
+///
+/// The shadow is the box expanded by `spread` and blurred by `blur_radius`,
+/// then painted with an `offset` to the box, like the `box-shadow` in CSS. It
+/// doesn't affect the layout of the child. The shadows of the same shape share
+/// one alpha mask, tinted by their own colors.
+///
+/// # Example
+///
+/// ```
+/// use ribir::prelude::*;
+///
+/// let _card = fn_widget! {
+///   @BoxShadow {
+///     color: Color::BLACK.with_alpha(0.3),
+///     offset: Point::new(0., 2.),
+///     blur_radius: 8.,
+///     @Container { size: Size::new(100., 60.), background: Color::WHITE }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct BoxShadow {
+  /// The color of the shadow.
+  pub color: Color,
+  /// The offset of the shadow relative to the box.
+  #[declare(default)]
+  pub offset: Point,
+  /// How far the shadow fades out of the box.
+  #[declare(default)]
+  pub blur_radius: f32,
+  /// How much the box is expanded before blurring, a negative value shrinks
+  /// it.
+  #[declare(default)]
+  pub spread: f32,
+}
+
+impl_compose_child_for_wrap_render!(BoxShadow, DirtyPhase::Paint);
+
+impl WrapRender for BoxShadow {
+  fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
+    let size = ctx.box_size().unwrap();
+    if !size.is_empty() && self.color.alpha > 0 {
+      let (provider_ctx, mut painter) = ctx.provider_ctx_and_box_painter();
+      let radius = Provider::of::<Radius>(provider_ctx).map_or_else(Radius::default, |r| *r);
+      let Self { color, offset, blur_radius, spread } = *self;
+      let mask = box_shadow_mask(size, &radius, spread, blur_radius);
+
+      let margin = box_shadow_margin(spread, blur_radius);
+      let origin = offset - Vector::splat(margin);
+      let dst = Rect::new(origin, Size::new(mask.width() as f32, mask.height() as f32));
+      painter.draw_tinted_img(mask, &dst, &None, color);
+    }
+    host.paint(ctx);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{prelude::image::ColorFormat, reset_test_env, test_helper::*};
+
+  use super::*;
+
+  #[test]
+  fn shadows_share_mask() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @BoxShadow {
+          color: Color::RED,
+          blur_radius: 4.,
+          @MockBox { size: Size::new(20., 20.) }
+        }
+        @BoxShadow {
+          color: Color::BLUE,
+          offset: Point::new(2., 2.),
+          blur_radius: 4.,
+          @MockBox { size: Size::new(20., 20.) }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let shadows: Vec<_> = frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Paint { brush: CommandBrush::Image { img, tint, .. }, .. },
+          ..
+        }) => Some((img.clone(), *tint)),
+        _ => None,
+      })
+      .collect();
+
+    let [(red, Some(Color::RED)), (blue, Some(Color::BLUE))] = &shadows[..] else {
+      panic!("Expect a red and a blue shadow.");
+    };
+    assert_eq!(red.color_format(), ColorFormat::Alpha8);
+    // The same shape shares one mask, only the tint differs.
+    assert!(red == blue);
+    assert_eq!((red.width(), red.height()), (28, 28));
+  }
+}
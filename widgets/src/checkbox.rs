@@ -1,6 +1,6 @@
 use ribir_core::prelude::*;
 
-use crate::prelude::{PositionChild, icon_with_label};
+use crate::prelude::{PositionChild, Stack, icon_with_label};
 
 /// The `Checkbox` allows users to toggle an option on or off, or represent a
 /// list of options that are partially selected.
@@ -10,6 +10,8 @@ use crate::prelude::{PositionChild, icon_with_label};
 /// or after the radio button using the `Leading` and `Trailing` types, with the
 /// default position being after the radio button.
 ///
+/// The checkbox is toggled by a tap, or by the space key when it's focused.
+///
 /// # Example
 ///
 /// ```
@@ -89,11 +91,14 @@ impl ComposeChild<'static> for Checkbox {
         @Void { class: CHECKBOX }
       };
       @FatObj {
+        tab_index: 0i16,
         on_tap: move |_| $this.write().switch_check(),
         on_key_up: move |k| if *k.key() == VirtualKey::Named(NamedKey::Space) {
           $this.write().switch_check()
         },
-        @ icon_with_label(icon.into_widget(), child)
+        // The icon is regenerated when its class changes, so host the focus by a
+        // stable widget to keep it across the toggles.
+        @Stack { @ icon_with_label(icon.into_widget(), child) }
       }
     }
     .into_widget()
@@ -102,12 +107,69 @@ impl ComposeChild<'static> for Checkbox {
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, test_helper::*};
   use ribir_dev_helper::*;
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
   use super::*;
   use crate::prelude::*;
 
+  fn checkbox_env() -> (TestWindow, Watcher<Reader<bool>>) {
+    AppCtx::set_app_theme(ribir_material::purple::light());
+    let (checked, w_checked) = split_value(false);
+    let w = fn_widget! {
+      let checkbox = @Checkbox {};
+      watch!($checkbox.checked).subscribe(move |v| *$w_checked.write() = v);
+      checkbox
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    (wnd, checked)
+  }
+
+  fn tap_checkbox(wnd: &mut TestWindow) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (20., 20.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn tap_to_toggle() {
+    reset_test_env!();
+    let (mut wnd, checked) = checkbox_env();
+
+    tap_checkbox(&mut wnd);
+    assert!(*checked.read());
+    tap_checkbox(&mut wnd);
+    assert!(!*checked.read());
+  }
+
+  #[test]
+  fn space_to_toggle() {
+    reset_test_env!();
+    let (mut wnd, checked) = checkbox_env();
+
+    // The tap also focuses the checkbox.
+    tap_checkbox(&mut wnd);
+    assert!(*checked.read());
+    assert!(wnd.focusing().is_some());
+
+    for state in [ElementState::Pressed, ElementState::Released] {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::Space),
+        VirtualKey::Named(NamedKey::Space),
+        false,
+        KeyLocation::Standard,
+        state,
+      );
+    }
+    wnd.draw_frame();
+    assert!(!*checked.read());
+  }
+
   widget_image_tests!(
     checkbox,
     WidgetTester::new(self::column! {
@@ -63,29 +63,88 @@ impl Render for Divider {
   fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
     if self.direction.is_horizontal() {
       let width = clamp.max.width;
-      if width.is_finite() { Size::new(width, self.extent) } else { clamp.min }
+      if width.is_finite() { clamp.clamp(Size::new(width, self.extent)) } else { clamp.min }
     } else {
       let height = clamp.max.height;
-      if height.is_finite() { Size::new(self.extent, height) } else { clamp.min }
+      if height.is_finite() { clamp.clamp(Size::new(self.extent, height)) } else { clamp.min }
     }
   }
 
   fn paint(&self, ctx: &mut PaintingCtx) {
-    let mut size = ctx.box_size().unwrap();
+    let rect = self.line_rect(ctx.box_size().unwrap());
+    let painter = ctx.painter();
+    painter.set_fill_brush(self.color.clone());
+    painter.rect(&rect);
+    painter.fill();
+  }
+}
+
+impl Divider {
+  /// The rect of the line in a divider box of `size`, the line is inset by the
+  /// indents on the main axis and centered on the cross axis.
+  fn line_rect(&self, mut size: Size) -> Rect {
     let (origin, size) = if self.direction.is_horizontal() {
-      size.width -= self.indent + self.end_indent;
+      size.width = (size.width - self.indent - self.end_indent).max(0.);
       size.height = self.thickness;
       let y = (self.extent - self.thickness) / 2.;
       (Point::new(self.indent, y), size)
     } else {
       size.width = self.thickness;
-      size.height -= self.indent + self.end_indent;
+      size.height = (size.height - self.indent - self.end_indent).max(0.);
       let x = (self.extent - self.thickness) / 2.;
       (Point::new(x, self.indent), size)
     };
-    let painter = ctx.painter();
-    painter.set_fill_brush(self.color.clone());
-    painter.rect(&Rect::new(origin, size));
-    painter.fill();
+    Rect::new(origin, size)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
+
+  use super::*;
+
+  widget_layout_test!(
+    horizontal_divider,
+    WidgetTester::new(fn_widget! {
+      @Column {
+        @Divider { extent: 8., thickness: 2., indent: 10., end_indent: 20. }
+      }
+    })
+    .with_wnd_size(Size::new(100., 100.)),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(100., 8.))
+  );
+
+  widget_layout_test!(
+    vertical_divider,
+    WidgetTester::new(fn_widget! {
+      @Row {
+        @Divider { extent: 8., direction: Direction::Vertical }
+      }
+    })
+    .with_wnd_size(Size::new(100., 100.)),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(8., 100.))
+  );
+
+  #[test]
+  fn line_inset_by_indents() {
+    let divider = |direction| Divider {
+      extent: 8.,
+      color: Color::RED.into(),
+      direction,
+      thickness: 2.,
+      indent: 10.,
+      end_indent: 20.,
+    };
+    let size = Size::new(100., 100.);
+    assert_eq!(
+      divider(Direction::Horizontal).line_rect(size),
+      Rect::new(Point::new(10., 3.), Size::new(70., 2.))
+    );
+    assert_eq!(
+      divider(Direction::Vertical).line_rect(size),
+      Rect::new(Point::new(3., 10.), Size::new(2., 70.))
+    );
   }
 }
@@ -90,7 +90,9 @@ impl WrapRender for IconText {
     let font_face = Provider::of::<IconFont>(&ctx).unwrap().0.clone();
     let mut style = Provider::of::<TextStyle>(ctx).unwrap().clone();
     style.font_face = font_face;
-    style.font_size = style.line_height;
+    let icon_size = style.line_height.to_pixel(style.font_size);
+    style.font_size = icon_size;
+    style.line_height = icon_size.into();
     let mut style = Provider::new(style);
     style.setup(ctx.as_mut());
     let size = host.perform_layout(clamp, ctx);
@@ -106,9 +108,10 @@ struct IconRender {
 
 impl Render for IconRender {
   fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
-    let icon_size = Provider::of::<TextStyle>(ctx)
-      .unwrap()
-      .line_height;
+    let icon_size = {
+      let style = Provider::of::<TextStyle>(ctx).unwrap();
+      style.line_height.to_pixel(style.font_size)
+    };
     let child_size = ctx
       .perform_single_child_layout(BoxClamp::default())
       .unwrap_or_default();
@@ -4,12 +4,14 @@ use ribir_core::prelude::*;
 
 use crate::prelude::*;
 
+mod caret_blink;
 mod edit_text;
 mod text_glyphs;
 
 mod text_editable;
 mod text_selectable;
 
+pub use caret_blink::*;
 pub use edit_text::*;
 pub use text_editable::*;
 pub use text_glyphs::*;
@@ -128,6 +130,7 @@ impl BaseText for InputText {
     self.0.measure_bytes(byte_from, char_len)
   }
   fn select_token(&self, byte_from: usize) -> Range<usize> { self.0.select_token(byte_from) }
+  fn floor_char_boundary(&self, at: usize) -> usize { self.0.floor_char_boundary(at) }
 }
 
 impl VisualText for InputText {
@@ -222,6 +225,27 @@ mod tests {
     assert_eq!(*value.read(), "helloworld");
   }
 
+  #[test]
+  fn input_edit_in_char() {
+    reset_test_env!();
+    let (value, w_value) = split_value(String::default());
+    let w = fn_widget! {
+      let input = @Input { auto_focus: true };
+      $input.write().set_text("aéb");
+      // The caret in the middle of `é`.
+      $input.write().select(2, 2);
+      watch!($input.text().clone())
+        .subscribe(move |text| *$w_value.write() = text.to_string());
+      input
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    wnd.processes_receive_chars("x".into());
+    wnd.draw_frame();
+    assert_eq!(*value.read(), "axéb");
+  }
+
   #[test]
   fn input_tap_focus() {
     reset_test_env!();
@@ -0,0 +1,112 @@
+use ribir_core::prelude::*;
+
+/// The blink state of a text caret. The caret is visible when it's reset, then
+/// toggles its visibility every `interval`.
+///
+/// The editor resets it when a key is pressed or the caret is moved, and
+/// provides it to the caret, so the caret class can paint by the
+/// [`CaretBlink::visible`].
+pub struct CaretBlink {
+  interval: Duration,
+  reset_at: Instant,
+  visible: bool,
+  timer: Option<TaskHandle<NormalReturn<()>>>,
+}
+
+impl CaretBlink {
+  pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+  pub fn new(interval: Duration, now: Instant) -> Self {
+    Self { interval, reset_at: now, visible: true, timer: None }
+  }
+
+  /// Return if the caret should be painted now.
+  pub fn visible(&self) -> bool { self.visible }
+
+  /// Return if the caret should be painted at the given time.
+  pub fn visible_at(&self, at: Instant) -> bool { self.toggles(at).is_multiple_of(2) }
+
+  pub fn interval(&self) -> Duration { self.interval }
+
+  /// Set the blink interval, and restart the blink from visible.
+  pub fn set_interval(&mut self, interval: Duration, now: Instant) {
+    self.interval = interval;
+    self.reset(now);
+  }
+
+  /// Make the caret visible, and restart the blink from `now`.
+  pub fn reset(&mut self, now: Instant) {
+    self.reset_at = now;
+    self.visible = true;
+  }
+
+  /// Update the visibility to the given time.
+  pub fn tick(&mut self, now: Instant) { self.visible = self.visible_at(now); }
+
+  /// The time the visibility toggles next after `now`.
+  pub fn next_toggle_at(&self, now: Instant) -> Instant {
+    self.reset_at + self.interval * (self.toggles(now) + 1)
+  }
+
+  /// Keep the `blink` toggling on time by the timer of the application, until
+  /// [`CaretBlink::stop`] is called.
+  pub fn start(this: &Stateful<Self>) {
+    let at = this.read().next_toggle_at(Instant::now());
+    let blink = this.clone_writer();
+    let h = observable::timer_at((), at, AppCtx::scheduler()).subscribe(move |_| {
+      // The timer may be scheduled before a reset, only notify the change.
+      let visible = blink.read().visible_at(Instant::now());
+      if visible != blink.read().visible {
+        blink.write().visible = visible;
+      }
+      CaretBlink::start(&blink);
+    });
+    if let Some(h) = this.silent().timer.replace(h) {
+      h.unsubscribe();
+    }
+  }
+
+  /// Stop the timer started by [`CaretBlink::start`].
+  pub fn stop(&mut self) {
+    if let Some(h) = self.timer.take() {
+      h.unsubscribe();
+    }
+  }
+
+  fn toggles(&self, at: Instant) -> u32 {
+    let elapsed = at.saturating_duration_since(self.reset_at);
+    (elapsed.as_nanos() / self.interval.as_nanos().max(1)) as u32
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blink_by_interval() {
+    let start = Instant::now();
+    let ms = |ms: u64| start + Duration::from_millis(ms);
+    let mut blink = CaretBlink::new(Duration::from_millis(500), start);
+    assert!(blink.visible());
+
+    blink.tick(ms(499));
+    assert!(blink.visible());
+    blink.tick(ms(500));
+    assert!(!blink.visible());
+    blink.tick(ms(1000));
+    assert!(blink.visible());
+    assert_eq!(blink.next_toggle_at(ms(1000)), ms(1500));
+
+    // Move the caret while it's hidden.
+    blink.tick(ms(1600));
+    assert!(!blink.visible());
+    blink.reset(ms(1700));
+    assert!(blink.visible());
+    assert_eq!(blink.next_toggle_at(ms(1700)), ms(2200));
+    blink.tick(ms(2100));
+    assert!(blink.visible());
+    blink.tick(ms(2200));
+    assert!(!blink.visible());
+  }
+}
@@ -1,7 +1,9 @@
 use std::ops::Range;
 
-use ribir_core::prelude::{CowArc, Substr};
-use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+use ribir_core::prelude::{CowArc, Substr, word_range_at};
+use unicode_segmentation::GraphemeCursor;
+
+use super::{CaretPosition, Selection};
 
 pub trait BaseText: Eq {
   fn measure_bytes(&self, byte_from: usize, char_len: isize) -> usize;
@@ -9,6 +11,10 @@ pub trait BaseText: Eq {
   fn substr(&self, rg: Range<usize>) -> Substr;
   fn len(&self) -> usize;
   fn is_empty(&self) -> bool { self.len() == 0 }
+  /// Return the nearest char boundary at or before the byte `at`, the offsets
+  /// of the edits are snapped to it. The default treats every byte offset in
+  /// the text as a boundary.
+  fn floor_char_boundary(&self, at: usize) -> usize { at.min(self.len()) }
 }
 
 pub trait EditText: BaseText {
@@ -17,6 +23,94 @@ pub trait EditText: BaseText {
   fn del_rg_str(&mut self, rg: Range<usize>) -> Range<usize>;
 }
 
+/// A text with the caret and the selection tracked across the edits, it's the
+/// data model of the text editing.
+///
+/// The edits keep the selection valid, the caret moves to the end of the
+/// inserted text or the start of the deleted range. Keep it in a `Stateful`,
+/// so the edits notify the widgets depending on it to relayout.
+#[derive(Clone, Default)]
+pub struct EditableText<T> {
+  text: T,
+  selection: Selection,
+}
+
+impl<T: EditText> EditableText<T> {
+  pub fn new(text: T) -> Self { Self { text, selection: Selection::default() } }
+
+  pub fn text(&self) -> &T { &self.text }
+
+  pub fn selection(&self) -> Selection { self.selection }
+
+  /// The caret is the moving end of the selection.
+  pub fn caret(&self) -> CaretPosition { self.selection.to }
+
+  /// Set the selection, the both ends are clamped into the text.
+  pub fn set_selection(&mut self, selection: Selection) {
+    let clamp = |pos: CaretPosition| CaretPosition {
+      cluster: self.text.floor_char_boundary(pos.cluster),
+      position: None,
+    };
+    self.selection = Selection { from: clamp(selection.from), to: clamp(selection.to) };
+  }
+
+  /// Insert `s` at the cluster `at` and place the caret after it, return the
+  /// length of the inserted text, it may differ from the length of `s` if the
+  /// text filters the characters. The `at` is snapped to the char boundary
+  /// before it.
+  pub fn insert(&mut self, at: usize, s: &str) -> usize { self.edit().insert(at, s) }
+
+  /// Delete the text in `rg` and place the caret at the start of it, return the
+  /// range actually deleted. The range is extended to the char boundaries to
+  /// cover the chars it partially covers.
+  pub fn delete_range(&mut self, rg: Range<usize>) -> Range<usize> { self.edit().delete_range(rg) }
+
+  /// Replace the selected text with `s`, such as the characters typed or
+  /// committed by the IME. Return the length of the inserted text.
+  pub fn replace_selection(&mut self, s: &str) -> usize { self.edit().replace_selection(s) }
+
+  fn edit(&mut self) -> TextEdit<'_, T> {
+    TextEdit { text: &mut self.text, selection: &mut self.selection }
+  }
+}
+
+/// The edit operations on a text and its selection, shared by the
+/// [`EditableText`] and the editors that keep the text and the selection in
+/// their own fields.
+pub(crate) struct TextEdit<'a, T> {
+  pub(crate) text: &'a mut T,
+  pub(crate) selection: &'a mut Selection,
+}
+
+impl<T: EditText> TextEdit<'_, T> {
+  pub(crate) fn insert(&mut self, at: usize, s: &str) -> usize {
+    let at = self.text.floor_char_boundary(at);
+    let len = self.text.insert_str(at, s);
+    self.set_caret(at + len);
+    len
+  }
+
+  pub(crate) fn delete_range(&mut self, rg: Range<usize>) -> Range<usize> {
+    let start = self.text.floor_char_boundary(rg.start);
+    let mut end = self.text.floor_char_boundary(rg.end).max(start);
+    if rg.start < rg.end && end < rg.end.min(self.text.len()) {
+      end += self.text.measure_bytes(end, 1);
+    }
+    let rg = self.text.del_rg_str(start..end);
+    self.set_caret(rg.start);
+    rg
+  }
+
+  pub(crate) fn replace_selection(&mut self, s: &str) -> usize {
+    let rg = self.delete_range(self.selection.cluster_rg());
+    self.insert(rg.start, s)
+  }
+
+  fn set_caret(&mut self, cluster: usize) {
+    *self.selection = Selection::splat(CaretPosition { cluster, position: None });
+  }
+}
+
 impl BaseText for CowArc<str> {
   fn len(&self) -> usize { str::len(self) }
   fn substr(&self, rg: Range<usize>) -> Substr { self.substr(rg) }
@@ -36,37 +130,14 @@ impl BaseText for CowArc<str> {
     if is_backward { legacy.cur_cursor() - byte_from } else { byte_from - legacy.cur_cursor() }
   }
 
-  fn select_token(&self, byte_from: usize) -> Range<usize> {
-    if byte_from >= self.len() {
-      return Range { start: self.len(), end: self.len() };
-    }
-    let mut legacy = GraphemeCursor::new(byte_from, self.len(), true);
-    let is_whitespace = self[byte_from..]
-      .chars()
-      .next()
-      .unwrap()
-      .is_whitespace();
-    loop {
-      let size = legacy.prev_boundary(self, 0).unwrap();
-      if size.is_none() || size.unwrap() == 0 {
-        break;
-      }
-      let pos = legacy.cur_cursor();
-      let c = self[pos..].chars().next().unwrap();
-      if is_whitespace != c.is_whitespace() || c == '\r' || c == '\n' {
-        break;
-      }
-    }
+  fn select_token(&self, byte_from: usize) -> Range<usize> { word_range_at(self, byte_from) }
 
-    let mut base = legacy.cur_cursor();
-    let it = self[legacy.cur_cursor()..].split_word_bounds();
-    for word in it {
-      if base + word.len() > byte_from {
-        return Range { start: base, end: base + word.len() };
-      }
-      base += word.len();
+  fn floor_char_boundary(&self, at: usize) -> usize {
+    let mut at = at.min(self.len());
+    while !self.is_char_boundary(at) {
+      at -= 1;
     }
-    Range { start: self.len(), end: self.len() }
+    at
   }
 }
 
@@ -92,3 +163,70 @@ impl EditText for CowArc<str> {
     rg
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn edit_across_graphemes() {
+    // The `á` is composed by `a` and a combining acute accent.
+    let mut text = EditableText::new(CowArc::from("a\u{301}b"));
+
+    assert_eq!(text.insert(3, "👍"), 4);
+    assert_eq!(&**text.text(), "a\u{301}👍b");
+    assert_eq!(text.caret().cluster, 7);
+
+    let caret = text.caret().cluster;
+    let len = text.text().measure_bytes(caret, -1);
+    assert_eq!(text.delete_range(caret - len..caret), 3..7);
+    assert_eq!(text.caret().cluster, 3);
+
+    // Delete the whole grapheme of `á`.
+    let len = text.text().measure_bytes(3, -1);
+    assert_eq!(text.delete_range(3 - len..3), 0..3);
+    assert_eq!(&**text.text(), "b");
+    assert_eq!(text.caret().cluster, 0);
+  }
+
+  #[test]
+  fn clamp_selection() {
+    let mut text = EditableText::new(CowArc::from("abc"));
+    let end = CaretPosition { cluster: 10, position: None };
+    text.set_selection(Selection { from: CaretPosition::default(), to: end });
+    assert_eq!(text.selection().cluster_rg(), 0..3);
+
+    assert_eq!(text.replace_selection("xy"), 2);
+    assert_eq!(&**text.text(), "xy");
+    assert_eq!(text.caret().cluster, 2);
+
+    // The range beyond the text is clamped.
+    assert_eq!(text.delete_range(1..10), 1..2);
+    assert_eq!(text.caret().cluster, 1);
+  }
+
+  #[test]
+  fn snap_to_char_boundary() {
+    // The `é` takes the bytes `1..3`.
+    let mut text = EditableText::new(CowArc::from("aéb"));
+
+    // Insert in the middle of `é` is snapped to its start.
+    assert_eq!(text.insert(2, "x"), 1);
+    assert_eq!(&**text.text(), "axéb");
+    assert_eq!(text.caret().cluster, 2);
+
+    // The range partially covering `é` deletes the whole char.
+    assert_eq!(text.delete_range(3..4), 2..4);
+    assert_eq!(&**text.text(), "axb");
+    assert_eq!(text.caret().cluster, 2);
+
+    // The empty range deletes nothing.
+    assert_eq!(text.delete_range(1..1), 1..1);
+    assert_eq!(&**text.text(), "axb");
+
+    let mut text = EditableText::new(CowArc::from("é"));
+    let middle = CaretPosition { cluster: 1, position: None };
+    text.set_selection(Selection::splat(middle));
+    assert_eq!(text.caret().cluster, 0);
+  }
+}
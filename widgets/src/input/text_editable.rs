@@ -4,7 +4,7 @@ use ribir_core::prelude::*;
 
 use super::{
   CaretPosition,
-  edit_text::EditText,
+  edit_text::{EditText, TextEdit},
   text_selectable::{Selection, TextSelectable},
 };
 use crate::{input::text_glyphs::VisualGlyphsHelper, prelude::*};
@@ -81,13 +81,24 @@ impl<T: EditText + 'static> BasicEditor<T> {
         },
         @ { Void }
       };
+      let blink = Stateful::new(CaretBlink::new(CaretBlink::DEFAULT_INTERVAL, Instant::now()));
+      CaretBlink::start(&blink);
       let wnd = BuildCtx::get().window();
       let u = watch!($this;).subscribe(move |_| {
+        // Keep the caret visible while typing or moving.
+        $blink.write().reset(Instant::now());
         wnd.once_layout_ready(move || {
           $caret.write().anchor = Anchor::from_point($this.caret_pos())
         })
       });
-      caret.on_disposed(move |_| u.unsubscribe())
+      let caret = caret.on_disposed(move |_| {
+        u.unsubscribe();
+        $blink.silent().stop();
+      });
+      @Providers {
+        providers: [Provider::new(blink.clone_writer())],
+        @ { caret }
+      }
     }
     .into_widget()
   }
@@ -179,20 +190,17 @@ impl<T: EditText + 'static> BasicEditor<T> {
     }
   }
 
-  fn insert(&mut self, chars: &str) -> usize {
-    let del_rg = self.del_sel();
-    let len = self.insert_str(del_rg.start, chars);
-    let pos = CaretPosition { cluster: len + del_rg.start, position: None };
-    self.host.selection = Selection::splat(pos);
-    len
-  }
+  fn insert(&mut self, chars: &str) -> usize { self.edit().replace_selection(chars) }
 
   fn del_sel(&mut self) -> Range<usize> { self.delete(self.cluster_rg()) }
 
-  fn delete(&mut self, rg: Range<usize>) -> Range<usize> {
-    let del_rg = self.del_rg_str(rg);
-    self.host.selection = Selection::splat(CaretPosition { cluster: del_rg.start, position: None });
-    del_rg
+  fn delete(&mut self, rg: Range<usize>) -> Range<usize> { self.edit().delete_range(rg) }
+
+  /// Edit the text and the selection as an
+  /// [`EditableText`](super::EditableText), so the edits keep the caret valid.
+  fn edit(&mut self) -> TextEdit<'_, T> {
+    let TextSelectable { text, selection } = &mut self.host;
+    TextEdit { text: &mut **text, selection }
   }
 
   fn is_in_pre_edit(&self) -> bool { self.pre_edit.is_some() }
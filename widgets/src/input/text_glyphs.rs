@@ -242,8 +242,12 @@ mod tests {
         ..<_>::default()
       },
       letter_space: 0.,
-      line_height: 16.,
+      line_height: 16f32.into(),
       overflow: TextOverflow::AutoWrap,
+      font_features: vec![],
+      palette_index: 0,
+      text_transform: TextTransform::None,
+      case_locale: CaseLocale::Default,
     };
     let text: CowArc<str> = "1 23 456 7890\n12345".into();
     let glyphs = store.typography(
@@ -62,26 +62,7 @@ impl<T: VisualText + Clone + 'static> Compose for TextSelectable<T> {
     fn_widget! {
       let selection = split_writer!(&mut this.selection);
 
-      @PointerSelectRegion {
-        on_custom_concrete_event: {
-          move |e: &mut PointerSelectEvent| {
-            match e.data() {
-              PointerSelectData::Move{ from, to } |
-              PointerSelectData::End { from, to } => {
-                let new_sel = $this.glyphs().map(|g| {
-                  Selection {
-                    from: g.caret_position_from_pos(*from),
-                    to: g.caret_position_from_pos(*to),
-                  }
-                });
-                if let Some(new_sel) = new_sel {
-                  *$selection.write() = new_sel;
-                }
-              },
-              _ => {}
-            }
-          }
-        },
+      @Stack {
         on_key_down: move |e| {
           let new_sel = $this.select_with_key(e);
           if let Some(new_sel) = new_sel {
@@ -100,6 +81,22 @@ impl<T: VisualText + Clone + 'static> Compose for TextSelectable<T> {
             }
           }
         },
+        on_drag_start: move |e| {
+          // The pointer down is dispatched with the latest pointer position, so
+          // anchor the selection at where the drag pressed.
+          if !e.with_shift_key() {
+            let caret = $this.glyphs().map(|g| g.caret_position_from_pos(e.start_position()));
+            if let Some(caret) = caret {
+              $selection.write().from = CaretPosition{ cluster: caret.cluster, position: None };
+            }
+          }
+        },
+        on_drag_update: move |e| {
+          let caret = $this.glyphs().map(|g| g.caret_position_from_pos(e.position()));
+          if let Some(caret) = caret {
+            $selection.write().to = caret;
+          }
+        },
         on_double_tap: move |e| {
           let caret = $this
             .glyphs()
@@ -110,26 +107,23 @@ impl<T: VisualText + Clone + 'static> Compose for TextSelectable<T> {
             $selection.write().to = CaretPosition{ cluster: rg.end, position: None };
           }
         },
-        @Stack {
-          @OnlySizedByParent {
-            @Stack {
-              @pipe!{
-                let this = $this;
-                let rcs = this.glyphs()
-                  .map(|glyphs| glyphs.select_range(&$selection.cluster_rg()))
-                  .unwrap_or_default();
-                rcs.into_iter().map(move |rc| @Container {
-                  class: TEXT_SELECTION,
-                  anchor: Anchor::from_point(rc.origin),
-                  size: rc.size,
-                })
-              }
+        @OnlySizedByParent {
+          @Stack {
+            @pipe!{
+              let this = $this;
+              let rcs = this.glyphs()
+                .map(|glyphs| glyphs.select_range(&$selection.cluster_rg()))
+                .unwrap_or_default();
+              rcs.into_iter().map(move |rc| @Container {
+                class: TEXT_SELECTION,
+                anchor: Anchor::from_point(rc.origin),
+                size: rc.size,
+              })
             }
           }
-          @part_writer!(&mut this.text)
         }
+        @part_writer!(&mut this.text)
       }
-
     }
     .into_widget()
   }
@@ -245,3 +239,51 @@ impl<T> std::ops::Deref for TextSelectable<T> {
 impl<T> std::ops::DerefMut for TextSelectable<T> {
   fn deref_mut(&mut self) -> &mut Self::Target { &mut self.text }
 }
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  #[test]
+  fn drag_select() {
+    reset_test_env!();
+
+    let text = Stateful::new(TextSelectable {
+      selection: Selection::default(),
+      text: TextGlyphs::new(CowArc::from("Hello world")),
+    });
+    let c_text = text.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! { c_text.clone_writer().into_widget() });
+    wnd.draw_frame();
+
+    let (from, to) = {
+      let text = text.read();
+      let glyphs = text.glyphs().unwrap();
+      let cursor = |cluster| glyphs.cursor(CaretPosition { cluster, position: None });
+      (cursor(2), cursor(6))
+    };
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |pos: Point| {
+      let position = (pos.x as f64, pos.y as f64 + 1.).into();
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position });
+    };
+    move_to(from);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    move_to(to);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    let text = text.read();
+    assert_eq!(text.cluster_rg(), 2..6);
+    let rects = text
+      .glyphs()
+      .unwrap()
+      .select_range(&text.cluster_rg());
+    assert_eq!(rects.len(), 1);
+  }
+}
@@ -29,6 +29,8 @@ pub use flex::*;
 pub use sized_box::*;
 pub mod expanded;
 pub use expanded::*;
+mod spacer;
+pub use spacer::*;
 mod stack;
 pub use stack::*;
 pub mod only_sized_by_parent;
@@ -37,3 +39,5 @@ mod fractionally;
 pub use fractionally::*;
 mod line;
 pub use line::*;
+mod wrap;
+pub use wrap::*;
@@ -1,6 +1,6 @@
 use ribir_core::prelude::{log::warn, *};
 
-use super::{Direction, Expanded};
+use super::{Direction, Expanded, Spacer};
 
 /// How the children should be placed along the main axis in a flex layout.
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
@@ -29,6 +29,24 @@ pub enum JustifyContent {
   SpaceEvenly,
 }
 
+/// How the flex paints the children that overflow its box, the children
+/// overflow when they are larger than the flex along the main axis and not
+/// wrapped.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum FlexOverflow {
+  /// Paint the overflowing children outside the box of the flex.
+  #[default]
+  Visible,
+  /// Clip the children by the box of the flex.
+  Clip,
+  /// Scroll the children along the main axis, the flex is wrapped by the
+  /// `scrollable` builtin field when it's declared.
+  ///
+  /// It only takes effect when declared as a value after the `direction` of
+  /// the flex, a pipe that changes to it only clips the children.
+  Scroll,
+}
+
 /// The `Flex` is a layout container that arranges its children in a
 /// one-dimensional manner. It distributes space among the children and provides
 /// alignment options in two axes.
@@ -37,7 +55,8 @@ pub enum JustifyContent {
 /// is determined by the `direction` property, while the cross axis is
 /// perpendicular to it. The `direction` property can be set to
 /// `Direction::Horizontal` or `Direction::Vertical`, and setting `reverse` to
-/// true will reverse the main axis.
+/// true will reverse the main axis. A horizontal flex is mirrored in the
+/// right-to-left [`Directionality`], so its main axis starts from the right.
 ///
 /// If the direction of the flex container is known, consider using [`Row`] or
 /// [`Column`] instead. The `wrap` property controls whether flex items should
@@ -55,7 +74,8 @@ pub enum JustifyContent {
 /// child expand to fill the available space along the main axis. The space is
 /// distributed to expanded children based on their `flex` value, with the
 /// available space being the remaining area in the main axis after allocating
-/// space for all children.
+/// space for all children. A [`Spacer`] takes up the space in the same way but
+/// without a child.
 ///
 /// Therefore, the `Expanded` widget will expand only within a fixed-size
 /// container.
@@ -85,6 +105,25 @@ pub struct Flex {
   /// Define item between gap in cross axis
   #[declare(default)]
   pub line_gap: f32,
+  /// How to paint the children overflowing the flex.
+  #[declare(default, custom)]
+  pub overflow: FlexOverflow,
+}
+
+pub trait FlexDeclarerExtend {
+  fn overflow<const M: usize>(self, overflow: impl DeclareInto<FlexOverflow, M>) -> Self;
+}
+
+impl FlexDeclarerExtend for FatObj<FlexDeclarer> {
+  fn overflow<const M: usize>(mut self, overflow: impl DeclareInto<FlexOverflow, M>) -> Self {
+    let overflow = overflow.declare_into();
+    if matches!(overflow, DeclareInit::Value(FlexOverflow::Scroll)) {
+      let vertical = matches!(self.direction, Some(DeclareInit::Value(Direction::Vertical)));
+      self = self.scrollable(if vertical { Scrollable::Y } else { Scrollable::X });
+    }
+    self.overflow = Some(overflow);
+    self
+  }
 }
 
 /// A type help to declare flex widget as horizontal.
@@ -125,6 +164,7 @@ impl Render for Flex {
     let mut layouter = FlexLayouter {
       reverse: self.reverse,
       dir: self.direction,
+      mirror: self.direction.is_horizontal() && Directionality::of(ctx).is_rtl(),
       align_items: self.align_items,
       justify_content: self.justify_content,
       wrap: self.wrap,
@@ -137,8 +177,39 @@ impl Render for Flex {
     layouter.layout(clamp, ctx)
   }
 
-  #[inline]
-  fn paint(&self, _: &mut PaintingCtx) {}
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    if self.overflow != FlexOverflow::Visible {
+      let rect = Rect::from_size(ctx.box_size().unwrap());
+      ctx.box_painter().clip(Path::rect(&rect).into());
+    }
+  }
+
+  fn intrinsic_width(&self, kind: IntrinsicSize, _: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self.intrinsic_extent(Direction::Horizontal, kind, ctx)
+  }
+
+  fn intrinsic_height(&self, kind: IntrinsicSize, _: Option<f32>, ctx: &mut LayoutCtx) -> f32 {
+    self.intrinsic_extent(Direction::Vertical, kind, ctx)
+  }
+}
+
+impl Flex {
+  fn intrinsic_extent(&self, axis: Direction, kind: IntrinsicSize, ctx: &mut LayoutCtx) -> f32 {
+    let (ctx, children) = ctx.split_children();
+    let mut extents = children.map(|c| match axis {
+      Direction::Horizontal => ctx.child_intrinsic_width(c, kind, None),
+      Direction::Vertical => ctx.child_intrinsic_height(c, kind, None),
+    });
+
+    // A wrapped flex can place every child on its own line, so its minimum main
+    // extent is the largest child, the same as its cross extent.
+    if axis != self.direction || (self.wrap && kind == IntrinsicSize::MinContent) {
+      extents.fold(0., f32::max)
+    } else {
+      let first = extents.next().unwrap_or_default();
+      extents.fold(first, |acc, e| acc + self.item_gap + e)
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -173,6 +244,9 @@ impl std::ops::Sub for FlexSize {
 struct FlexLayouter {
   reverse: bool,
   dir: Direction,
+  /// Place the children from the end of the main axis, for the right-to-left
+  /// horizontal flex.
+  mirror: bool,
   align_items: Align,
   justify_content: JustifyContent,
   wrap: bool,
@@ -260,6 +334,11 @@ impl FlexLayouter {
       let flex = ctx
         .query_of_widget::<Expanded>(c)
         .map(|expanded| expanded.flex)
+        .or_else(|| {
+          ctx
+            .query_of_widget::<Spacer>(c)
+            .map(|spacer| spacer.flex)
+        })
         .filter(|f| f.is_normal() && *f > 0.)
         .inspect(|_| {
           self.current_line.has_flex = true;
@@ -297,7 +376,7 @@ impl FlexLayouter {
   }
 
   fn update_children_position(&mut self, bound: FlexSize, ctx: &mut LayoutCtx) {
-    let Self { reverse, dir, align_items, justify_content, lines, .. } = self;
+    let Self { reverse, dir, mirror, align_items, justify_content, lines, .. } = self;
 
     let cross_size = lines.iter().map(|l| l.cross_line_height).sum();
     // cross gap don't use calc offset
@@ -315,7 +394,7 @@ impl FlexLayouter {
               align_items.align_value(item.size.cross, line.cross_line_height);
 
             item.pos.cross = cross + item_cross_offset + self.cross_axis_gap;
-            item.pos.main = main;
+            item.pos.main = if *mirror { bound.main - main - item.size.main } else { main };
             main = main + item.size.main + step;
           });
           cross += line.cross_line_height + self.cross_axis_gap;
@@ -435,6 +514,8 @@ impl JustifyContent {
 
 #[cfg(test)]
 mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
   use ribir_core::test_helper::*;
   use ribir_dev_helper::*;
 
@@ -453,6 +534,134 @@ mod tests {
     LayoutCase::default().with_size(Size::new(100., 20.))
   );
 
+  #[derive(SingleChild)]
+  struct IntrinsicProbe(Rc<RefCell<(f32, f32)>>);
+
+  impl Render for IntrinsicProbe {
+    fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+      let child = ctx.assert_single_child();
+      let min = ctx.child_intrinsic_width(child, IntrinsicSize::MinContent, None);
+      let max = ctx.child_intrinsic_width(child, IntrinsicSize::MaxContent, None);
+      *self.0.borrow_mut() = (min, max);
+      ctx.perform_child_layout(child, clamp)
+    }
+  }
+
+  #[test]
+  fn intrinsic_width() {
+    reset_test_env!();
+
+    let widths = Rc::new(RefCell::new((0., 0.)));
+    let c_widths = widths.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let probe = IntrinsicProbe(c_widths.clone());
+      @$probe {
+        @Flex {
+          item_gap: 5.,
+          @{ (0..3).map(|_| SizedBox { size: Size::new(10., 20.) }) }
+        }
+      }
+    });
+    wnd.draw_frame();
+    assert_eq!(*widths.borrow(), (40., 40.));
+
+    let widths = Rc::new(RefCell::new((0., 0.)));
+    let c_widths = widths.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let probe = IntrinsicProbe(c_widths.clone());
+      @$probe {
+        @Flex {
+          wrap: true,
+          item_gap: 5.,
+          @{ (0..3).map(|_| SizedBox { size: Size::new(10., 20.) }) }
+        }
+      }
+    });
+    wnd.draw_frame();
+    assert_eq!(*widths.borrow(), (10., 40.));
+  }
+
+  fn painted_colors(overflow: FlexOverflow) -> Vec<Color> {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @SizedBox {
+          size: Size::new(100., 20.),
+          @Row {
+            overflow: overflow,
+            @Container { size: Size::new(60., 20.), background: Color::RED }
+            @Container { size: Size::new(60., 20.), background: Color::GREEN }
+            @Container { size: Size::new(60., 20.), background: Color::BLUE }
+          }
+        }
+      },
+      Size::new(200., 20.),
+    );
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Paint { brush: CommandBrush::Color(c), .. },
+          ..
+        }) => Some(*c),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn overflow_visible() {
+    assert_eq!(painted_colors(FlexOverflow::Visible), [Color::RED, Color::GREEN, Color::BLUE]);
+  }
+
+  #[test]
+  fn overflow_clip() {
+    // The last child is totally outside the row, so it's not painted.
+    assert_eq!(painted_colors(FlexOverflow::Clip), [Color::RED, Color::GREEN]);
+  }
+
+  #[test]
+  fn overflow_scroll() {
+    use winit::event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent};
+
+    assert_eq!(painted_colors(FlexOverflow::Scroll), [Color::RED, Color::GREEN]);
+
+    reset_test_env!();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @SizedBox {
+          size: Size::new(100., 20.),
+          @Row {
+            overflow: FlexOverflow::Scroll,
+            @Container { size: Size::new(60., 20.), background: Color::RED }
+            @Container { size: Size::new(60., 20.), background: Color::GREEN }
+            @Container { size: Size::new(60., 20.), background: Color::BLUE }
+          }
+        }
+      },
+      Size::new(200., 20.),
+    );
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((-100., 0.).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+
+    // The row scrolls to its end, the first child is out of the view.
+    let row = wnd.layout_info_by_path(&[0, 0, 0]).unwrap();
+    assert_eq!(row.pos, Point::new(-80., 0.));
+  }
+
   widget_layout_test!(
     vertical_line,
     WidgetTester::new(fn_widget! {
@@ -530,6 +739,37 @@ mod tests {
     LayoutCase::new(&[0, 2]).with_rect(ribir_geom::rect(0., 0., 30., 20.))
   );
 
+  widget_layout_test!(
+    rtl_row,
+    WidgetTester::new(fn_widget! {
+      @Row {
+        providers: [Provider::new(Directionality::Rtl)],
+        item_gap: 15.,
+        @SizedBox { size: Size::new(120., 20.) }
+        @SizedBox { size: Size::new(80., 20.) }
+        @SizedBox { size: Size::new(30., 20.) }
+      }
+    })
+    .with_wnd_size(Size::new(500., 500.)),
+    LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(140., 0., 120., 20.)),
+    LayoutCase::new(&[0, 1]).with_rect(ribir_geom::rect(45., 0., 80., 20.)),
+    LayoutCase::new(&[0, 2]).with_rect(ribir_geom::rect(0., 0., 30., 20.))
+  );
+
+  widget_layout_test!(
+    rtl_column_not_mirrored,
+    WidgetTester::new(fn_widget! {
+      @Column {
+        providers: [Provider::new(Directionality::Rtl)],
+        @SizedBox { size: Size::new(120., 20.) }
+        @SizedBox { size: Size::new(80., 20.) }
+      }
+    })
+    .with_wnd_size(Size::new(500., 500.)),
+    LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(0., 0., 120., 20.)),
+    LayoutCase::new(&[0, 1]).with_rect(ribir_geom::rect(0., 20., 80., 20.))
+  );
+
   widget_layout_test!(
     main_axis_expand,
     WidgetTester::new(fn_widget! {
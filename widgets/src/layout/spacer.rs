@@ -0,0 +1,59 @@
+use ribir_core::prelude::*;
+
+/// A widget that takes up the free space of the main axis of `Flex` without
+/// rendering anything. If multiple spacers or `Expanded` are in the same line,
+/// the free space is divided among them according to the flex factor.
+///
+/// It works exactly like an `Expanded` with an empty child.
+// `Spacer` should not support `FatObj` for the same reason as `Expanded`.
+#[simple_declare]
+#[derive(Clone, PartialEq)]
+pub struct Spacer {
+  #[declare(default = 1.)]
+  pub flex: f32,
+}
+
+impl Default for Spacer {
+  fn default() -> Self { Self { flex: 1. } }
+}
+
+impl Compose for Spacer {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    let mut child = Void.into_widget();
+    let data: Box<dyn Query> = match this.try_into_value() {
+      Ok(this) => Box::new(Queryable(this)),
+      Err(this) => {
+        child = child.dirty_on(this.raw_modifies(), DirtyPhase::Layout);
+        Box::new(this)
+      }
+    };
+
+    child.attach_data(data)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::prelude::*;
+
+  widget_layout_test!(
+    spacer_push_to_ends,
+    WidgetTester::new(fn_widget! {
+      let size = Size::new(100., 50.);
+      @Row {
+        @SizedBox { size }
+        @Spacer {}
+        @SizedBox { size }
+      }
+    })
+    .with_wnd_size(Size::new(500., 500.)),
+    LayoutCase::default().with_size(Size::new(500., 50.)),
+    LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(0., 0., 100., 50.)),
+    LayoutCase::new(&[0, 1]).with_rect(ribir_geom::rect(100., 0., 300., 0.)),
+    LayoutCase::new(&[0, 2]).with_rect(ribir_geom::rect(400., 0., 100., 50.))
+  );
+}
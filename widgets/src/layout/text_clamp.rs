@@ -23,7 +23,10 @@ impl WrapRender for TextClamp {
   fn perform_layout(&self, mut clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
     let text_style = Provider::of::<TextStyle>(ctx).unwrap();
     if let Some(rows) = self.rows {
-      let height = rows * text_style.line_height;
+      let height = rows
+        * text_style
+          .line_height
+          .to_pixel(text_style.font_size);
       clamp = clamp.with_fixed_height(height.clamp(clamp.min.height, clamp.max.height));
     }
     if let Some(cols) = self.cols {
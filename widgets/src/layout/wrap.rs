@@ -0,0 +1,195 @@
+use ribir_core::prelude::*;
+
+use super::{Direction, JustifyContent};
+
+/// The `Wrap` is a flow layout that places its children one after another
+/// along the main axis, and starts a new run when the next child exceeds the
+/// main extent. The runs are stacked along the cross axis.
+///
+/// Unlike a wrapped [`Flex`](super::Flex), the children of a `Wrap` are never
+/// expanded, and the runs are placed along the cross axis by `run_alignment`.
+/// It's suitable for tag clouds and toolbars.
+///
+/// # Example
+///
+/// ```
+/// use ribir::prelude::*;
+///
+/// let _tags = fn_widget! {
+///   @Wrap {
+///     main_spacing: 4.,
+///     cross_spacing: 4.,
+///     @Text { text: "rust" }
+///     @Text { text: "gui" }
+///     @Text { text: "declarative" }
+///   }
+/// };
+/// ```
+#[derive(Default, MultiChild, Declare, Clone, PartialEq)]
+pub struct Wrap {
+  /// The direction of the main axis that the children are placed in a run.
+  #[declare(default)]
+  pub direction: Direction,
+  /// The gap between two children in a run.
+  #[declare(default)]
+  pub main_spacing: f32,
+  /// The gap between two runs.
+  #[declare(default)]
+  pub cross_spacing: f32,
+  /// How the runs should be placed along the cross axis.
+  #[declare(default)]
+  pub run_alignment: JustifyContent,
+}
+
+#[derive(Default)]
+struct Run {
+  main: f32,
+  cross: f32,
+  /// The child and its offset in the run along the main axis.
+  items: Vec<(WidgetId, f32)>,
+}
+
+impl Render for Wrap {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let Self { direction: dir, main_spacing, cross_spacing, run_alignment } = *self;
+    let (max_main, max_cross) = split_size(clamp.max, dir);
+    let child_clamp = clamp.loose();
+
+    let mut runs: Vec<Run> = vec![];
+    let mut run = Run::default();
+    let (ctx, children) = ctx.split_children();
+    for c in children {
+      let (main, cross) = split_size(ctx.perform_child_layout(c, child_clamp), dir);
+      if !run.items.is_empty() {
+        if run.main + main_spacing + main > max_main {
+          runs.push(std::mem::take(&mut run));
+        } else {
+          run.main += main_spacing;
+        }
+      }
+      run.items.push((c, run.main));
+      run.main += main;
+      run.cross = run.cross.max(cross);
+    }
+    if !run.items.is_empty() {
+      runs.push(run);
+    }
+
+    let main = runs.iter().fold(0f32, |max, r| max.max(r.main));
+    let content_cross = runs
+      .iter()
+      .fold(-cross_spacing, |sum, r| sum + r.cross + cross_spacing)
+      .max(0.);
+    let cross = if run_alignment != JustifyContent::Start && max_cross.is_finite() {
+      max_cross
+    } else {
+      content_cross
+    };
+    let size = clamp.clamp(join_size(main, cross, dir));
+
+    let (_, cross) = split_size(size, dir);
+    let (mut offset, step) = run_place_args(cross - content_cross, runs.len(), run_alignment);
+    for r in runs {
+      for (c, main) in r.items {
+        let (x, y) = join_size(main, offset, dir).to_tuple();
+        ctx.update_position(c, Point::new(x, y));
+      }
+      offset += r.cross + cross_spacing + step;
+    }
+
+    size
+  }
+}
+
+/// Return the offset of the first run and the extra space between two runs.
+fn run_place_args(free: f32, runs: usize, align: JustifyContent) -> (f32, f32) {
+  let free = free.max(0.);
+  let cnt = runs as f32;
+  match align {
+    JustifyContent::Start => (0., 0.),
+    JustifyContent::Center => (free / 2., 0.),
+    JustifyContent::End => (free, 0.),
+    JustifyContent::SpaceBetween if runs > 1 => (0., free / (cnt - 1.)),
+    JustifyContent::SpaceBetween => (0., 0.),
+    JustifyContent::SpaceAround => {
+      let step = free / cnt;
+      (step / 2., step)
+    }
+    JustifyContent::SpaceEvenly => {
+      let step = free / (cnt + 1.);
+      (step, step)
+    }
+  }
+}
+
+fn split_size(size: Size, dir: Direction) -> (f32, f32) {
+  match dir {
+    Direction::Horizontal => (size.width, size.height),
+    Direction::Vertical => (size.height, size.width),
+  }
+}
+
+fn join_size(main: f32, cross: f32, dir: Direction) -> Size {
+  match dir {
+    Direction::Horizontal => Size::new(main, cross),
+    Direction::Vertical => Size::new(cross, main),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::prelude::*;
+
+  widget_layout_test!(
+    wrap_runs_with_spacing,
+    WidgetTester::new(fn_widget! {
+      @Wrap {
+        main_spacing: 10.,
+        cross_spacing: 5.,
+        @{ (0..5).map(|_| SizedBox { size: Size::new(40., 20.) }) }
+      }
+    })
+    .with_wnd_size(Size::new(100., 100.)),
+    LayoutCase::default().with_size(Size::new(90., 70.)),
+    LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(0., 0., 40., 20.)),
+    LayoutCase::new(&[0, 1]).with_rect(ribir_geom::rect(50., 0., 40., 20.)),
+    LayoutCase::new(&[0, 2]).with_rect(ribir_geom::rect(0., 25., 40., 20.)),
+    LayoutCase::new(&[0, 3]).with_rect(ribir_geom::rect(50., 25., 40., 20.)),
+    LayoutCase::new(&[0, 4]).with_rect(ribir_geom::rect(0., 50., 40., 20.))
+  );
+
+  widget_layout_test!(
+    wrap_center_runs,
+    WidgetTester::new(fn_widget! {
+      @Wrap {
+        cross_spacing: 10.,
+        run_alignment: JustifyContent::Center,
+        @{ (0..3).map(|_| SizedBox { size: Size::new(60., 20.) }) }
+      }
+    })
+    .with_wnd_size(Size::new(100., 100.)),
+    LayoutCase::default().with_size(Size::new(60., 100.)),
+    LayoutCase::new(&[0, 0]).with_y(10.),
+    LayoutCase::new(&[0, 1]).with_y(40.),
+    LayoutCase::new(&[0, 2]).with_y(70.)
+  );
+
+  widget_layout_test!(
+    vertical_wrap,
+    WidgetTester::new(fn_widget! {
+      @Wrap {
+        direction: Direction::Vertical,
+        main_spacing: 10.,
+        @{ (0..3).map(|_| SizedBox { size: Size::new(20., 40.) }) }
+      }
+    })
+    .with_wnd_size(Size::new(100., 100.)),
+    LayoutCase::default().with_size(Size::new(40., 90.)),
+    LayoutCase::new(&[0, 1]).with_pos(Point::new(0., 50.)),
+    LayoutCase::new(&[0, 2]).with_pos(Point::new(20., 0.))
+  );
+}
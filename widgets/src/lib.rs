@@ -1,4 +1,5 @@
 pub mod avatar;
+pub mod box_shadow;
 pub mod buttons;
 pub mod checkbox;
 pub mod common_widget;
@@ -22,8 +23,8 @@ pub mod text_field;
 pub mod transform_box;
 pub mod prelude {
   pub use super::{
-    avatar::*, buttons::*, checkbox::*, common_widget::*, divider::*, grid_view::*, icon::*,
-    input::*, label::*, layout::*, link::*, lists::*, path::*, progress::*, radio::*, scrollbar::*,
-    select_region::*, slider::*, tabs::*, text_field::*, transform_box::*,
+    avatar::*, box_shadow::*, buttons::*, checkbox::*, common_widget::*, divider::*, grid_view::*,
+    icon::*, input::*, label::*, layout::*, link::*, lists::*, path::*, progress::*, radio::*,
+    scrollbar::*, select_region::*, slider::*, tabs::*, text_field::*, transform_box::*,
   };
 }
@@ -294,7 +294,9 @@ impl<'c> ComposeChild<'c> for ListItem {
                   @{ supporting.map(|supporting|  {
                     @ConstrainedBox {
                       clamp: {
-                        let line_height = supporting_style.line_height;
+                        let line_height = supporting_style
+                          .line_height
+                          .to_pixel(supporting_style.font_size);
                         pipe!{
                           let text_height = line_height * $this.line_number as f32;
                           BoxClamp::fixed_height(text_height)
@@ -343,7 +345,7 @@ impl CustomStyle for ListItemStyle {
     let typography = TypographyTheme::of(ctx);
     let palette = Palette::of(ctx);
     ListItemStyle {
-      padding_style: Some(EdgeInsets { left: 0., right: 24., bottom: 8., top: 8. }),
+      padding_style: Some(EdgeInsets::new(8., 24., 8., 0.)),
       item_align: |num| {
         if num >= 2 { Align::Start } else { Align::Center }
       },
@@ -74,6 +74,16 @@ impl Slider {
     v
   }
 
+  /// Move the value by `steps` steps, a step is a division if the slider is
+  /// divided, otherwise one percent of the range.
+  fn nudge(&mut self, steps: f32) {
+    let step = match self.divisions {
+      Some(divisions) if divisions > 0 => 1. / divisions as f32,
+      _ => 0.01,
+    };
+    self.set_to(self.ratio() + steps * step);
+  }
+
   fn validate(&mut self) {
     if self.max < self.min {
       swap(&mut self.max, &mut self.min);
@@ -112,15 +122,23 @@ impl Compose for Slider {
       });
 
       let mut row = @Row { align_items: Align::Center };
-      let drag_info = Stateful::new(None);
       @ Stack {
         class: SLIDER_CONTAINER,
+        on_key_down: move |e| match e.key() {
+          VirtualKey::Named(NamedKey::ArrowLeft) => $this.write().nudge(-1.),
+          VirtualKey::Named(NamedKey::ArrowRight) => $this.write().nudge(1.),
+          _ => {}
+        },
         @ $row {
           v_align: VAlign::Center,
           on_tap: move |e| {
             let width = $row.layout_size().width;
             $this.write().set_to(e.position().x / width);
           },
+          on_drag_update: move |e| {
+            let width = $row.layout_size().width;
+            $this.write().set_to(e.position().x / width);
+          },
           on_disposed: move |_| u.unsubscribe(),
           @Expanded {
             flex: pipe!($this.ratio()),
@@ -129,19 +147,6 @@ impl Compose for Slider {
           @Void {
             class: SLIDER_INDICATOR ,
             on_tap: move |e| e.stop_propagation(),
-            on_pointer_down: move |e| {
-              if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
-                *$drag_info.write() = Some((handle, e.global_pos().x, $this.ratio()));
-              }
-            },
-            on_pointer_move: move|e| if let Some((_, pos, ratio)) = $drag_info.as_ref() {
-              let width = $row.layout_size().width;
-              let val = ratio + (e.global_pos().x - pos) / width;
-              $this.write().set_to(val);
-            },
-            on_pointer_up: move |_| {
-              $drag_info.write().take();
-            },
             tooltips: pipe!($this.value).map(move |v| {
               let precision = precision($this.min, $this.max);
               format!("{:.1$}", v, precision)
@@ -377,11 +382,88 @@ fn stop_indicator_track(cnt: usize, actives: Range<usize>, filter: Vec<usize>) -
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, test_helper::*};
   use ribir_dev_helper::*;
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
   use super::*;
 
+  fn slider_env() -> (TestWindow, Watcher<Reader<f32>>) {
+    AppCtx::set_app_theme(ribir_material::purple::light());
+    let (value, w_value) = split_value(0.);
+    let w = fn_widget! {
+      let slider = @Slider { value: 0. };
+      watch!($slider.value).subscribe(move |v| *$w_value.write() = v);
+      @SizedBox {
+        size: Size::new(200., 44.),
+        @ { slider }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 44.));
+    wnd.draw_frame();
+    (wnd, value)
+  }
+
+  fn cursor_move_to(wnd: &TestWindow, x: f64) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 22.).into() });
+  }
+
+  #[test]
+  fn drag_to_max() {
+    reset_test_env!();
+    let (mut wnd, value) = slider_env();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    // Press on the indicator.
+    cursor_move_to(&wnd, 8.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    cursor_move_to(&wnd, 100.);
+    cursor_move_to(&wnd, 500.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    assert_eq!(*value.read(), 100.);
+  }
+
+  #[test]
+  fn tap_track_jump() {
+    reset_test_env!();
+    let (mut wnd, value) = slider_env();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    cursor_move_to(&wnd, 100.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    assert_eq!(*value.read(), 50.);
+  }
+
+  #[test]
+  fn arrow_key_nudge() {
+    reset_test_env!();
+    let (mut wnd, value) = slider_env();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    // Focus the slider.
+    cursor_move_to(&wnd, 100.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    wnd.processes_keyboard_event(
+      PhysicalKey::Code(KeyCode::ArrowRight),
+      VirtualKey::Named(NamedKey::ArrowRight),
+      false,
+      KeyLocation::Standard,
+      ElementState::Pressed,
+    );
+    wnd.draw_frame();
+    assert!((*value.read() - 51.).abs() < 1e-3);
+  }
+
   widget_image_tests!(
     slider_widgets,
     WidgetTester::new(self::column! {
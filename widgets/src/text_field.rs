@@ -188,9 +188,9 @@ impl TextFieldThemeSuit {
 
     let mut themes = HashMap::new();
 
-    let input_expand_padding = EdgeInsets { left: 16., right: 16., bottom: 16., top: 16. };
+    let input_expand_padding = EdgeInsets::all(16.);
 
-    let input_collapse_padding = EdgeInsets { left: 16., right: 16., bottom: 8., top: 8. };
+    let input_collapse_padding = EdgeInsets::symmetrical(8., 16.);
 
     themes.insert(TextFieldState::Enabled, TextFieldTheme {
       text_brush: palette.on_surface().into(),